@@ -306,3 +306,125 @@ impl std::fmt::Display for Activity {
         }
     }
 }
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Activity {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("Activity", 18)?;
+        state.serialize_field("kind", &self.kind())?;
+        state.serialize_field("application_id", &self.application_id())?;
+        state.serialize_field("name", &self.name())?;
+        state.serialize_field("state", &self.state())?;
+        state.serialize_field("details", &self.details())?;
+        state.serialize_field("start_time", &self.start_time())?;
+        state.serialize_field("end_time", &self.end_time())?;
+        state.serialize_field("large_image_key", &self.large_image_key())?;
+        state.serialize_field("large_image_tooltip", &self.large_image_tooltip())?;
+        state.serialize_field("small_image_key", &self.small_image_key())?;
+        state.serialize_field("small_image_tooltip", &self.small_image_tooltip())?;
+        state.serialize_field("party_id", &self.party_id())?;
+        state.serialize_field("party_amount", &self.party_amount())?;
+        state.serialize_field("party_capacity", &self.party_capacity())?;
+        state.serialize_field("instance", &self.instance())?;
+        state.serialize_field("match_secret", &self.match_secret())?;
+        state.serialize_field("join_secret", &self.join_secret())?;
+        state.serialize_field("spectate_secret", &self.spectate_secret())?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Activity {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use crate::utils::deserialize_charbuf;
+
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "Activity")]
+        struct Fields {
+            kind: ActivityKind,
+            application_id: ClientID,
+            name: String,
+            state: String,
+            details: String,
+            start_time: UnixTimestamp,
+            end_time: UnixTimestamp,
+            large_image_key: String,
+            large_image_tooltip: String,
+            small_image_key: String,
+            small_image_tooltip: String,
+            party_id: String,
+            party_amount: u32,
+            party_capacity: u32,
+            instance: bool,
+            match_secret: String,
+            join_secret: String,
+            spectate_secret: String,
+        }
+
+        let fields = Fields::deserialize(deserializer)?;
+        let mut raw = sys::DiscordActivity {
+            type_: fields.kind.into(),
+            application_id: fields.application_id,
+            instance: fields.instance,
+            ..sys::DiscordActivity::default()
+        };
+
+        deserialize_charbuf(&mut raw.name, &fields.name)?;
+        deserialize_charbuf(&mut raw.state, &fields.state)?;
+        deserialize_charbuf(&mut raw.details, &fields.details)?;
+        raw.timestamps.start = fields.start_time;
+        raw.timestamps.end = fields.end_time;
+        deserialize_charbuf(&mut raw.assets.large_image, &fields.large_image_key)?;
+        deserialize_charbuf(&mut raw.assets.large_text, &fields.large_image_tooltip)?;
+        deserialize_charbuf(&mut raw.assets.small_image, &fields.small_image_key)?;
+        deserialize_charbuf(&mut raw.assets.small_text, &fields.small_image_tooltip)?;
+        deserialize_charbuf(&mut raw.party.id, &fields.party_id)?;
+        raw.party.size.current_size = fields
+            .party_amount
+            .try_into()
+            .map_err(serde::de::Error::custom)?;
+        raw.party.size.max_size = fields
+            .party_capacity
+            .try_into()
+            .map_err(serde::de::Error::custom)?;
+        deserialize_charbuf(&mut raw.secrets.match_, &fields.match_secret)?;
+        deserialize_charbuf(&mut raw.secrets.join, &fields.join_secret)?;
+        deserialize_charbuf(&mut raw.secrets.spectate, &fields.spectate_secret)?;
+
+        Ok(Self(raw))
+    }
+}
+
+#[cfg(all(test, feature = "bincode"))]
+mod tests {
+    use crate::{sys, utils::write_charbuf, Activity, Bincode, Decode, Encode};
+
+    #[test]
+    fn serde() {
+        let mut activity = Activity::empty();
+        activity
+            .with_state("state")
+            .with_details("details")
+            .with_start_time(1)
+            .with_end_time(2)
+            .with_large_image_key("large key")
+            .with_large_image_tooltip("large tooltip")
+            .with_small_image_key("small key")
+            .with_small_image_tooltip("small tooltip")
+            .with_party_id("party")
+            .with_party_amount(3)
+            .with_party_capacity(4)
+            .with_instance(true)
+            .with_match_secret("match")
+            .with_join_secret("join")
+            .with_spectate_secret("spectate");
+        activity.0.type_ = sys::DiscordActivityType_Listening;
+        activity.0.application_id = 5;
+        write_charbuf(&mut activity.0.name, "name");
+
+        let bytes = Bincode(activity.clone()).encode().unwrap();
+        assert_eq!(Bincode::decode(&bytes), Ok(Bincode(activity)));
+    }
+}
@@ -0,0 +1,59 @@
+use crate::{Decode, Encode, Error, Result};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Encodes a `Serialize` and `Deserialize` type with [`bincode`](https://docs.rs/bincode)
+///
+/// Requires the `bincode` feature.
+///
+/// ```rust
+/// # use discord_game_sdk::*;
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// struct Input {
+///     x: f32,
+///     y: f32,
+/// }
+///
+/// # fn example(discord: Discord<'_, ()>, peer_id: NetworkPeerID) -> Result<()> {
+/// let inputs = TypedChannel::<Bincode<Input>>::new(0, Reliability::Unreliable);
+/// inputs.send(&discord, peer_id, &Bincode(Input { x: 0.0, y: 1.0 }))?;
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Bincode<T>(pub T);
+
+impl<T: Serialize> Encode for Bincode<T> {
+    fn encode(&self) -> Result<Vec<u8>> {
+        bincode_rs::serialize(&self.0).map_err(|error| {
+            log::warn!("failed to encode message: {}", error);
+            Error::InvalidPayload
+        })
+    }
+}
+
+impl<T: DeserializeOwned> Decode for Bincode<T> {
+    fn decode(bytes: &[u8]) -> Result<Self> {
+        bincode_rs::deserialize(bytes)
+            .map(Bincode)
+            .map_err(|error| {
+                log::warn!("failed to decode message: {}", error);
+                Error::InvalidPayload
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Bincode, Decode, Encode, Error};
+
+    #[test]
+    fn round_trip() {
+        let value = Bincode((7u8, "seven".to_string()));
+        let bytes = value.encode().unwrap();
+
+        assert_eq!(Bincode::decode(&bytes), Ok(value));
+        assert_eq!(
+            Bincode::<(u8, String)>::decode(&bytes[..3]),
+            Err(Error::InvalidPayload)
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use crate::Result;
+use std::{
+    cell::RefCell,
+    fmt,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+/// Result of a method of the [Futures](struct.Discord.html#futures) section
+///
+/// Resolves when the SDK fires the callback of the method, during
+/// [`run_callbacks`](struct.Discord.html#method.run_callbacks).
+/// Resolves to `Err(TransactionAborted)` if the [`Discord`](struct.Discord.html) instance is
+/// dropped first.
+///
+/// Requires the `futures` feature.
+pub struct CallbackFuture<T>(Rc<RefCell<Slot<T>>>);
+
+struct Slot<T> {
+    result: Option<Result<T>>,
+    waker: Option<Waker>,
+}
+
+impl<T> CallbackFuture<T> {
+    /// A pending future and the function that resolves it
+    pub(crate) fn new() -> (Self, impl FnOnce(Result<T>)) {
+        let slot = Rc::new(RefCell::new(Slot {
+            result: None,
+            waker: None,
+        }));
+
+        let resolve = {
+            let slot = slot.clone();
+
+            move |result| {
+                let waker = {
+                    let mut slot = slot.borrow_mut();
+                    slot.result = Some(result);
+                    slot.waker.take()
+                };
+
+                if let Some(waker) = waker {
+                    waker.wake();
+                }
+            }
+        };
+
+        (Self(slot), resolve)
+    }
+}
+
+impl<T> Future for CallbackFuture<T> {
+    type Output = Result<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut slot = self.0.borrow_mut();
+
+        match slot.result.take() {
+            Some(result) => Poll::Ready(result),
+
+            None => {
+                slot.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> fmt::Debug for CallbackFuture<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("CallbackFuture")
+            .field("ready", &self.0.borrow().result.is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Discord, LobbyTransaction};
+    use std::{
+        future::Future,
+        pin::Pin,
+        sync::atomic::{AtomicUsize, Ordering},
+        task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+    };
+
+    static WAKES: AtomicUsize = AtomicUsize::new(0);
+
+    fn waker() -> Waker {
+        const VTABLE: RawWakerVTable = RawWakerVTable::new(
+            |_| RawWaker::new(std::ptr::null(), &VTABLE),
+            |_| {
+                let _ = WAKES.fetch_add(1, Ordering::SeqCst);
+            },
+            |_| {
+                let _ = WAKES.fetch_add(1, Ordering::SeqCst);
+            },
+            |_| {},
+        );
+
+        unsafe { Waker::from_raw(RawWaker::new(std::ptr::null(), &VTABLE)) }
+    }
+
+    #[test]
+    fn resolves_during_run_callbacks() {
+        let mut discord = Discord::<()>::mock();
+        let waker = waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut future = discord.create_lobby_async(&LobbyTransaction::new());
+        assert!(Pin::new(&mut future).poll(&mut cx).is_pending());
+
+        discord.run_callbacks().unwrap();
+        assert_eq!(WAKES.load(Ordering::SeqCst), 1);
+
+        match Pin::new(&mut future).poll(&mut cx) {
+            Poll::Ready(Ok(lobby)) => assert_eq!(lobby.owner_id(), 1),
+            other => panic!("{:?}", other.map(|res| res.map(|_| ()))),
+        }
+
+        let aborted = discord.clear_activity_async();
+        drop(discord);
+        match Pin::new(&mut { aborted }).poll(&mut cx) {
+            Poll::Ready(Err(crate::Error::TransactionAborted)) => {}
+            other => panic!("{:?}", other),
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use crate::{Error, Reliability, Result};
+use std::collections::HashMap;
+
+/// Traffic counters of one networking channel
+///
+/// Part of a [`NetworkStats`](struct.NetworkStats.html) snapshot.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ChannelStats {
+    pub(crate) reliability: Option<Reliability>,
+    pub(crate) messages_sent: u64,
+    pub(crate) bytes_sent: u64,
+    pub(crate) messages_received: u64,
+    pub(crate) bytes_received: u64,
+    pub(crate) send_errors: HashMap<Error, u64>,
+}
+
+impl ChannelStats {
+    /// The reliability the channel was last opened with
+    ///
+    /// `None` if the channel was opened before the counters were enabled.
+    pub fn reliability(&self) -> Option<Reliability> {
+        self.reliability
+    }
+
+    /// Amount of messages sent successfully
+    pub fn messages_sent(&self) -> u64 {
+        self.messages_sent
+    }
+
+    /// Amount of bytes sent successfully
+    pub fn bytes_sent(&self) -> u64 {
+        self.bytes_sent
+    }
+
+    /// Amount of messages received
+    pub fn messages_received(&self) -> u64 {
+        self.messages_received
+    }
+
+    /// Amount of bytes received
+    pub fn bytes_received(&self) -> u64 {
+        self.bytes_received
+    }
+
+    /// Amount of failed sends, by error
+    pub fn send_errors(&self) -> &HashMap<Error, u64> {
+        &self.send_errors
+    }
+
+    /// Amount of failed sends, for every error
+    pub fn send_error_count(&self) -> u64 {
+        self.send_errors.values().sum()
+    }
+
+    pub(crate) fn sent(&mut self, len: usize, res: &Result<()>) {
+        match *res {
+            Ok(()) => {
+                self.messages_sent += 1;
+                self.bytes_sent += len as u64;
+            }
+
+            Err(error) => *self.send_errors.entry(error).or_insert(0) += 1,
+        }
+    }
+
+    pub(crate) fn received(&mut self, len: usize) {
+        self.messages_received += 1;
+        self.bytes_received += len as u64;
+    }
+
+    pub(crate) fn merge(&mut self, other: &Self) {
+        self.messages_sent += other.messages_sent;
+        self.bytes_sent += other.bytes_sent;
+        self.messages_received += other.messages_received;
+        self.bytes_received += other.bytes_received;
+
+        for (&error, &count) in &other.send_errors {
+            *self.send_errors.entry(error).or_insert(0) += count;
+        }
+    }
+}
@@ -1,9 +1,10 @@
-use crate::sys;
+use crate::{sys, Cast};
 
 /// Lobby Search Comparison
 ///
 /// > [Enum in official docs](https://discordapp.com/developers/docs/game-sdk/lobbies#data-models-lobbysearchcomparison-enum)
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Comparison {
     /// Metadata must be equal to the search value
     Equal,
@@ -19,6 +20,17 @@ pub enum Comparison {
     NotEqual,
 }
 
+impl Comparison {
+    /// Whether values of a given cast can be compared this way,
+    /// strings can only be compared with `Equal` and `NotEqual`
+    pub fn supports(self, cast: Cast) -> bool {
+        match cast {
+            Cast::Number => true,
+            Cast::String => self == Self::Equal || self == Self::NotEqual,
+        }
+    }
+}
+
 impl Into<sys::EDiscordLobbySearchComparison> for Comparison {
     fn into(self) -> sys::EDiscordLobbySearchComparison {
         match self {
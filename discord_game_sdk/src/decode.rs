@@ -0,0 +1,26 @@
+use crate::{Error, Result};
+
+/// A value that can be received through a [`TypedChannel`](struct.TypedChannel.html)
+///
+/// Implemented for raw bytes and strings, and by [`Bincode`](struct.Bincode.html)
+/// for any `Deserialize` type with the `bincode` feature.
+pub trait Decode: Sized {
+    /// Decodes the contents of a message
+    ///
+    /// ## Errors
+    ///
+    /// Should fail with [`Error::InvalidPayload`](enum.Error.html#variant.InvalidPayload).
+    fn decode(bytes: &[u8]) -> Result<Self>;
+}
+
+impl Decode for Vec<u8> {
+    fn decode(bytes: &[u8]) -> Result<Self> {
+        Ok(bytes.to_vec())
+    }
+}
+
+impl Decode for String {
+    fn decode(bytes: &[u8]) -> Result<Self> {
+        String::from_utf8(bytes.to_vec()).map_err(|_| Error::InvalidPayload)
+    }
+}
@@ -1,10 +1,16 @@
-use crate::{sys, ClientID};
-use std::{cell::UnsafeCell, marker::PhantomData, mem::ManuallyDrop};
+use crate::{discord_handle::Commands, sys, ClientID, NetworkStats, SendQueue};
+use std::{
+    cell::{RefCell, UnsafeCell},
+    marker::PhantomData,
+    mem::ManuallyDrop,
+    sync::Arc,
+};
 
 /// Main interface with SDK
 ///
 /// The Discord Game SDK is not thread-safe, this struct should only be made `Send`/`Sync` with
 /// appropriate safety measures, and not as-is.
+/// Other threads can send commands to it through a [`DiscordHandle`](struct.DiscordHandle.html).
 ///
 /// As opposed to the general structure of the Discord Game SDK, and to help with memory and thread
 /// safety, the methods of the Manager "classes" are part of this struct.
@@ -49,6 +55,7 @@ use std::{cell::UnsafeCell, marker::PhantomData, mem::ManuallyDrop};
 /// - [Applications](#applications)
 /// - [Images](#images)
 /// - [Lobbies](#lobbies)
+/// - [Matchmaking](#matchmaking)
 /// - [Networking](#networking)
 /// - [Overlay](#overlay)
 /// - [Relationships](#relationships)
@@ -56,6 +63,9 @@ use std::{cell::UnsafeCell, marker::PhantomData, mem::ManuallyDrop};
 /// - [Store](#store)
 /// - [Users](#users)
 /// - [Voice](#voice)
+/// - [Owned Results](#owned-results)
+/// - [Event Queue](#event-queue)
+/// - [Futures](#futures)
 pub struct Discord<'d, E>(pub(crate) *mut DiscordInner<'d, E>);
 
 impl<E> Drop for Discord<'_, E> {
@@ -112,6 +122,11 @@ pub(crate) struct DiscordInner<'d, E> {
     pub(crate) core: *mut sys::IDiscordCore,
     pub(crate) client_id: sys::DiscordClientId,
     pub(crate) event_handler: UnsafeCell<Option<E>>,
+    pub(crate) commands: Arc<Commands<E>>,
+    pub(crate) network_stats: RefCell<Option<NetworkStats>>,
+    pub(crate) send_queue: RefCell<Option<SendQueue>>,
+    #[cfg(any(test, feature = "mock"))]
+    pub(crate) is_mock: bool,
 
     pub(crate) achievement_events: sys::IDiscordAchievementEvents,
     pub(crate) activity_events: sys::IDiscordActivityEvents,
@@ -132,6 +147,12 @@ impl<E> DiscordInner<'_, E> {
     pub(crate) fn event_handler_mut(&mut self) -> &mut Option<E> {
         unsafe { &mut *self.event_handler.get() }
     }
+
+    pub(crate) fn record_network_stats(&self, record: impl FnOnce(&mut NetworkStats)) {
+        if let Some(stats) = self.network_stats.borrow_mut().as_mut() {
+            record(stats);
+        }
+    }
 }
 
 impl<E: std::fmt::Debug> std::fmt::Debug for DiscordInner<'_, E> {
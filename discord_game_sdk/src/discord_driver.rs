@@ -0,0 +1,193 @@
+use crate::{Discord, Result};
+use std::{cell::RefCell, fmt, rc::Rc, time::Duration};
+
+/// Owns a [`Discord`](struct.Discord.html) instance and runs its callbacks from an async runtime
+///
+/// The driver is meant to live on a single thread, on a `tokio::task::LocalSet` or an
+/// `async-std` task for example, next to the tasks that use it. Its clones share the same
+/// instance: one of them [runs](#method.run_tokio) the callbacks at a fixed tick rate, waking
+/// the futures of the [Futures](struct.Discord.html#futures) section, while the others
+/// [access the instance](#method.with) to start new requests.
+///
+/// Requires the `tokio` or `async-std` feature.
+///
+/// ```rust
+/// # use discord_game_sdk::*;
+/// # async fn example(discord: Discord<'static, ()>) -> Result<()> {
+/// let driver = DiscordDriver::new(discord);
+///
+/// // On a `tokio::task::LocalSet`:
+/// // tokio::task::spawn_local(driver.clone().run_tokio());
+///
+/// let lobby = driver
+///     .with(|discord| discord.create_lobby_async(&LobbyTransaction::new()))
+///     .await?;
+/// # Ok(()) }
+/// ```
+pub struct DiscordDriver<E: 'static> {
+    discord: Rc<RefCell<Discord<'static, E>>>,
+    tick_rate: Duration,
+}
+
+impl<E> DiscordDriver<E> {
+    /// Drives `discord`, running its callbacks 60 times a second
+    pub fn new(discord: Discord<'static, E>) -> Self {
+        Self {
+            discord: Rc::new(RefCell::new(discord)),
+            tick_rate: Duration::from_micros(16_667),
+        }
+    }
+
+    /// How long to wait between two calls to
+    /// [`run_callbacks`](struct.Discord.html#method.run_callbacks)
+    ///
+    /// Only affects loops started after this call.
+    pub fn with_tick_rate(&mut self, tick_rate: Duration) -> &mut Self {
+        self.tick_rate = tick_rate;
+        self
+    }
+
+    /// Calls `f` with the instance
+    ///
+    /// Futures returned by `f` may be awaited afterwards, as they do not borrow the instance.
+    ///
+    /// ## Panics
+    ///
+    /// If called from within an [`EventHandler`](trait.EventHandler.html) method or a callback,
+    /// use the `Discord` those receive instead.
+    pub fn with<R>(&self, f: impl FnOnce(&Discord<'static, E>) -> R) -> R {
+        f(&self.discord.borrow())
+    }
+
+    /// Calls `f` with the instance, mutably
+    ///
+    /// ## Panics
+    ///
+    /// Same as [`with`](#method.with).
+    pub fn with_mut<R>(&self, f: impl FnOnce(&mut Discord<'static, E>) -> R) -> R {
+        f(&mut self.discord.borrow_mut())
+    }
+
+    /// Runs the callbacks once, see
+    /// [`run_callbacks`](struct.Discord.html#method.run_callbacks)
+    pub fn tick(&self) -> Result<()> {
+        self.discord.borrow_mut().run_callbacks()
+    }
+
+    /// Runs the callbacks at the tick rate, on a `tokio` runtime with the time driver enabled
+    ///
+    /// Missed ticks are delayed rather than run in a burst.
+    ///
+    /// Requires the `tokio` feature.
+    ///
+    /// ## Errors
+    ///
+    /// Stops at the first error of [`tick`](#method.tick).
+    #[cfg(feature = "tokio")]
+    pub async fn run_tokio(self) -> Result<()> {
+        use tokio_rt::time::{interval, MissedTickBehavior};
+
+        let mut interval = interval(self.tick_rate);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            let _ = interval.tick().await;
+            self.tick()?;
+        }
+    }
+
+    /// Runs the callbacks at the tick rate, on the `async-std` runtime
+    ///
+    /// Requires the `async-std` feature.
+    ///
+    /// ## Errors
+    ///
+    /// Stops at the first error of [`tick`](#method.tick).
+    #[cfg(feature = "async-std")]
+    pub async fn run_async_std(self) -> Result<()> {
+        loop {
+            async_std_rt::task::sleep(self.tick_rate).await;
+            self.tick()?;
+        }
+    }
+}
+
+impl<E> Clone for DiscordDriver<E> {
+    fn clone(&self) -> Self {
+        Self {
+            discord: self.discord.clone(),
+            tick_rate: self.tick_rate,
+        }
+    }
+}
+
+impl<E> fmt::Debug for DiscordDriver<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("DiscordDriver")
+            .field("tick_rate", &self.tick_rate)
+            .finish()
+    }
+}
+
+#[cfg(all(test, any(feature = "tokio", feature = "async-std")))]
+mod tests {
+    use crate::{Discord, DiscordDriver, LobbyTransaction};
+    use std::time::Duration;
+
+    fn driver() -> DiscordDriver<()> {
+        let mut driver = DiscordDriver::new(Discord::<()>::mock());
+        let _ = driver.with_tick_rate(Duration::from_millis(1));
+        driver
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn tokio() {
+        use tokio_rt::{runtime::Builder, task};
+
+        let runtime = Builder::new_current_thread().enable_time().build().unwrap();
+        let local = task::LocalSet::new();
+        let driver = driver();
+
+        let lobby = local.block_on(&runtime, async {
+            let runner = task::spawn_local(driver.clone().run_tokio());
+
+            let lobby = driver
+                .with(|discord| discord.create_lobby_async(&LobbyTransaction::new()))
+                .await;
+
+            runner.abort();
+            lobby
+        });
+
+        assert_eq!(lobby.unwrap().owner_id(), 1);
+    }
+
+    #[cfg(feature = "async-std")]
+    #[test]
+    fn async_std() {
+        use async_std_rt::{future::poll_fn, task};
+        use std::{future::Future, task::Poll};
+
+        let driver = driver();
+
+        let lobby = task::block_on(async {
+            let mut runner = Box::pin(driver.clone().run_async_std());
+            let mut lobby = Box::pin(
+                driver.with(|discord| discord.create_lobby_async(&LobbyTransaction::new())),
+            );
+
+            // The runner is not `Send`, and `spawn_local` is unstable in `async-std`
+            poll_fn(|cx| {
+                if let Poll::Ready(res) = runner.as_mut().poll(cx) {
+                    panic!("the driver stopped: {:?}", res);
+                }
+
+                lobby.as_mut().poll(cx)
+            })
+            .await
+        });
+
+        assert_eq!(lobby.unwrap().owner_id(), 1);
+    }
+}
@@ -0,0 +1,198 @@
+use crate::{Activity, Discord, LobbyID, NetworkChannelID, Result, UserID};
+use std::{
+    fmt,
+    sync::{
+        mpsc::{channel, Receiver, Sender},
+        Mutex, Weak,
+    },
+};
+
+pub(crate) type Command<E> = Box<dyn FnOnce(&Discord<'_, E>) + Send>;
+
+/// Commands sent by the handles, run by `Discord::run_callbacks`
+pub(crate) type Commands<E> = Mutex<Vec<Command<E>>>;
+
+/// Handle to a [`Discord`](struct.Discord.html) instance that can be sent to other threads
+///
+/// Obtained with [`Discord::handle`](struct.Discord.html#method.handle).
+///
+/// Methods queue a command that runs on the thread owning the instance, during its next call to
+/// [`run_callbacks`](struct.Discord.html#method.run_callbacks). Their results are sent through
+/// the returned [`Receiver`](https://doc.rust-lang.org/std/sync/mpsc/struct.Receiver.html),
+/// once the SDK fires the callback of the method.
+///
+/// If the instance is dropped, commands are discarded and the receivers disconnect.
+///
+/// ```rust
+/// # use discord_game_sdk::*;
+/// # fn example(mut discord: Discord<'_, ()>) -> Result<()> {
+/// let handle = discord.handle();
+///
+/// let ui = std::thread::spawn(move || {
+///     let result = handle.update_activity(Activity::empty().with_state("In the menus"));
+///
+///     if let Ok(Err(error)) = result.recv() {
+///         eprintln!("failed to update activity: {}", error);
+///     }
+/// });
+///
+/// loop {
+///     discord.run_callbacks()?;
+/// #   break;
+/// }
+/// # Ok(()) }
+/// ```
+pub struct DiscordHandle<E>(pub(crate) Weak<Commands<E>>);
+
+impl<E> DiscordHandle<E> {
+    /// Runs `command` on the thread owning the instance, and sends back what it returns
+    ///
+    /// The most general form of the other methods, any method of `Discord` can be called here.
+    pub fn run<R: Send + 'static>(
+        &self,
+        command: impl FnOnce(&Discord<'_, E>) -> R + Send + 'static,
+    ) -> Receiver<R> {
+        let (sender, receiver) = channel();
+
+        self.push(Box::new(move |discord| {
+            let _ = sender.send(command(discord));
+        }));
+
+        receiver
+    }
+
+    /// Whether the instance is still alive
+    pub fn is_alive(&self) -> bool {
+        self.0.upgrade().is_some()
+    }
+
+    /// See [`Discord::update_activity`](struct.Discord.html#method.update_activity)
+    pub fn update_activity(&self, activity: &Activity) -> Receiver<Result<()>> {
+        let activity = activity.clone();
+
+        self.with_callback(move |discord, sender| discord.update_activity(&activity, reply(sender)))
+    }
+
+    /// See [`Discord::clear_activity`](struct.Discord.html#method.clear_activity)
+    pub fn clear_activity(&self) -> Receiver<Result<()>> {
+        self.with_callback(|discord, sender| discord.clear_activity(reply(sender)))
+    }
+
+    /// See [`Discord::send_lobby_message`](struct.Discord.html#method.send_lobby_message)
+    pub fn send_lobby_message(
+        &self,
+        lobby_id: LobbyID,
+        buffer: impl Into<Vec<u8>>,
+    ) -> Receiver<Result<()>> {
+        let buffer = buffer.into();
+
+        self.with_callback(move |discord, sender| {
+            discord.send_lobby_message(lobby_id, buffer, reply(sender))
+        })
+    }
+
+    /// See [`Discord::send_lobby_network_message`](struct.Discord.html#method.send_lobby_network_message)
+    pub fn send_lobby_network_message(
+        &self,
+        lobby_id: LobbyID,
+        user_id: UserID,
+        channel_id: NetworkChannelID,
+        buffer: impl Into<Vec<u8>>,
+    ) -> Receiver<Result<()>> {
+        let buffer = buffer.into();
+
+        self.run(move |discord| {
+            discord.send_lobby_network_message(lobby_id, user_id, channel_id, &buffer)
+        })
+    }
+
+    /// See [`Discord::write_file_async`](struct.Discord.html#method.write_file_async)
+    pub fn write_file(
+        &self,
+        filename: impl Into<String>,
+        buffer: impl Into<Vec<u8>>,
+    ) -> Receiver<Result<()>> {
+        let filename = filename.into();
+        let buffer = buffer.into();
+
+        self.with_callback(move |discord, sender| {
+            discord.write_file_async(filename, buffer, reply(sender))
+        })
+    }
+
+    fn with_callback<T: Send + 'static>(
+        &self,
+        command: impl FnOnce(&Discord<'_, E>, Sender<T>) + Send + 'static,
+    ) -> Receiver<T> {
+        let (sender, receiver) = channel();
+
+        self.push(Box::new(move |discord| command(discord, sender)));
+
+        receiver
+    }
+
+    fn push(&self, command: Command<E>) {
+        if let Some(commands) = self.0.upgrade() {
+            commands.lock().unwrap().push(command);
+        }
+    }
+}
+
+/// Callback sending its result through `sender`
+fn reply<'d, E, T: 'd>(sender: Sender<T>) -> impl 'd + FnOnce(&Discord<'d, E>, T) {
+    move |_, result| {
+        let _ = sender.send(result);
+    }
+}
+
+impl<E> Clone for DiscordHandle<E> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<E> fmt::Debug for DiscordHandle<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("DiscordHandle")
+            .field("alive", &self.is_alive())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Discord, DiscordHandle};
+    use std::thread;
+
+    #[test]
+    fn runs_commands_during_run_callbacks() {
+        fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+        let mut discord = Discord::<()>::mock();
+        let handle = discord.handle();
+        assert_send_sync(&handle);
+
+        let (written, user_id) = thread::spawn(move || {
+            (
+                handle.write_file("save", b"data".to_vec()),
+                handle.run(|discord| discord.current_user().map(|user| user.id())),
+            )
+        })
+        .join()
+        .unwrap();
+
+        assert!(written.try_recv().is_err());
+        assert!(user_id.try_recv().is_err());
+
+        discord.run_callbacks().unwrap();
+        assert_eq!(user_id.try_recv().unwrap().unwrap(), 1);
+        assert!(written.try_recv().unwrap().is_ok());
+        assert_eq!(discord.read_file("save", &mut [0; 4]).unwrap(), 4);
+
+        let handle: DiscordHandle<()> = discord.handle();
+        let cleared = handle.clear_activity();
+        drop(discord);
+        assert!(!handle.is_alive());
+        assert!(cleared.recv().is_err());
+    }
+}
@@ -0,0 +1,26 @@
+use crate::Result;
+
+/// A value that can be sent through a [`TypedChannel`](struct.TypedChannel.html)
+///
+/// Implemented for raw bytes and strings, and by [`Bincode`](struct.Bincode.html)
+/// for any `Serialize` type with the `bincode` feature.
+pub trait Encode {
+    /// Encodes the value as the contents of a message
+    ///
+    /// ## Errors
+    ///
+    /// Should fail with [`Error::InvalidPayload`](enum.Error.html#variant.InvalidPayload).
+    fn encode(&self) -> Result<Vec<u8>>;
+}
+
+impl Encode for Vec<u8> {
+    fn encode(&self) -> Result<Vec<u8>> {
+        Ok(self.clone())
+    }
+}
+
+impl Encode for String {
+    fn encode(&self) -> Result<Vec<u8>> {
+        Ok(self.as_bytes().to_vec())
+    }
+}
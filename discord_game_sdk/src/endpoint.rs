@@ -0,0 +1,88 @@
+use crate::{Discord, Event, LobbyID, NetworkChannelID, NetworkPeerID, Result, UserID};
+
+/// The other end of a networking channel
+///
+/// Messages are sent to and received from either a peer of the
+/// [networking layer](struct.Discord.html#networking), or a member through the
+/// [lobby networking layer](struct.Discord.html#lobbies).
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Endpoint {
+    /// A channel opened with [`open_channel`](struct.Discord.html#method.open_channel)
+    Peer {
+        /// The peer on the other end
+        peer_id: NetworkPeerID,
+        /// The channel
+        channel_id: NetworkChannelID,
+    },
+
+    /// A channel opened with
+    /// [`open_lobby_network_channel`](struct.Discord.html#method.open_lobby_network_channel)
+    Lobby {
+        /// The lobby of the channel
+        lobby_id: LobbyID,
+        /// The member on the other end
+        member_id: UserID,
+        /// The channel
+        channel_id: NetworkChannelID,
+    },
+}
+
+impl Endpoint {
+    /// The channel of the endpoint
+    pub fn channel_id(&self) -> NetworkChannelID {
+        match *self {
+            Self::Peer { channel_id, .. } | Self::Lobby { channel_id, .. } => channel_id,
+        }
+    }
+
+    /// Where a networking event comes from, and what it carries
+    pub fn from_event(event: &Event) -> Option<(Self, &[u8])> {
+        match *event {
+            Event::NetworkMessage {
+                peer_id,
+                channel_id,
+                ref data,
+            } => Some((
+                Self::Peer {
+                    peer_id,
+                    channel_id,
+                },
+                data,
+            )),
+
+            Event::LobbyNetworkMessage {
+                lobby_id,
+                member_id,
+                channel_id,
+                ref data,
+            } => Some((
+                Self::Lobby {
+                    lobby_id,
+                    member_id,
+                    channel_id,
+                },
+                data,
+            )),
+
+            _ => None,
+        }
+    }
+
+    /// Sends a message with [`send_message`](struct.Discord.html#method.send_message) or
+    /// [`send_lobby_network_message`](struct.Discord.html#method.send_lobby_network_message)
+    pub fn send<E>(&self, discord: &Discord<'_, E>, buffer: &[u8]) -> Result<()> {
+        match *self {
+            Self::Peer {
+                peer_id,
+                channel_id,
+            } => discord.send_message(peer_id, channel_id, buffer),
+
+            Self::Lobby {
+                lobby_id,
+                member_id,
+                channel_id,
+            } => discord.send_lobby_network_message(lobby_id, member_id, channel_id, buffer),
+        }
+    }
+}
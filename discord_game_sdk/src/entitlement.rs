@@ -35,3 +35,37 @@ impl std::fmt::Debug for Entitlement {
             .finish()
     }
 }
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Entitlement {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("Entitlement", 3)?;
+        state.serialize_field("id", &self.id())?;
+        state.serialize_field("kind", &self.kind())?;
+        state.serialize_field("sku_id", &self.sku_id())?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Entitlement {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "Entitlement")]
+        struct Fields {
+            id: Snowflake,
+            kind: EntitlementKind,
+            sku_id: Snowflake,
+        }
+
+        let fields = Fields::deserialize(deserializer)?;
+
+        Ok(Self(sys::DiscordEntitlement {
+            id: fields.id,
+            type_: fields.kind.into(),
+            sku_id: fields.sku_id,
+        }))
+    }
+}
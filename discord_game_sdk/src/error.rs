@@ -200,3 +200,70 @@ impl fmt::Display for Error {
 }
 
 impl std::error::Error for Error {}
+
+impl From<Error> for std::io::Error {
+    fn from(error: Error) -> Self {
+        let kind = match error {
+            Error::NotFound => std::io::ErrorKind::NotFound,
+            Error::InvalidFilename | Error::InvalidFileSize => std::io::ErrorKind::InvalidInput,
+            _ => std::io::ErrorKind::Other,
+        };
+
+        Self::new(kind, error)
+    }
+}
+
+#[cfg(any(test, feature = "mock"))]
+impl Error {
+    /// The result code the SDK would return, for the mock's fault injection
+    pub(crate) fn into_sys(self) -> sys::EDiscordResult {
+        use Error::*;
+
+        match self {
+            ServiceUnavailable => sys::DiscordResult_ServiceUnavailable,
+            InvalidVersion => sys::DiscordResult_InvalidVersion,
+            LockFailed => sys::DiscordResult_LockFailed,
+            Internal => sys::DiscordResult_InternalError,
+            InvalidPayload => sys::DiscordResult_InvalidPayload,
+            InvalidCommand => sys::DiscordResult_InvalidCommand,
+            InvalidPermissions => sys::DiscordResult_InvalidPermissions,
+            NotFetched => sys::DiscordResult_NotFetched,
+            NotFound => sys::DiscordResult_NotFound,
+            Conflict => sys::DiscordResult_Conflict,
+            InvalidSecret => sys::DiscordResult_InvalidSecret,
+            InvalidJoinSecret => sys::DiscordResult_InvalidJoinSecret,
+            NoEligibleActivity => sys::DiscordResult_NoEligibleActivity,
+            InvalidInvite => sys::DiscordResult_InvalidInvite,
+            NotAuthenticated => sys::DiscordResult_NotAuthenticated,
+            InvalidAccessToken => sys::DiscordResult_InvalidAccessToken,
+            ApplicationMismatch => sys::DiscordResult_ApplicationMismatch,
+            InvalidDataUrl => sys::DiscordResult_InvalidDataUrl,
+            InvalidBase64 => sys::DiscordResult_InvalidBase64,
+            NotFiltered => sys::DiscordResult_NotFiltered,
+            LobbyFull => sys::DiscordResult_LobbyFull,
+            InvalidLobbySecret => sys::DiscordResult_InvalidLobbySecret,
+            InvalidFilename => sys::DiscordResult_InvalidFilename,
+            InvalidFileSize => sys::DiscordResult_InvalidFileSize,
+            InvalidEntitlement => sys::DiscordResult_InvalidEntitlement,
+            NotInstalled => sys::DiscordResult_NotInstalled,
+            NotRunning => sys::DiscordResult_NotRunning,
+            InsufficientBuffer => sys::DiscordResult_InsufficientBuffer,
+            PurchaseCanceled => sys::DiscordResult_PurchaseCanceled,
+            InvalidGuild => sys::DiscordResult_InvalidGuild,
+            InvalidEvent => sys::DiscordResult_InvalidEvent,
+            InvalidChannel => sys::DiscordResult_InvalidChannel,
+            InvalidOrigin => sys::DiscordResult_InvalidOrigin,
+            RateLimited => sys::DiscordResult_RateLimited,
+            OAuth2 => sys::DiscordResult_OAuth2Error,
+            SelectChannelTimeout => sys::DiscordResult_SelectChannelTimeout,
+            GetGuildTimeout => sys::DiscordResult_GetGuildTimeout,
+            SelectVoiceForceRequired => sys::DiscordResult_SelectVoiceForceRequired,
+            CaptureShortcutAlreadyListening => sys::DiscordResult_CaptureShortcutAlreadyListening,
+            UnauthorizedForAchievement => sys::DiscordResult_UnauthorizedForAchievement,
+            InvalidGiftCode => sys::DiscordResult_InvalidGiftCode,
+            Purchase => sys::DiscordResult_PurchaseError,
+            TransactionAborted => sys::DiscordResult_TransactionAborted,
+            Undefined(n) => n,
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use crate::{
+    Action, Activity, Entitlement, LobbyID, NetworkChannelID, NetworkPeerID, Relationship, User,
+    UserAchievement, UserID,
+};
+
+/// Owned counterpart to the methods of [`EventHandler`](trait.EventHandler.html)
+///
+/// Each variant carries the same data as the method of the same name.
+/// Queued by [`EventQueue`](struct.EventQueue.html).
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Event {
+    /// See [`on_user_achievement_update`](trait.EventHandler.html#method.on_user_achievement_update)
+    UserAchievementUpdate {
+        /// The achievement that was updated
+        user_achievement: UserAchievement,
+    },
+
+    /// See [`on_activity_join`](trait.EventHandler.html#method.on_activity_join)
+    ActivityJoin {
+        /// The join secret of the activity
+        secret: String,
+    },
+
+    /// See [`on_activity_spectate`](trait.EventHandler.html#method.on_activity_spectate)
+    ActivitySpectate {
+        /// The spectate secret of the activity
+        secret: String,
+    },
+
+    /// See [`on_activity_join_request`](trait.EventHandler.html#method.on_activity_join_request)
+    ActivityJoinRequest {
+        /// The user asking to join
+        user: User,
+    },
+
+    /// See [`on_activity_invite`](trait.EventHandler.html#method.on_activity_invite)
+    ActivityInvite {
+        /// Whether the invite is to join or to spectate
+        kind: Action,
+        /// The user sending the invite
+        user: User,
+        /// The activity the invite is for
+        activity: Activity,
+    },
+
+    /// See [`on_lobby_update`](trait.EventHandler.html#method.on_lobby_update)
+    LobbyUpdate {
+        /// The lobby that was updated
+        lobby_id: LobbyID,
+    },
+
+    /// See [`on_lobby_delete`](trait.EventHandler.html#method.on_lobby_delete)
+    LobbyDelete {
+        /// The lobby that was deleted
+        lobby_id: LobbyID,
+        /// Why the lobby was deleted
+        reason: u32,
+    },
+
+    /// See [`on_member_connect`](trait.EventHandler.html#method.on_member_connect)
+    MemberConnect {
+        /// The lobby that was joined
+        lobby_id: LobbyID,
+        /// The user that joined
+        member_id: UserID,
+    },
+
+    /// See [`on_member_update`](trait.EventHandler.html#method.on_member_update)
+    MemberUpdate {
+        /// The lobby of the member
+        lobby_id: LobbyID,
+        /// The member that was updated
+        member_id: UserID,
+    },
+
+    /// See [`on_member_disconnect`](trait.EventHandler.html#method.on_member_disconnect)
+    MemberDisconnect {
+        /// The lobby that was left
+        lobby_id: LobbyID,
+        /// The user that left
+        member_id: UserID,
+    },
+
+    /// See [`on_lobby_message`](trait.EventHandler.html#method.on_lobby_message)
+    LobbyMessage {
+        /// The lobby the message was sent to
+        lobby_id: LobbyID,
+        /// The member that sent the message
+        member_id: UserID,
+        /// The contents of the message
+        data: Vec<u8>,
+    },
+
+    /// See [`on_speaking`](trait.EventHandler.html#method.on_speaking)
+    Speaking {
+        /// The lobby of the member
+        lobby_id: LobbyID,
+        /// The member that started or stopped speaking
+        member_id: UserID,
+        /// Whether the member is speaking
+        speaking: bool,
+    },
+
+    /// See [`on_lobby_network_message`](trait.EventHandler.html#method.on_lobby_network_message)
+    LobbyNetworkMessage {
+        /// The lobby the message was sent through
+        lobby_id: LobbyID,
+        /// The member that sent the message
+        member_id: UserID,
+        /// The channel the message was sent on
+        channel_id: NetworkChannelID,
+        /// The contents of the message
+        data: Vec<u8>,
+    },
+
+    /// See [`on_network_message`](trait.EventHandler.html#method.on_network_message)
+    NetworkMessage {
+        /// The peer that sent the message
+        peer_id: NetworkPeerID,
+        /// The channel the message was sent on
+        channel_id: NetworkChannelID,
+        /// The contents of the message
+        data: Vec<u8>,
+    },
+
+    /// See [`on_network_route_update`](trait.EventHandler.html#method.on_network_route_update)
+    NetworkRouteUpdate {
+        /// The new route of the current user
+        route: String,
+    },
+
+    /// See [`on_overlay_toggle`](trait.EventHandler.html#method.on_overlay_toggle)
+    OverlayToggle {
+        /// Whether the overlay was closed
+        closed: bool,
+    },
+
+    /// See [`on_relationships_refresh`](trait.EventHandler.html#method.on_relationships_refresh)
+    RelationshipsRefresh,
+
+    /// See [`on_relationship_update`](trait.EventHandler.html#method.on_relationship_update)
+    RelationshipUpdate {
+        /// The relationship that changed
+        relationship: Relationship,
+    },
+
+    /// See [`on_entitlement_create`](trait.EventHandler.html#method.on_entitlement_create)
+    EntitlementCreate {
+        /// The entitlement that was received
+        entitlement: Entitlement,
+    },
+
+    /// See [`on_entitlement_delete`](trait.EventHandler.html#method.on_entitlement_delete)
+    EntitlementDelete {
+        /// The entitlement that was lost
+        entitlement: Entitlement,
+    },
+
+    /// See [`on_current_user_update`](trait.EventHandler.html#method.on_current_user_update)
+    CurrentUserUpdate,
+
+    /// See [`on_voice_settings_update`](trait.EventHandler.html#method.on_voice_settings_update)
+    VoiceSettingsUpdate,
+}
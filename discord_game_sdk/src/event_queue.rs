@@ -0,0 +1,420 @@
+use crate::{
+    Action, Activity, Discord, Entitlement, Event, EventHandler, LobbyID, NetworkChannelID,
+    NetworkPeerID, Relationship, User, UserAchievement, UserID,
+};
+use std::{cell::RefCell, collections::VecDeque, fmt, rc::Rc};
+
+/// [`EventHandler`](trait.EventHandler.html) that queues every event as an owned
+/// [`Event`](enum.Event.html)
+///
+/// Clones share the same queue. Events are read through
+/// [`drain_events`](struct.Discord.html#method.drain_events),
+/// [`event_stream`](struct.Discord.html#method.event_stream),
+/// or the methods of a clone kept aside, see the [Event Queue](struct.Discord.html#event-queue)
+/// section.
+///
+/// A custom [`EventHandler`](trait.EventHandler.html) can also hold a queue, and
+/// [`push`](#method.push) the events it does not handle itself.
+/// Implementing `AsRef<EventQueue>` gives it the methods of the Event Queue section.
+///
+/// ```rust
+/// # use discord_game_sdk::*;
+/// #[derive(Default)]
+/// struct Handler {
+///     queue: EventQueue,
+/// }
+///
+/// impl AsRef<EventQueue> for Handler {
+///     fn as_ref(&self) -> &EventQueue {
+///         &self.queue
+///     }
+/// }
+///
+/// impl EventHandler for Handler {
+///     fn on_overlay_toggle(&mut self, _: &Discord<'_, Self>, closed: bool) {
+///         println!("overlay closed: {}", closed);
+///     }
+///
+///     fn on_lobby_message(
+///         &mut self,
+///         _: &Discord<'_, Self>,
+///         lobby_id: LobbyID,
+///         member_id: UserID,
+///         data: &[u8],
+///     ) {
+///         self.queue.push(Event::LobbyMessage {
+///             lobby_id,
+///             member_id,
+///             data: data.to_vec(),
+///         })
+///     }
+/// }
+///
+/// # fn example(mut discord: Discord<'_, Handler>) -> Result<()> {
+/// *discord.event_handler_mut() = Some(Handler::default());
+///
+/// discord.run_callbacks()?;
+///
+/// for event in discord.drain_events() {
+///     if let Event::LobbyMessage { data, .. } = event {
+///         println!("{:?}", data);
+///     }
+/// }
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Default)]
+pub struct EventQueue(pub(crate) Rc<RefCell<Queue>>);
+
+#[derive(Default)]
+pub(crate) struct Queue {
+    pub(crate) events: VecDeque<Event>,
+    #[cfg(feature = "futures")]
+    pub(crate) waker: Option<std::task::Waker>,
+}
+
+impl EventQueue {
+    /// An empty queue
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of events in the queue
+    pub fn len(&self) -> usize {
+        self.0.borrow().events.len()
+    }
+
+    /// Whether the queue is empty
+    pub fn is_empty(&self) -> bool {
+        self.0.borrow().events.is_empty()
+    }
+
+    /// Removes the oldest event from the queue
+    pub fn pop(&self) -> Option<Event> {
+        self.0.borrow_mut().events.pop_front()
+    }
+
+    /// Removes all events from the queue, oldest first
+    pub fn drain(&self) -> std::collections::vec_deque::IntoIter<Event> {
+        self.0.borrow_mut().events.split_off(0).into_iter()
+    }
+
+    /// Adds an event to the back of the queue, waking the
+    /// [`EventStream`](struct.EventStream.html) if any
+    pub fn push(&self, event: Event) {
+        let mut queue = self.0.borrow_mut();
+        queue.events.push_back(event);
+
+        #[cfg(feature = "futures")]
+        {
+            if let Some(waker) = queue.waker.take() {
+                drop(queue);
+                waker.wake();
+            }
+        }
+    }
+}
+
+impl AsRef<EventQueue> for EventQueue {
+    fn as_ref(&self) -> &EventQueue {
+        self
+    }
+}
+
+impl fmt::Debug for EventQueue {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_tuple("EventQueue")
+            .field(&self.0.borrow().events)
+            .finish()
+    }
+}
+
+impl EventHandler for EventQueue {
+    fn on_user_achievement_update(
+        &mut self,
+        _: &Discord<'_, Self>,
+        user_achievement: &UserAchievement,
+    ) {
+        self.push(Event::UserAchievementUpdate {
+            user_achievement: user_achievement.clone(),
+        })
+    }
+
+    fn on_activity_join(&mut self, _: &Discord<'_, Self>, secret: &str) {
+        self.push(Event::ActivityJoin {
+            secret: secret.to_string(),
+        })
+    }
+
+    fn on_activity_spectate(&mut self, _: &Discord<'_, Self>, secret: &str) {
+        self.push(Event::ActivitySpectate {
+            secret: secret.to_string(),
+        })
+    }
+
+    fn on_activity_join_request(&mut self, _: &Discord<'_, Self>, user: &User) {
+        self.push(Event::ActivityJoinRequest { user: user.clone() })
+    }
+
+    fn on_activity_invite(
+        &mut self,
+        _: &Discord<'_, Self>,
+        kind: Action,
+        user: &User,
+        activity: &Activity,
+    ) {
+        self.push(Event::ActivityInvite {
+            kind,
+            user: user.clone(),
+            activity: activity.clone(),
+        })
+    }
+
+    fn on_lobby_update(&mut self, _: &Discord<'_, Self>, lobby_id: LobbyID) {
+        self.push(Event::LobbyUpdate { lobby_id })
+    }
+
+    fn on_lobby_delete(&mut self, _: &Discord<'_, Self>, lobby_id: LobbyID, reason: u32) {
+        self.push(Event::LobbyDelete { lobby_id, reason })
+    }
+
+    fn on_member_connect(&mut self, _: &Discord<'_, Self>, lobby_id: LobbyID, member_id: UserID) {
+        self.push(Event::MemberConnect {
+            lobby_id,
+            member_id,
+        })
+    }
+
+    fn on_member_update(&mut self, _: &Discord<'_, Self>, lobby_id: LobbyID, member_id: UserID) {
+        self.push(Event::MemberUpdate {
+            lobby_id,
+            member_id,
+        })
+    }
+
+    fn on_member_disconnect(
+        &mut self,
+        _: &Discord<'_, Self>,
+        lobby_id: LobbyID,
+        member_id: UserID,
+    ) {
+        self.push(Event::MemberDisconnect {
+            lobby_id,
+            member_id,
+        })
+    }
+
+    fn on_lobby_message(
+        &mut self,
+        _: &Discord<'_, Self>,
+        lobby_id: LobbyID,
+        member_id: UserID,
+        data: &[u8],
+    ) {
+        self.push(Event::LobbyMessage {
+            lobby_id,
+            member_id,
+            data: data.to_vec(),
+        })
+    }
+
+    fn on_speaking(
+        &mut self,
+        _: &Discord<'_, Self>,
+        lobby_id: LobbyID,
+        member_id: UserID,
+        speaking: bool,
+    ) {
+        self.push(Event::Speaking {
+            lobby_id,
+            member_id,
+            speaking,
+        })
+    }
+
+    fn on_lobby_network_message(
+        &mut self,
+        _: &Discord<'_, Self>,
+        lobby_id: LobbyID,
+        member_id: UserID,
+        channel_id: NetworkChannelID,
+        data: &[u8],
+    ) {
+        self.push(Event::LobbyNetworkMessage {
+            lobby_id,
+            member_id,
+            channel_id,
+            data: data.to_vec(),
+        })
+    }
+
+    fn on_network_message(
+        &mut self,
+        _: &Discord<'_, Self>,
+        peer_id: NetworkPeerID,
+        channel_id: NetworkChannelID,
+        data: &[u8],
+    ) {
+        self.push(Event::NetworkMessage {
+            peer_id,
+            channel_id,
+            data: data.to_vec(),
+        })
+    }
+
+    fn on_network_route_update(&mut self, _: &Discord<'_, Self>, route: &str) {
+        self.push(Event::NetworkRouteUpdate {
+            route: route.to_string(),
+        })
+    }
+
+    fn on_overlay_toggle(&mut self, _: &Discord<'_, Self>, closed: bool) {
+        self.push(Event::OverlayToggle { closed })
+    }
+
+    fn on_relationships_refresh(&mut self, _: &Discord<'_, Self>) {
+        self.push(Event::RelationshipsRefresh)
+    }
+
+    fn on_relationship_update(&mut self, _: &Discord<'_, Self>, relationship: &Relationship) {
+        self.push(Event::RelationshipUpdate {
+            relationship: relationship.clone(),
+        })
+    }
+
+    fn on_entitlement_create(&mut self, _: &Discord<'_, Self>, entitlement: &Entitlement) {
+        self.push(Event::EntitlementCreate {
+            entitlement: entitlement.clone(),
+        })
+    }
+
+    fn on_entitlement_delete(&mut self, _: &Discord<'_, Self>, entitlement: &Entitlement) {
+        self.push(Event::EntitlementDelete {
+            entitlement: entitlement.clone(),
+        })
+    }
+
+    fn on_current_user_update(&mut self, _: &Discord<'_, Self>) {
+        self.push(Event::CurrentUserUpdate)
+    }
+
+    fn on_voice_settings_update(&mut self, _: &Discord<'_, Self>) {
+        self.push(Event::VoiceSettingsUpdate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Discord, Event, EventHandler, EventQueue};
+
+    #[test]
+    fn drain_events() {
+        let mut discord = Discord::<EventQueue>::mock();
+        *discord.event_handler_mut() = Some(EventQueue::new());
+
+        discord.run_callbacks().unwrap();
+        assert_eq!(discord.drain_events().count(), 3);
+
+        discord.inject_event(Event::OverlayToggle { closed: false });
+        discord.inject_event(Event::ActivityJoin {
+            secret: "secret".to_string(),
+        });
+        assert_eq!(discord.drain_events().count(), 0);
+
+        discord.run_callbacks().unwrap();
+        assert_eq!(
+            discord.drain_events().collect::<Vec<_>>(),
+            [
+                Event::OverlayToggle { closed: false },
+                Event::ActivityJoin {
+                    secret: "secret".to_string()
+                },
+            ]
+        );
+        assert!(discord.event_handler().as_ref().unwrap().is_empty());
+    }
+
+    #[test]
+    fn delegate() {
+        #[derive(Default)]
+        struct Handler {
+            queue: EventQueue,
+            overlay_closed: Option<bool>,
+        }
+
+        impl AsRef<EventQueue> for Handler {
+            fn as_ref(&self) -> &EventQueue {
+                &self.queue
+            }
+        }
+
+        impl EventHandler for Handler {
+            fn on_overlay_toggle(&mut self, _: &Discord<'_, Self>, closed: bool) {
+                self.overlay_closed = Some(closed);
+            }
+
+            fn on_activity_join(&mut self, _: &Discord<'_, Self>, secret: &str) {
+                self.queue.push(Event::ActivityJoin {
+                    secret: secret.to_string(),
+                })
+            }
+        }
+
+        let mut discord = Discord::<Handler>::mock();
+        *discord.event_handler_mut() = Some(Handler::default());
+
+        discord.inject_event(Event::OverlayToggle { closed: true });
+        discord.inject_event(Event::ActivityJoin {
+            secret: "secret".to_string(),
+        });
+        discord.run_callbacks().unwrap();
+
+        assert_eq!(
+            discord.drain_events().collect::<Vec<_>>(),
+            [Event::ActivityJoin {
+                secret: "secret".to_string()
+            }]
+        );
+        assert_eq!(
+            discord.event_handler().as_ref().unwrap().overlay_closed,
+            Some(true)
+        );
+    }
+
+    #[cfg(feature = "futures")]
+    #[test]
+    fn event_stream() {
+        use futures_core::Stream;
+        use std::{
+            pin::Pin,
+            ptr,
+            task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+        };
+
+        fn noop(_: *const ()) {}
+
+        const VTABLE: RawWakerVTable =
+            RawWakerVTable::new(|_| RawWaker::new(ptr::null(), &VTABLE), noop, noop, noop);
+
+        let waker = unsafe { Waker::from_raw(RawWaker::new(ptr::null(), &VTABLE)) };
+        let mut cx = Context::from_waker(&waker);
+
+        let mut discord = Discord::<EventQueue>::mock();
+        *discord.event_handler_mut() = Some(EventQueue::new());
+
+        discord.run_callbacks().unwrap();
+        let _ = discord.drain_events();
+
+        let mut events = discord.event_stream();
+        assert_eq!(Pin::new(&mut events).poll_next(&mut cx), Poll::Pending);
+
+        discord.inject_event(Event::CurrentUserUpdate);
+        discord.run_callbacks().unwrap();
+        assert_eq!(
+            Pin::new(&mut events).poll_next(&mut cx),
+            Poll::Ready(Some(Event::CurrentUserUpdate))
+        );
+
+        drop(discord);
+        assert_eq!(Pin::new(&mut events).poll_next(&mut cx), Poll::Ready(None));
+    }
+}
@@ -0,0 +1,50 @@
+use crate::{event_queue::Queue, Event};
+use futures_core::Stream;
+use std::{
+    cell::RefCell,
+    fmt,
+    pin::Pin,
+    rc::Weak,
+    task::{Context, Poll},
+};
+
+/// [`Stream`](https://docs.rs/futures-core/0.3/futures_core/stream/trait.Stream.html) of the
+/// events of an [`EventQueue`](struct.EventQueue.html), see
+/// [`event_stream`](struct.Discord.html#method.event_stream)
+///
+/// Takes events out of the queue as they arrive, during
+/// [`run_callbacks`](struct.Discord.html#method.run_callbacks).
+/// Ends once every clone of the queue is dropped, along with the `Discord` instance.
+///
+/// Requires the `futures` feature.
+pub struct EventStream(pub(crate) Weak<RefCell<Queue>>);
+
+impl Stream for EventStream {
+    type Item = Event;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Event>> {
+        let queue = match self.0.upgrade() {
+            Some(queue) => queue,
+            None => return Poll::Ready(None),
+        };
+
+        let mut queue = queue.borrow_mut();
+
+        match queue.events.pop_front() {
+            Some(event) => Poll::Ready(Some(event)),
+
+            None => {
+                queue.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl fmt::Debug for EventStream {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("EventStream")
+            .field("ended", &self.0.upgrade().is_none())
+            .finish()
+    }
+}
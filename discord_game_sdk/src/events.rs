@@ -1,6 +1,7 @@
 use crate::{
     discord::{Discord, DiscordInner},
-    sys, utils, Activity, Entitlement, EventHandler, Relationship, User, UserAchievement,
+    sys, utils, Activity, Entitlement, EventHandler, NetworkStats, Relationship, User,
+    UserAchievement,
 };
 use std::{ffi::c_void, mem::ManuallyDrop};
 
@@ -30,6 +31,12 @@ fn with_event_handler<E>(
     })
 }
 
+fn record_network_stats<E>(inner: *mut c_void, record: impl FnOnce(&mut NetworkStats)) {
+    debug_assert!(!inner.is_null());
+
+    unsafe { &*(inner as *const DiscordInner<'_, E>) }.record_network_stats(record)
+}
+
 pub(crate) fn achievement<E: EventHandler>() -> sys::IDiscordAchievementEvents {
     sys::IDiscordAchievementEvents {
         on_user_achievement_update: {
@@ -222,6 +229,12 @@ pub(crate) fn lobby<E: EventHandler>() -> sys::IDiscordLobbyEvents {
                 data: *mut u8,
                 data_len: u32,
             ) {
+                record_network_stats::<E>(inner, |stats| {
+                    stats
+                        .lobby_mut(lobby_id, channel_id)
+                        .received(data_len as usize)
+                });
+
                 with_event_handler(inner, |eh: &mut E, discord| {
                     eh.on_lobby_network_message(discord, lobby_id, member_id, channel_id, unsafe {
                         std::slice::from_raw_parts(data, data_len as usize)
@@ -244,6 +257,12 @@ pub(crate) fn network<E: EventHandler>() -> sys::IDiscordNetworkEvents {
                 data: *mut u8,
                 data_len: u32,
             ) {
+                record_network_stats::<E>(inner, |stats| {
+                    stats
+                        .peer_mut(peer_id, channel_id)
+                        .received(data_len as usize)
+                });
+
                 with_event_handler(inner, |eh: &mut E, discord| {
                     eh.on_network_message(discord, peer_id, channel_id, unsafe {
                         std::slice::from_raw_parts(data, data_len as usize)
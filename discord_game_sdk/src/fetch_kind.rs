@@ -1,5 +1,6 @@
 /// Image Fetch Option
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FetchKind {
     /// Always download a fresh version of the image
     ForceRefresh,
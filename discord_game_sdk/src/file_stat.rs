@@ -35,3 +35,47 @@ impl std::fmt::Debug for FileStat {
             .finish()
     }
 }
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for FileStat {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("FileStat", 3)?;
+        state.serialize_field("filename", &self.filename())?;
+        state.serialize_field("size", &self.size())?;
+        state.serialize_field("last_modified", &self.last_modified())?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for FileStat {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use crate::utils::deserialize_charbuf;
+
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "FileStat")]
+        struct Fields {
+            filename: String,
+            size: u64,
+            last_modified: UnixTimestamp,
+        }
+
+        let fields = Fields::deserialize(deserializer)?;
+        let last_modified = fields
+            .last_modified
+            .try_into()
+            .map_err(serde::de::Error::custom)?;
+
+        let mut raw = sys::DiscordFileStat {
+            size: fields.size,
+            last_modified,
+            ..sys::DiscordFileStat::default()
+        };
+
+        deserialize_charbuf(&mut raw.filename, &fields.filename)?;
+
+        Ok(Self(raw))
+    }
+}
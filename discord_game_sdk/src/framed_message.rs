@@ -0,0 +1,31 @@
+use crate::Endpoint;
+
+/// A whole message, reassembled by [`Framing`](struct.Framing.html)
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct FramedMessage {
+    pub(crate) endpoint: Endpoint,
+    pub(crate) kind: u16,
+    pub(crate) payload: Vec<u8>,
+}
+
+impl FramedMessage {
+    /// Where the message comes from
+    pub fn endpoint(&self) -> Endpoint {
+        self.endpoint
+    }
+
+    /// The type ID the message was sent with
+    pub fn kind(&self) -> u16 {
+        self.kind
+    }
+
+    /// The contents of the message
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    /// Takes the contents of the message
+    pub fn into_payload(self) -> Vec<u8> {
+        self.payload
+    }
+}
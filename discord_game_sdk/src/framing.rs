@@ -0,0 +1,457 @@
+use crate::{Discord, Endpoint, Error, Event, FramedMessage, NetworkChannelID, Result};
+use std::{
+    collections::{BTreeSet, HashMap, VecDeque},
+    convert::TryFrom,
+};
+
+// flags: u8, kind: u16, message ID: u32, fragment index: u16, fragment count: u16
+const HEADER_SIZE: usize = 11;
+
+const SEQUENCED: u8 = 1;
+
+// Incomplete messages kept at once, the oldest are dropped first
+const MAX_PARTIALS: usize = 64;
+
+// Messages dropped for being too large that are remembered, so that their later frames are
+// dropped too, the oldest are forgotten first
+const MAX_REJECTED: usize = 64;
+
+/// Splits messages into frames that fit the SDK, and puts them back together
+///
+/// Each message is sent with a type ID, and split into as many frames as needed.
+/// Frames of a message are reassembled on the other end, which must also use `Framing`,
+/// and delivered as a whole [`FramedMessage`](struct.FramedMessage.html).
+///
+/// Frames that announce more fragments than [`max_fragments`](#method.max_fragments), or that
+/// make a message larger than [`max_message_size`](#method.max_message_size), are dropped,
+/// along with the rest of their message, so that a peer cannot hold much memory in
+/// incomplete messages.
+///
+/// Messages sent on [sequenced channels](#method.sequence_channel) carry a sequence number,
+/// and are dropped on arrival when a newer message of the same endpoint was already delivered.
+/// This is meant for unreliable channels, where only the latest state matters.
+///
+/// ```rust
+/// # use discord_game_sdk::*;
+/// # fn example(mut discord: Discord<'_, EventQueue>, lobby_id: LobbyID, member_id: UserID) -> Result<()> {
+/// const CHAT: u16 = 1;
+///
+/// let mut framing = Framing::new();
+/// let endpoint = Endpoint::Lobby {
+///     lobby_id,
+///     member_id,
+///     channel_id: 0,
+/// };
+///
+/// framing.send(&discord, endpoint, CHAT, &[0; 10_000])?;
+///
+/// discord.run_callbacks()?;
+///
+/// for event in discord.drain_events() {
+///     if let Some(message) = framing.handle_event(&event) {
+///         if message.kind() == CHAT {
+///             println!("{} bytes from {:?}", message.payload().len(), message.endpoint());
+///         }
+///     }
+/// }
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Framing {
+    max_frame_size: usize,
+    max_fragments: u16,
+    max_message_size: usize,
+    sequenced: BTreeSet<NetworkChannelID>,
+    next_ids: HashMap<Endpoint, u32>,
+    last_ids: HashMap<Endpoint, u32>,
+    partials: HashMap<(Endpoint, u32), Partial>,
+    rejected: VecDeque<(Endpoint, u32)>,
+    received: u64,
+}
+
+#[derive(Clone, Debug)]
+struct Partial {
+    kind: u16,
+    fragments: Vec<Option<Vec<u8>>>,
+    missing: usize,
+    size: usize,
+    started: u64,
+}
+
+impl Default for Framing {
+    fn default() -> Self {
+        Self {
+            max_frame_size: 1200,
+            max_fragments: 1024,
+            max_message_size: 1024 * 1024,
+            sequenced: BTreeSet::new(),
+            next_ids: HashMap::new(),
+            last_ids: HashMap::new(),
+            partials: HashMap::new(),
+            rejected: VecDeque::new(),
+            received: 0,
+        }
+    }
+}
+
+impl Framing {
+    /// Framing with frames of up to 1200 bytes, messages of up to 1024 fragments and 1 MiB,
+    /// and no sequenced channel
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the size of the largest frame, header included
+    ///
+    /// ## Panics
+    ///
+    /// `max_frame_size` must leave room for the header and at least one byte.
+    pub fn max_frame_size(&mut self, max_frame_size: usize) -> &mut Self {
+        assert!(max_frame_size > HEADER_SIZE);
+
+        self.max_frame_size = max_frame_size;
+        self
+    }
+
+    /// Sets how many fragments a received message can have
+    pub fn max_fragments(&mut self, max_fragments: u16) -> &mut Self {
+        self.max_fragments = max_fragments;
+        self
+    }
+
+    /// Sets the size of the largest received message, headers excluded
+    pub fn max_message_size(&mut self, max_message_size: usize) -> &mut Self {
+        self.max_message_size = max_message_size;
+        self
+    }
+
+    /// Marks a channel as sequenced, older messages sent on it are dropped on arrival
+    pub fn sequence_channel(&mut self, channel_id: NetworkChannelID) -> &mut Self {
+        let _ = self.sequenced.insert(channel_id);
+        self
+    }
+
+    /// Splits a message into frames, without sending them
+    ///
+    /// ## Errors
+    ///
+    /// [`Error::InvalidPayload`](enum.Error.html#variant.InvalidPayload) if the message
+    /// needs more frames than can be counted.
+    pub fn frames(
+        &mut self,
+        endpoint: Endpoint,
+        kind: u16,
+        payload: &[u8],
+    ) -> Result<Vec<Vec<u8>>> {
+        let chunk_size = self.max_frame_size - HEADER_SIZE;
+        let count = payload.chunks(chunk_size).len();
+        let count = u16::try_from(count.max(1)).map_err(|_| Error::InvalidPayload)?;
+
+        let id = self.next_ids.entry(endpoint).or_insert(0);
+        *id = id.wrapping_add(1);
+        let id = *id;
+
+        let flags = if self.sequenced.contains(&endpoint.channel_id()) {
+            SEQUENCED
+        } else {
+            0
+        };
+
+        let mut chunks = payload.chunks(chunk_size);
+
+        Ok((0..count)
+            .map(|index| {
+                let chunk = chunks.next().unwrap_or(&[]);
+
+                let mut frame = Vec::with_capacity(HEADER_SIZE + chunk.len());
+                frame.push(flags);
+                frame.extend_from_slice(&kind.to_le_bytes());
+                frame.extend_from_slice(&id.to_le_bytes());
+                frame.extend_from_slice(&index.to_le_bytes());
+                frame.extend_from_slice(&count.to_le_bytes());
+                frame.extend_from_slice(chunk);
+                frame
+            })
+            .collect())
+    }
+
+    /// Sends a message, in as many frames as needed
+    ///
+    /// ## Errors
+    ///
+    /// [`Error::InvalidPayload`](enum.Error.html#variant.InvalidPayload) if the message
+    /// needs more frames than can be counted, or the error of the first frame that could not
+    /// be sent. The frames sent before it are lost.
+    pub fn send<E>(
+        &mut self,
+        discord: &Discord<'_, E>,
+        endpoint: Endpoint,
+        kind: u16,
+        payload: &[u8],
+    ) -> Result<()> {
+        for frame in self.frames(endpoint, kind, payload)? {
+            endpoint.send(discord, &frame)?;
+        }
+
+        Ok(())
+    }
+
+    /// Takes in a frame, returns the message it completes
+    ///
+    /// Malformed frames are dropped.
+    pub fn receive(&mut self, endpoint: Endpoint, frame: &[u8]) -> Option<FramedMessage> {
+        if frame.len() < HEADER_SIZE {
+            log::warn!(
+                "dropping frame of {} bytes from {:?}",
+                frame.len(),
+                endpoint
+            );
+            return None;
+        }
+
+        let flags = frame[0];
+        let kind = u16::from_le_bytes([frame[1], frame[2]]);
+        let id = u32::from_le_bytes([frame[3], frame[4], frame[5], frame[6]]);
+        let index = usize::from(u16::from_le_bytes([frame[7], frame[8]]));
+        let count = usize::from(u16::from_le_bytes([frame[9], frame[10]]));
+        let chunk = &frame[HEADER_SIZE..];
+
+        if index >= count {
+            log::warn!("dropping frame {}/{} from {:?}", index, count, endpoint);
+            return None;
+        }
+
+        if self.rejected.contains(&(endpoint, id)) {
+            return None;
+        }
+
+        if count > usize::from(self.max_fragments) || chunk.len() > self.max_message_size {
+            log::warn!(
+                "dropping frame of message {} from {:?}, which is too large",
+                id,
+                endpoint
+            );
+            self.reject(endpoint, id);
+            return None;
+        }
+
+        let sequenced = flags & SEQUENCED != 0;
+
+        if sequenced {
+            if let Some(&last) = self.last_ids.get(&endpoint) {
+                if !is_newer(last, id) {
+                    return None;
+                }
+            }
+        }
+
+        let payload = if count == 1 {
+            chunk.to_vec()
+        } else {
+            self.received += 1;
+
+            let started = self.received;
+            let partial = self
+                .partials
+                .entry((endpoint, id))
+                .or_insert_with(|| Partial {
+                    kind,
+                    fragments: vec![None; count],
+                    missing: count,
+                    size: 0,
+                    started,
+                });
+
+            if partial.fragments.len() != count || partial.kind != kind {
+                log::warn!(
+                    "dropping mismatched frame of message {} from {:?}",
+                    id,
+                    endpoint
+                );
+                return None;
+            }
+
+            if partial.fragments[index].is_none() {
+                if partial.size + chunk.len() > self.max_message_size {
+                    log::warn!(
+                        "dropping message {} from {:?}, which is too large",
+                        id,
+                        endpoint
+                    );
+                    self.reject(endpoint, id);
+                    return None;
+                }
+
+                partial.fragments[index] = Some(chunk.to_vec());
+                partial.missing -= 1;
+                partial.size += chunk.len();
+            }
+
+            if partial.missing > 0 {
+                self.evict();
+                return None;
+            }
+
+            self.partials
+                .remove(&(endpoint, id))
+                .unwrap()
+                .fragments
+                .into_iter()
+                .flat_map(Option::unwrap)
+                .collect()
+        };
+
+        if sequenced {
+            let _ = self.last_ids.insert(endpoint, id);
+
+            // Older messages can no longer be delivered
+            self.partials
+                .retain(|&(from, other), _| from != endpoint || is_newer(id, other));
+        }
+
+        Some(FramedMessage {
+            endpoint,
+            kind,
+            payload,
+        })
+    }
+
+    /// Takes in the frame of a networking event, returns the message it completes
+    ///
+    /// Ignores other events.
+    pub fn handle_event(&mut self, event: &Event) -> Option<FramedMessage> {
+        let (endpoint, frame) = Endpoint::from_event(event)?;
+        self.receive(endpoint, frame)
+    }
+
+    /// Forgets what was sent to and received from an endpoint, for when the channel is closed
+    pub fn forget(&mut self, endpoint: Endpoint) {
+        let _ = self.next_ids.remove(&endpoint);
+        let _ = self.last_ids.remove(&endpoint);
+        self.partials.retain(|&(from, _), _| from != endpoint);
+        self.rejected.retain(|&(from, _)| from != endpoint);
+    }
+
+    fn reject(&mut self, endpoint: Endpoint, id: u32) {
+        let _ = self.partials.remove(&(endpoint, id));
+
+        if self.rejected.len() == MAX_REJECTED {
+            let _ = self.rejected.pop_front();
+        }
+
+        self.rejected.push_back((endpoint, id));
+    }
+
+    fn evict(&mut self) {
+        while self.partials.len() > MAX_PARTIALS {
+            let oldest = *self
+                .partials
+                .iter()
+                .min_by_key(|(_, partial)| partial.started)
+                .unwrap()
+                .0;
+
+            let _ = self.partials.remove(&oldest);
+        }
+    }
+}
+
+// Message IDs wrap around, an ID is newer if it is less than half the range ahead
+fn is_newer(last: u32, id: u32) -> bool {
+    (id.wrapping_sub(last) as i32) > 0
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Endpoint, Framing};
+
+    const PEER: Endpoint = Endpoint::Peer {
+        peer_id: 7,
+        channel_id: 1,
+    };
+
+    #[test]
+    fn reassembles() {
+        let (mut sender, mut receiver) = (Framing::new(), Framing::new());
+        sender.max_frame_size(16);
+
+        let payload = (0..100).collect::<Vec<u8>>();
+        let mut frames = sender.frames(PEER, 3, &payload).unwrap();
+        assert_eq!(frames.len(), 20);
+        assert!(frames.iter().all(|frame| frame.len() <= 16));
+
+        // Unreliable channels may deliver frames out of order
+        frames.swap(0, 19);
+        let last = frames.pop().unwrap();
+
+        for frame in &frames {
+            assert_eq!(receiver.receive(PEER, frame), None);
+        }
+
+        let message = receiver.receive(PEER, &last).unwrap();
+        assert_eq!((message.endpoint(), message.kind()), (PEER, 3));
+        assert_eq!(message.payload(), &payload[..]);
+
+        let empty = sender.frames(PEER, 4, &[]).unwrap();
+        assert_eq!(empty.len(), 1);
+        assert!(receiver
+            .receive(PEER, &empty[0])
+            .unwrap()
+            .payload()
+            .is_empty());
+
+        assert_eq!(receiver.receive(PEER, &[0; 4]), None);
+    }
+
+    #[test]
+    fn drops_stale_messages() {
+        let (mut sender, mut receiver) = (Framing::new(), Framing::new());
+        sender.sequence_channel(1);
+
+        let first = sender.frames(PEER, 0, b"first").unwrap();
+        let second = sender.frames(PEER, 0, b"second").unwrap();
+
+        assert_eq!(
+            receiver.receive(PEER, &second[0]).unwrap().payload(),
+            b"second"
+        );
+        assert_eq!(receiver.receive(PEER, &first[0]), None);
+        assert_eq!(receiver.receive(PEER, &second[0]), None);
+
+        // Other endpoints are sequenced separately
+        let other = Endpoint::Peer {
+            peer_id: 8,
+            channel_id: 1,
+        };
+        assert!(receiver.receive(other, &first[0]).is_some());
+    }
+
+    #[test]
+    fn limits_messages() {
+        let (mut sender, mut receiver) = (Framing::new(), Framing::new());
+        sender.max_frame_size(21);
+        receiver.max_fragments(4).max_message_size(20);
+
+        // Too many fragments
+        let frames = sender.frames(PEER, 0, &[0; 41]).unwrap();
+        assert_eq!(frames.len(), 5);
+        assert!(frames
+            .iter()
+            .all(|frame| receiver.receive(PEER, frame).is_none()));
+
+        // Too many bytes, the message is dropped at its third fragment, along with the fourth
+        let frames = sender.frames(PEER, 0, &[0; 31]).unwrap();
+        assert_eq!(frames.len(), 4);
+        assert!(frames
+            .iter()
+            .all(|frame| receiver.receive(PEER, frame).is_none()));
+        assert!(receiver.partials.is_empty());
+
+        let frames = sender.frames(PEER, 0, &[0; 20]).unwrap();
+        assert_eq!(receiver.receive(PEER, &frames[0]), None);
+        assert_eq!(
+            receiver.receive(PEER, &frames[1]).unwrap().payload().len(),
+            20
+        );
+    }
+}
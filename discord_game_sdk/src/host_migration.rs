@@ -0,0 +1,323 @@
+use crate::{Discord, Error, Event, HostPolicy, LobbyID, LobbyTransaction, Result, UserID};
+use std::{
+    cell::RefCell,
+    collections::{BTreeSet, HashMap},
+    fmt,
+    rc::Rc,
+};
+
+type OnHostChanged = Box<dyn FnMut(LobbyID, UserID, UserID)>;
+
+/// Elects a new lobby owner when the owner leaves
+///
+/// When the owner of a lobby leaves, the SDK hands the lobby to another member.
+/// Every member elects a new host with the same [`HostPolicy`](enum.HostPolicy.html),
+/// and if the SDK picked someone else, that member hands the lobby over to the elected one
+/// with [`update_lobby`](struct.Discord.html#method.update_lobby).
+/// A handover that fails is attempted again on the next [`update`](#method.update).
+/// [`on_host_changed`](#method.on_host_changed) fires once the elected member owns the lobby,
+/// or when the owner gives the lobby away.
+///
+/// Like [`LobbyCache`](struct.LobbyCache.html), lobby events only mark lobbies as stale,
+/// and they are read again on the next [`update`](#method.update).
+///
+/// ```rust
+/// # use discord_game_sdk::*;
+/// # fn example(mut discord: Discord<'_, EventQueue>, lobby_id: LobbyID) -> Result<()> {
+/// let mut migration = HostMigration::new(HostPolicy::LowestUserID);
+/// migration.track(lobby_id);
+/// migration.on_host_changed(|lobby_id, _, host| println!("{} now hosts {}", host, lobby_id));
+///
+/// loop {
+///     discord.run_callbacks()?;
+///
+///     for event in discord.drain_events() {
+///         migration.handle_event(&event);
+///     }
+///
+///     migration.update(&discord)?;
+/// #   break;
+/// }
+/// # Ok(()) }
+/// ```
+#[derive(Default)]
+pub struct HostMigration {
+    policy: HostPolicy,
+    hosts: HashMap<LobbyID, Host>,
+    stale: BTreeSet<LobbyID>,
+    failed_handovers: Rc<RefCell<BTreeSet<LobbyID>>>,
+    on_host_changed: Option<OnHostChanged>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Host {
+    host: Option<UserID>,
+    migrating: bool,
+    handed_over_to: Option<UserID>,
+}
+
+impl HostMigration {
+    /// Watches no lobby, electing new hosts with a given policy
+    pub fn new(policy: HostPolicy) -> Self {
+        Self {
+            policy,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the function called with the lobby, the previous host and the new host,
+    /// when the host of a lobby changes
+    pub fn on_host_changed(&mut self, f: impl 'static + FnMut(LobbyID, UserID, UserID)) {
+        self.on_host_changed = Some(Box::new(f));
+    }
+
+    /// The last known host of a lobby
+    pub fn host(&self, lobby_id: LobbyID) -> Option<UserID> {
+        self.hosts.get(&lobby_id).and_then(|host| host.host)
+    }
+
+    /// Starts watching a lobby, its host is read on the next update
+    pub fn track(&mut self, lobby_id: LobbyID) {
+        let _ = self.hosts.entry(lobby_id).or_insert(Host {
+            host: None,
+            migrating: false,
+            handed_over_to: None,
+        });
+
+        let _ = self.stale.insert(lobby_id);
+    }
+
+    /// Stops watching a lobby
+    pub fn untrack(&mut self, lobby_id: LobbyID) {
+        let _ = self.hosts.remove(&lobby_id);
+        let _ = self.stale.remove(&lobby_id);
+    }
+
+    /// Marks the lobby of a lobby event as stale, ignores other events
+    pub fn handle_event(&mut self, event: &Event) {
+        match *event {
+            Event::LobbyUpdate { lobby_id } => self.on_lobby_update(lobby_id),
+            Event::LobbyDelete { lobby_id, reason } => self.on_lobby_delete(lobby_id, reason),
+            Event::MemberDisconnect {
+                lobby_id,
+                member_id,
+            } => self.on_member_disconnect(lobby_id, member_id),
+            _ => {}
+        }
+    }
+
+    /// To be called from [`EventHandler::on_lobby_update`](trait.EventHandler.html#method.on_lobby_update)
+    pub fn on_lobby_update(&mut self, lobby_id: LobbyID) {
+        if self.hosts.contains_key(&lobby_id) {
+            let _ = self.stale.insert(lobby_id);
+        }
+    }
+
+    /// To be called from [`EventHandler::on_lobby_delete`](trait.EventHandler.html#method.on_lobby_delete)
+    pub fn on_lobby_delete(&mut self, lobby_id: LobbyID, _reason: u32) {
+        self.untrack(lobby_id);
+    }
+
+    /// To be called from [`EventHandler::on_member_disconnect`](trait.EventHandler.html#method.on_member_disconnect)
+    pub fn on_member_disconnect(&mut self, lobby_id: LobbyID, member_id: UserID) {
+        if let Some(host) = self.hosts.get_mut(&lobby_id) {
+            if host.migrating || host.host == Some(member_id) {
+                host.migrating = true;
+                let _ = self.stale.insert(lobby_id);
+            }
+        }
+    }
+
+    /// Reads the stale lobbies from the SDK, hands lobbies over to their elected host
+    /// and fires [`on_host_changed`](#method.on_host_changed)
+    ///
+    /// Lobbies the SDK no longer knows about, or that no member is left in,
+    /// are no longer watched.
+    ///
+    /// ## Errors
+    ///
+    /// Lobbies that could not be read stay stale, and are read again on the next update.
+    /// The other lobbies are still read, and the first error is returned.
+    pub fn update<E>(&mut self, discord: &Discord<'_, E>) -> Result<()> {
+        let mut result = Ok(());
+
+        for lobby_id in self.failed_handovers.replace(BTreeSet::new()) {
+            if let Some(host) = self.hosts.get_mut(&lobby_id) {
+                host.handed_over_to = None;
+                let _ = self.stale.insert(lobby_id);
+            }
+        }
+
+        for lobby_id in self.stale.iter().cloned().collect::<Vec<_>>() {
+            if let Err(error) = self.update_host(discord, lobby_id) {
+                if result.is_ok() {
+                    result = Err(error);
+                }
+            }
+        }
+
+        result
+    }
+
+    fn update_host<E>(&mut self, discord: &Discord<'_, E>, lobby_id: LobbyID) -> Result<()> {
+        let owner = match discord.lobby(lobby_id) {
+            Ok(lobby) => lobby.owner_id(),
+
+            Err(Error::NotFound) => {
+                self.untrack(lobby_id);
+                return Ok(());
+            }
+
+            Err(error) => return Err(error),
+        };
+
+        let host = self.hosts[&lobby_id];
+
+        let elected = if host.migrating {
+            let members = discord
+                .iter_lobby_member_ids(lobby_id)?
+                .collect::<Result<Vec<_>>>()?;
+
+            match self.policy.elect(&members) {
+                Some(elected) => elected,
+
+                None => {
+                    self.untrack(lobby_id);
+                    return Ok(());
+                }
+            }
+        } else {
+            owner
+        };
+
+        if elected != owner
+            && host.handed_over_to != Some(elected)
+            && discord.current_user()?.id() == owner
+        {
+            let failed_handovers = self.failed_handovers.clone();
+
+            // The elected host fires the lobby update that completes the migration
+            discord.update_lobby(
+                lobby_id,
+                LobbyTransaction::new().owner(elected),
+                move |_, res| {
+                    if let Err(error) = res {
+                        log::warn!("failed to hand lobby over to elected host: {}", error);
+                        let _ = failed_handovers.borrow_mut().insert(lobby_id);
+                    }
+                },
+            );
+
+            self.hosts.get_mut(&lobby_id).unwrap().handed_over_to = Some(elected);
+        }
+
+        let _ = self.stale.remove(&lobby_id);
+
+        if elected != owner {
+            return Ok(());
+        }
+
+        let _ = self.hosts.insert(
+            lobby_id,
+            Host {
+                host: Some(owner),
+                migrating: false,
+                handed_over_to: None,
+            },
+        );
+
+        if let (Some(previous), Some(on_host_changed)) = (host.host, self.on_host_changed.as_mut())
+        {
+            if previous != owner {
+                on_host_changed(lobby_id, previous, owner);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for HostMigration {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("HostMigration")
+            .field("policy", &self.policy)
+            .field("hosts", &self.hosts)
+            .field("stale", &self.stale)
+            .field("failed_handovers", &self.failed_handovers.borrow())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Error, HostMigration, HostPolicy, MockHub, MockLobby, MockState};
+    use std::{cell::RefCell, rc::Rc};
+
+    #[test]
+    fn elects_lowest_user_id() {
+        migrate(0);
+    }
+
+    #[test]
+    fn retries_failed_handover() {
+        migrate(1);
+    }
+
+    // Carol is handed the lobby by the SDK, and hands it over to Bob
+    fn migrate(failed_handovers: u32) {
+        let hub = MockHub::new();
+
+        let alice = hub.member(
+            MockState::new()
+                .with_current_user(1, "alice", "0001")
+                .with_lobby(MockLobby::new(42, 1).with_member(3).with_member(2)),
+        );
+
+        let mut members = Vec::new();
+        let changes = Rc::new(RefCell::new(Vec::new()));
+
+        for (id, name) in &[(3, "carol"), (2, "bob")] {
+            let discord = hub.member(MockState::new().with_current_user(*id, name, "0000"));
+
+            let mut migration = HostMigration::new(HostPolicy::LowestUserID);
+            migration.track(42);
+            migration.update(&discord).unwrap();
+            assert_eq!(migration.host(42), Some(1));
+
+            let changes = changes.clone();
+            migration.on_host_changed(move |lobby_id, old, new| {
+                changes.borrow_mut().push((lobby_id, old, new))
+            });
+
+            members.push((discord, migration));
+        }
+
+        members[0].0.mock_fail(
+            "lobbies.update_lobby",
+            Error::ServiceUnavailable,
+            failed_handovers,
+        );
+
+        // The SDK hands the lobby to carol, who has been there the longest
+        drop(alice);
+
+        for _ in 0..2 + 2 * failed_handovers {
+            for (discord, migration) in &mut members {
+                discord.run_callbacks().unwrap();
+
+                for event in discord.drain_events() {
+                    migration.handle_event(&event);
+                }
+
+                migration.update(discord).unwrap();
+            }
+        }
+
+        assert_eq!(*changes.borrow(), [(42, 1, 2), (42, 1, 2)]);
+
+        for (discord, migration) in &members {
+            assert_eq!(discord.lobby(42).unwrap().owner_id(), 2);
+            assert_eq!(migration.host(42), Some(2));
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use crate::UserID;
+
+/// How [`HostMigration`](struct.HostMigration.html) elects the new owner of a lobby
+///
+/// Every member must use the same policy, so that they all elect the same member.
+#[derive(Clone, Copy, Debug)]
+pub enum HostPolicy {
+    /// The first member, in the order the SDK lists them
+    EarliestMember,
+
+    /// The member with the lowest user ID
+    LowestUserID,
+
+    /// A function of the remaining members, in the order the SDK lists them
+    ///
+    /// The members are never empty, and the function must return one of them.
+    Custom(fn(&[UserID]) -> UserID),
+}
+
+// `#[default]` on enum variants requires Rust 1.62
+#[allow(clippy::derivable_impls)]
+impl Default for HostPolicy {
+    fn default() -> Self {
+        Self::EarliestMember
+    }
+}
+
+impl HostPolicy {
+    pub(crate) fn elect(self, members: &[UserID]) -> Option<UserID> {
+        if members.is_empty() {
+            return None;
+        }
+
+        Some(match self {
+            Self::EarliestMember => members[0],
+            Self::LowestUserID => *members.iter().min().unwrap(),
+            Self::Custom(elect) => elect(members),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::HostPolicy;
+
+    #[test]
+    fn elect() {
+        assert_eq!(HostPolicy::EarliestMember.elect(&[3, 1, 2]), Some(3));
+        assert_eq!(HostPolicy::LowestUserID.elect(&[3, 1, 2]), Some(1));
+        assert_eq!(
+            HostPolicy::Custom(|members| members[2]).elect(&[3, 1, 2]),
+            Some(2)
+        );
+
+        assert_eq!(HostPolicy::EarliestMember.elect(&[]), None);
+        assert_eq!(HostPolicy::Custom(|members| members[0]).elect(&[]), None);
+    }
+}
@@ -42,3 +42,37 @@ impl std::fmt::Debug for ImageHandle {
             .finish()
     }
 }
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for ImageHandle {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("ImageHandle", 3)?;
+        state.serialize_field("kind", &self.kind())?;
+        state.serialize_field("id", &self.id())?;
+        state.serialize_field("size", &self.size())?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ImageHandle {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "ImageHandle")]
+        struct Fields {
+            kind: ImageKind,
+            id: Snowflake,
+            size: u32,
+        }
+
+        let fields = Fields::deserialize(deserializer)?;
+
+        Ok(Self(sys::DiscordImageHandle {
+            type_: fields.kind.into(),
+            id: fields.id,
+            size: fields.size,
+        }))
+    }
+}
@@ -60,3 +60,39 @@ impl std::fmt::Debug for InputMode {
             .finish()
     }
 }
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for InputMode {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("InputMode", 2)?;
+        state.serialize_field("kind", &self.kind())?;
+        state.serialize_field("shortcut", &self.shortcut())?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for InputMode {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use crate::utils::deserialize_charbuf;
+
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "InputMode")]
+        struct Fields {
+            kind: InputModeKind,
+            shortcut: String,
+        }
+
+        let fields = Fields::deserialize(deserializer)?;
+        let mut raw = sys::DiscordInputMode {
+            type_: fields.kind.into(),
+            ..sys::DiscordInputMode::default()
+        };
+
+        deserialize_charbuf(&mut raw.shortcut, &fields.shortcut)?;
+
+        Ok(Self(raw))
+    }
+}
@@ -39,6 +39,16 @@
 //!
 //! # Features:
 //!
+//! The `link`, `mock`, `image` and `futures` features build with Rust 1.37.
+//! The others depend on crates that require a newer Rust, and are only tested on stable:
+//!
+//! - `serde`, `bincode` and `deflate`: recent releases of `serde` and `flate2` require a newer
+//!   Rust, older releases can be pinned with `cargo update --precise`
+//! - `async-std`: Rust 1.39 and up
+//! - `tokio`: Rust 1.45 and up
+//! - `encryption`: Rust 1.56 and up
+//!
+//!
 //! ### `link`
 //!
 //! Enabled by default, delegates to `discord_game_sdk_sys/link`.
@@ -70,6 +80,69 @@
 //! Provides a conversion from our `Image` to `image::RgbaImage`.
 //!
 //!
+//! ### [`serde`](https://docs.rs/serde)
+//!
+//! Optional crate.
+//!
+//! Provides `Serialize` and `Deserialize` for the data types, such as `Activity`, `User` or
+//! `Lobby`, for the kind enums and for `Event`. Data types are represented by their fields as
+//! returned by their getters. Deserialization fails on strings that do not fit in the SDK's buffers.
+//!
+//!
+//! ### [`bincode`](https://docs.rs/bincode)
+//!
+//! Optional crate, enables `serde`.
+//!
+//! Provides `Bincode`, which encodes any `Serialize` and `Deserialize` type for a `TypedChannel`.
+//!
+//!
+//! ### `deflate`
+//!
+//! Optional crate [`flate2`](https://docs.rs/flate2).
+//!
+//! Provides `StorageCodec::deflate`, which compresses files written through a `StorageCodec`.
+//!
+//!
+//! ### `encryption`
+//!
+//! Optional crate [`chacha20poly1305`](https://docs.rs/chacha20poly1305).
+//!
+//! Provides `StorageCodec::encrypt`, which encrypts and authenticates files written through a
+//! `StorageCodec` with a key of the application's choosing.
+//!
+//!
+//! ### `mock`
+//!
+//! Provides `Discord::mock`, an in-process stand-in for the SDK that implements every manager,
+//! and `MockState` to seed it with users, lobbies, files and such.
+//!
+//! Several instances can share lobbies and exchange messages through a `MockHub`.
+//!
+//! Combined with `default-features = false`, this allows running tests without the SDK library
+//! or a Discord client, in CI for example:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! discord_game_sdk = { version = "1.0.1", default-features = false, features = ["mock"] }
+//! ```
+//!
+//!
+//! ### `futures`
+//!
+//! Provides an `_async` counterpart to every method that takes a callback, returning a
+//! `CallbackFuture` with an owned output, which resolves during `run_callbacks`.
+//!
+//! Also provides `Discord::event_stream`, a `Stream` of the events queued by an `EventQueue`.
+//!
+//!
+//! ### `tokio`, `async-std`
+//!
+//! Optional crates, enable `futures`.
+//!
+//! Provides `DiscordDriver`, which owns a `Discord` on a single thread and runs its callbacks at
+//! a configurable tick rate on the given runtime.
+//!
+//!
 //! # Safety
 //!
 //! This crate relies on the SDK to provide correct data and behavior:
@@ -119,18 +192,37 @@ mod action;
 mod activity;
 mod activity_kind;
 mod aliases;
+#[cfg(feature = "bincode")]
+mod bincode;
+#[cfg(feature = "futures")]
+mod callback_future;
 mod cast;
+mod channel_stats;
 mod comparison;
 mod create_flags;
+mod decode;
 mod discord;
+#[cfg(any(feature = "tokio", feature = "async-std"))]
+mod discord_driver;
+mod discord_handle;
 mod distance;
+mod encode;
+mod endpoint;
 mod entitlement;
 mod entitlement_kind;
 mod error;
+mod event;
 mod event_handler;
+mod event_queue;
+#[cfg(feature = "futures")]
+mod event_stream;
 pub(crate) mod events;
 mod fetch_kind;
 mod file_stat;
+mod framed_message;
+mod framing;
+mod host_migration;
+mod host_policy;
 mod image;
 mod image_handle;
 mod image_kind;
@@ -138,21 +230,39 @@ mod input_mode;
 mod input_mode_kind;
 pub(crate) mod iter;
 mod lobby;
+mod lobby_cache;
+mod lobby_change;
 mod lobby_kind;
 mod lobby_member_transaction;
+mod lobby_metadata;
+mod lobby_snapshot;
 mod lobby_transaction;
+mod matchmaker;
+mod matchmaking_step;
+mod member_snapshot;
+mod metadata_decoder;
+mod metadata_encoder;
+mod metadata_value;
+mod network_stats;
 mod oauth2_token;
+mod peer_mesh;
 mod premium_kind;
 mod presence;
 mod relationship;
 mod relationship_kind;
 mod reliability;
 mod request_reply;
+mod save_slots;
 mod search_query;
+mod send_queue;
 mod sku;
 mod sku_kind;
 mod status;
+mod storage_codec;
+mod storage_reader;
+mod storage_writer;
 mod to_result;
+mod typed_channel;
 mod user;
 mod user_achievement;
 mod user_flags;
@@ -166,6 +276,7 @@ mod methods {
     mod applications;
     mod images;
     mod lobbies;
+    mod matchmaking;
     mod networking;
     mod overlay;
     mod relationships;
@@ -175,11 +286,28 @@ mod methods {
     mod voice;
 
     mod callback;
+    mod event_queue;
+    mod owned;
+
+    #[cfg(feature = "futures")]
+    mod futures;
 }
 
-#[cfg(test)]
+#[cfg(feature = "bincode")]
+pub use self::bincode::Bincode;
+
+#[cfg(feature = "futures")]
+pub use self::{callback_future::CallbackFuture, event_stream::EventStream};
+
+#[cfg(any(feature = "tokio", feature = "async-std"))]
+pub use self::discord_driver::DiscordDriver;
+
+#[cfg(any(test, feature = "mock"))]
 mod mock;
 
+#[cfg(any(test, feature = "mock"))]
+pub use self::mock::{MockHub, MockLobby, MockOrder, MockState};
+
 pub(crate) use discord_game_sdk_sys as sys;
 
 pub use self::{
@@ -188,36 +316,65 @@ pub use self::{
     activity_kind::ActivityKind,
     aliases::*,
     cast::Cast,
+    channel_stats::ChannelStats,
     comparison::Comparison,
     create_flags::CreateFlags,
+    decode::Decode,
     discord::Discord,
+    discord_handle::DiscordHandle,
     distance::Distance,
+    encode::Encode,
+    endpoint::Endpoint,
     entitlement::Entitlement,
     entitlement_kind::EntitlementKind,
     error::{Error, Result},
+    event::Event,
     event_handler::EventHandler,
+    event_queue::EventQueue,
     fetch_kind::FetchKind,
     file_stat::FileStat,
+    framed_message::FramedMessage,
+    framing::Framing,
+    host_migration::HostMigration,
+    host_policy::HostPolicy,
     image::Image,
     image_handle::ImageHandle,
     image_kind::ImageKind,
     input_mode::InputMode,
     input_mode_kind::InputModeKind,
     lobby::Lobby,
+    lobby_cache::LobbyCache,
+    lobby_change::LobbyChange,
     lobby_kind::LobbyKind,
     lobby_member_transaction::LobbyMemberTransaction,
+    lobby_metadata::LobbyMetadata,
+    lobby_snapshot::LobbySnapshot,
     lobby_transaction::LobbyTransaction,
+    matchmaker::Matchmaker,
+    matchmaking_step::MatchmakingStep,
+    member_snapshot::MemberSnapshot,
+    metadata_decoder::MetadataDecoder,
+    metadata_encoder::MetadataEncoder,
+    metadata_value::MetadataValue,
+    network_stats::NetworkStats,
     oauth2_token::OAuth2Token,
+    peer_mesh::PeerMesh,
     premium_kind::PremiumKind,
     presence::Presence,
     relationship::Relationship,
     relationship_kind::RelationshipKind,
     reliability::Reliability,
     request_reply::RequestReply,
+    save_slots::SaveSlots,
     search_query::SearchQuery,
+    send_queue::SendQueue,
     sku::Sku,
     sku_kind::SkuKind,
     status::Status,
+    storage_codec::StorageCodec,
+    storage_reader::StorageReader,
+    storage_writer::StorageWriter,
+    typed_channel::TypedChannel,
     user::User,
     user_achievement::UserAchievement,
     user_flags::UserFlags,
@@ -51,3 +51,72 @@ impl std::fmt::Debug for Lobby {
             .finish()
     }
 }
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Lobby {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("Lobby", 6)?;
+        state.serialize_field("id", &self.id())?;
+        state.serialize_field("kind", &self.kind())?;
+        state.serialize_field("owner_id", &self.owner_id())?;
+        state.serialize_field("secret", &self.secret())?;
+        state.serialize_field("capacity", &self.capacity())?;
+        state.serialize_field("locked", &self.locked())?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Lobby {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use crate::utils::deserialize_charbuf;
+
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "Lobby")]
+        struct Fields {
+            id: LobbyID,
+            kind: LobbyKind,
+            owner_id: UserID,
+            secret: String,
+            capacity: u32,
+            locked: bool,
+        }
+
+        let fields = Fields::deserialize(deserializer)?;
+        let mut raw = sys::DiscordLobby {
+            id: fields.id,
+            type_: fields.kind.into(),
+            owner_id: fields.owner_id,
+            capacity: fields.capacity,
+            locked: fields.locked,
+            ..sys::DiscordLobby::default()
+        };
+
+        deserialize_charbuf(&mut raw.secret, &fields.secret)?;
+
+        Ok(Self(raw))
+    }
+}
+
+#[cfg(all(test, feature = "bincode"))]
+mod tests {
+    use crate::{sys, utils::write_charbuf, Bincode, Decode, Encode, Lobby};
+
+    #[test]
+    fn serde() {
+        let mut lobby = Lobby(sys::DiscordLobby {
+            id: 42,
+            type_: sys::DiscordLobbyType_Public,
+            owner_id: 7,
+            capacity: 4,
+            locked: true,
+            ..sys::DiscordLobby::default()
+        });
+        write_charbuf(&mut lobby.0.secret, "secret");
+
+        let bytes = Bincode(lobby.clone()).encode().unwrap();
+        assert_eq!(Bincode::decode(&bytes), Ok(Bincode(lobby)));
+    }
+}
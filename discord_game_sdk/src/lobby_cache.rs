@@ -0,0 +1,346 @@
+use crate::{Discord, Error, Event, LobbyChange, LobbyID, LobbySnapshot, Result, UserID};
+use std::collections::{BTreeSet, HashMap};
+
+/// Owned copies of lobbies, kept up to date from lobby events
+///
+/// Lobby events only mark lobbies as stale, they are read from the SDK on the next
+/// [`refresh`](#method.refresh), usually right after
+/// [`run_callbacks`](struct.Discord.html#method.run_callbacks).
+/// Each refresh records what changed as [`LobbyChange`](enum.LobbyChange.html)s,
+/// until they are taken with [`drain_changes`](#method.drain_changes).
+///
+/// The SDK does not fire events for the current user creating, joining or leaving a lobby,
+/// those must be reported with [`track`](#method.track) and [`untrack`](#method.untrack).
+///
+/// ```rust
+/// # use discord_game_sdk::*;
+/// # fn example(mut discord: Discord<'_, EventQueue>, lobby_id: LobbyID) -> Result<()> {
+/// let mut cache = LobbyCache::new();
+/// cache.track(lobby_id);
+///
+/// loop {
+///     discord.run_callbacks()?;
+///
+///     for event in discord.drain_events() {
+///         cache.handle_event(&event);
+///     }
+///
+///     cache.refresh(&discord)?;
+///
+///     for change in cache.drain_changes() {
+///         if let LobbyChange::MemberConnected { member_id, .. } = change {
+///             println!("{} joined", member_id);
+///         }
+///     }
+/// #   break;
+/// }
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct LobbyCache {
+    lobbies: HashMap<LobbyID, LobbySnapshot>,
+    stale: BTreeSet<LobbyID>,
+    changes: Vec<LobbyChange>,
+}
+
+impl LobbyCache {
+    /// An empty cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A lobby of the cache
+    pub fn lobby(&self, lobby_id: LobbyID) -> Option<&LobbySnapshot> {
+        self.lobbies.get(&lobby_id)
+    }
+
+    /// All lobbies of the cache, in no particular order
+    pub fn lobbies(&self) -> impl '_ + Iterator<Item = &LobbySnapshot> {
+        self.lobbies.values()
+    }
+
+    /// Adds a lobby to the cache on the next refresh, or reads it again if it already is
+    pub fn track(&mut self, lobby_id: LobbyID) {
+        let _ = self.stale.insert(lobby_id);
+    }
+
+    /// Removes a lobby from the cache
+    pub fn untrack(&mut self, lobby_id: LobbyID) {
+        let _ = self.stale.remove(&lobby_id);
+
+        if self.lobbies.remove(&lobby_id).is_some() {
+            self.changes.push(LobbyChange::Removed { lobby_id });
+        }
+    }
+
+    /// Marks the lobby of a lobby event as stale, ignores other events
+    pub fn handle_event(&mut self, event: &Event) {
+        match *event {
+            Event::LobbyUpdate { lobby_id } => self.on_lobby_update(lobby_id),
+            Event::LobbyDelete { lobby_id, reason } => self.on_lobby_delete(lobby_id, reason),
+            Event::MemberConnect {
+                lobby_id,
+                member_id,
+            } => self.on_member_connect(lobby_id, member_id),
+            Event::MemberUpdate {
+                lobby_id,
+                member_id,
+            } => self.on_member_update(lobby_id, member_id),
+            Event::MemberDisconnect {
+                lobby_id,
+                member_id,
+            } => self.on_member_disconnect(lobby_id, member_id),
+            _ => {}
+        }
+    }
+
+    /// To be called from [`EventHandler::on_lobby_update`](trait.EventHandler.html#method.on_lobby_update)
+    pub fn on_lobby_update(&mut self, lobby_id: LobbyID) {
+        self.track(lobby_id);
+    }
+
+    /// To be called from [`EventHandler::on_lobby_delete`](trait.EventHandler.html#method.on_lobby_delete)
+    pub fn on_lobby_delete(&mut self, lobby_id: LobbyID, _reason: u32) {
+        self.untrack(lobby_id);
+    }
+
+    /// To be called from [`EventHandler::on_member_connect`](trait.EventHandler.html#method.on_member_connect)
+    pub fn on_member_connect(&mut self, lobby_id: LobbyID, _member_id: UserID) {
+        self.track(lobby_id);
+    }
+
+    /// To be called from [`EventHandler::on_member_update`](trait.EventHandler.html#method.on_member_update)
+    pub fn on_member_update(&mut self, lobby_id: LobbyID, _member_id: UserID) {
+        self.track(lobby_id);
+    }
+
+    /// To be called from [`EventHandler::on_member_disconnect`](trait.EventHandler.html#method.on_member_disconnect)
+    pub fn on_member_disconnect(&mut self, lobby_id: LobbyID, _member_id: UserID) {
+        self.track(lobby_id);
+    }
+
+    /// Reads the stale lobbies from the SDK and records what changed
+    ///
+    /// Lobbies the SDK no longer knows about are removed.
+    ///
+    /// ## Errors
+    ///
+    /// Lobbies that could not be read stay stale, and are read again on the next refresh.
+    /// The other lobbies are still read, and the first error is returned.
+    pub fn refresh<E>(&mut self, discord: &Discord<'_, E>) -> Result<()> {
+        let mut result = Ok(());
+
+        for lobby_id in self.stale.iter().cloned().collect::<Vec<_>>() {
+            if let Err(error) = self.refresh_lobby(discord, lobby_id) {
+                if result.is_ok() {
+                    result = Err(error);
+                }
+            }
+        }
+
+        result
+    }
+
+    fn refresh_lobby<E>(&mut self, discord: &Discord<'_, E>, lobby_id: LobbyID) -> Result<()> {
+        let snapshot = match discord.lobby(lobby_id) {
+            Ok(lobby) => LobbySnapshot::fetch(discord, lobby)?,
+
+            Err(Error::NotFound) => {
+                self.untrack(lobby_id);
+                return Ok(());
+            }
+
+            Err(error) => return Err(error),
+        };
+
+        let _ = self.stale.remove(&lobby_id);
+
+        match self.lobbies.insert(lobby_id, snapshot) {
+            Some(old) => diff(&old, &self.lobbies[&lobby_id], &mut self.changes),
+            None => self.changes.push(LobbyChange::Added { lobby_id }),
+        }
+
+        Ok(())
+    }
+
+    /// Takes the changes recorded since the last call
+    pub fn drain_changes(&mut self) -> std::vec::Drain<'_, LobbyChange> {
+        self.changes.drain(..)
+    }
+}
+
+fn diff(old: &LobbySnapshot, new: &LobbySnapshot, changes: &mut Vec<LobbyChange>) {
+    let lobby_id = new.lobby.id();
+
+    if old.lobby != new.lobby {
+        changes.push(LobbyChange::Updated { lobby_id });
+    }
+
+    diff_metadata(&old.metadata, &new.metadata, |key, old, new| {
+        changes.push(LobbyChange::MetadataChanged {
+            lobby_id,
+            key,
+            old,
+            new,
+        })
+    });
+
+    let member_ids = old
+        .members
+        .keys()
+        .chain(new.members.keys())
+        .collect::<BTreeSet<_>>();
+
+    for &member_id in member_ids {
+        match (old.members.get(&member_id), new.members.get(&member_id)) {
+            (None, Some(_)) => changes.push(LobbyChange::MemberConnected {
+                lobby_id,
+                member_id,
+            }),
+
+            (Some(_), None) => changes.push(LobbyChange::MemberDisconnected {
+                lobby_id,
+                member_id,
+            }),
+
+            (Some(old), Some(new)) => {
+                diff_metadata(&old.metadata, &new.metadata, |key, old, new| {
+                    changes.push(LobbyChange::MemberMetadataChanged {
+                        lobby_id,
+                        member_id,
+                        key,
+                        old,
+                        new,
+                    })
+                })
+            }
+
+            (None, None) => unreachable!(),
+        }
+    }
+}
+
+fn diff_metadata(
+    old: &HashMap<String, String>,
+    new: &HashMap<String, String>,
+    mut changed: impl FnMut(String, Option<String>, Option<String>),
+) {
+    let keys = old.keys().chain(new.keys()).collect::<BTreeSet<_>>();
+
+    for key in keys {
+        let (old, new) = (old.get(key), new.get(key));
+
+        if old != new {
+            changed(key.clone(), old.cloned(), new.cloned());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Discord, Error, EventQueue, LobbyCache, LobbyChange, LobbyMemberTransaction, MockHub,
+        MockLobby, MockState,
+    };
+
+    #[test]
+    fn follows_members() {
+        let hub = MockHub::new();
+        let lobby = MockLobby::new(42, 1);
+
+        let mut alice = hub.member(
+            MockState::new()
+                .with_current_user(1, "alice", "0001")
+                .with_lobby(&lobby),
+        );
+
+        let mut bob = hub.member(MockState::new().with_current_user(2, "bob", "0002"));
+
+        let mut cache = LobbyCache::new();
+        cache.track(42);
+        cache.refresh(&alice).unwrap();
+        assert_eq!(
+            cache.drain_changes().collect::<Vec<_>>(),
+            [LobbyChange::Added { lobby_id: 42 }]
+        );
+
+        fn tick(
+            alice: &mut Discord<'_, EventQueue>,
+            bob: &mut Discord<'_, EventQueue>,
+            cache: &mut LobbyCache,
+        ) -> Vec<LobbyChange> {
+            bob.run_callbacks().unwrap();
+            alice.run_callbacks().unwrap();
+
+            for event in alice.drain_events() {
+                cache.handle_event(&event);
+            }
+
+            cache.refresh(alice).unwrap();
+            cache.drain_changes().collect()
+        }
+
+        bob.connect_lobby(42, "mock-secret-42", |_, res| assert!(res.is_ok()));
+        assert_eq!(
+            tick(&mut alice, &mut bob, &mut cache),
+            [LobbyChange::MemberConnected {
+                lobby_id: 42,
+                member_id: 2
+            }]
+        );
+        assert_eq!(cache.lobby(42).unwrap().members().len(), 2);
+
+        bob.update_member(
+            42,
+            2,
+            LobbyMemberTransaction::new().add_metadata("ready".into(), "1".into()),
+            |_, res| assert!(res.is_ok()),
+        );
+        assert_eq!(
+            tick(&mut alice, &mut bob, &mut cache),
+            [LobbyChange::MemberMetadataChanged {
+                lobby_id: 42,
+                member_id: 2,
+                key: "ready".to_string(),
+                old: None,
+                new: Some("1".to_string()),
+            }]
+        );
+
+        bob.disconnect_lobby(42, |_, res| assert!(res.is_ok()));
+        assert_eq!(
+            tick(&mut alice, &mut bob, &mut cache),
+            [LobbyChange::MemberDisconnected {
+                lobby_id: 42,
+                member_id: 2
+            }]
+        );
+    }
+
+    #[test]
+    fn skips_failing_lobbies() {
+        let discord = Discord::<()>::with_mock_state(
+            MockState::new()
+                .with_current_user(1, "alice", "0001")
+                .with_lobby(&MockLobby::new(41, 1))
+                .with_lobby(&MockLobby::new(42, 1)),
+        );
+
+        let mut cache = LobbyCache::new();
+        cache.track(41);
+        cache.track(42);
+
+        discord.mock_fail("lobbies.get_lobby", Error::ServiceUnavailable, 1);
+        assert_eq!(cache.refresh(&discord), Err(Error::ServiceUnavailable));
+        assert_eq!(
+            cache.drain_changes().collect::<Vec<_>>(),
+            [LobbyChange::Added { lobby_id: 42 }]
+        );
+
+        cache.refresh(&discord).unwrap();
+        assert_eq!(
+            cache.drain_changes().collect::<Vec<_>>(),
+            [LobbyChange::Added { lobby_id: 41 }]
+        );
+    }
+}
@@ -0,0 +1,65 @@
+use crate::{LobbyID, UserID};
+
+/// What changed in a lobby between two refreshes of a [`LobbyCache`](struct.LobbyCache.html)
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum LobbyChange {
+    /// The lobby is now in the cache
+    Added {
+        /// The lobby that was added
+        lobby_id: LobbyID,
+    },
+
+    /// The lobby was deleted, was left, or is no longer tracked
+    Removed {
+        /// The lobby that was removed
+        lobby_id: LobbyID,
+    },
+
+    /// The kind, owner, secret, capacity or lock of the lobby changed
+    Updated {
+        /// The lobby that was updated
+        lobby_id: LobbyID,
+    },
+
+    /// A metadata value of the lobby was set, changed or removed
+    MetadataChanged {
+        /// The lobby of the metadata
+        lobby_id: LobbyID,
+        /// The key of the value
+        key: String,
+        /// The previous value, `None` if it was just set
+        old: Option<String>,
+        /// The current value, `None` if it was removed
+        new: Option<String>,
+    },
+
+    /// A user joined the lobby
+    MemberConnected {
+        /// The lobby that was joined
+        lobby_id: LobbyID,
+        /// The user that joined
+        member_id: UserID,
+    },
+
+    /// A user left the lobby
+    MemberDisconnected {
+        /// The lobby that was left
+        lobby_id: LobbyID,
+        /// The user that left
+        member_id: UserID,
+    },
+
+    /// A metadata value of a member was set, changed or removed
+    MemberMetadataChanged {
+        /// The lobby of the member
+        lobby_id: LobbyID,
+        /// The member
+        member_id: UserID,
+        /// The key of the value
+        key: String,
+        /// The previous value, `None` if it was just set
+        old: Option<String>,
+        /// The current value, `None` if it was removed
+        new: Option<String>,
+    },
+}
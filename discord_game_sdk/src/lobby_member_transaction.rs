@@ -1,4 +1,4 @@
-use crate::{sys, to_result::ToResult, Result};
+use crate::{sys, to_result::ToResult, LobbyMetadata, MetadataEncoder, Result};
 use std::collections::HashMap;
 
 /// Lobby Member Transaction
@@ -38,6 +38,19 @@ impl LobbyMemberTransaction {
         self
     }
 
+    /// Sets or deletes the metadata values of a [`LobbyMetadata`](trait.LobbyMetadata.html)
+    /// for the user
+    ///
+    /// ## Errors
+    ///
+    /// [`Error::InvalidPayload`](enum.Error.html#variant.InvalidPayload) if a key is empty,
+    /// or if a key or value is too long or contains a nul byte.
+    /// Nothing is added to the transaction then.
+    pub fn add_typed_metadata(&mut self, metadata: &impl LobbyMetadata) -> Result<&mut Self> {
+        MetadataEncoder::encode(metadata, &mut self.metadata)?;
+        Ok(self)
+    }
+
     /// Deletes metadata value under a given key for the user
     ///
     /// ## Performance
@@ -0,0 +1,180 @@
+use crate::{MetadataDecoder, MetadataEncoder, Result};
+
+/// A Rust type stored as lobby or member metadata, one key per field
+///
+/// Typed metadata is written with
+/// [`LobbyTransaction::add_typed_metadata`](struct.LobbyTransaction.html#method.add_typed_metadata) and
+/// [`LobbyMemberTransaction::add_typed_metadata`](struct.LobbyMemberTransaction.html#method.add_typed_metadata),
+/// and read with [`Discord::lobby_typed_metadata`](struct.Discord.html#method.lobby_typed_metadata),
+/// [`Discord::lobby_member_typed_metadata`](struct.Discord.html#method.lobby_member_typed_metadata)
+/// or from a [`LobbySnapshot`](struct.LobbySnapshot.html).
+///
+/// ```rust
+/// # use discord_game_sdk::*;
+/// struct GameInfo {
+///     map: String,
+///     max_level: u32,
+///     ranked: bool,
+///     region: Option<String>,
+/// }
+///
+/// impl LobbyMetadata for GameInfo {
+///     fn encode(&self, encoder: &mut MetadataEncoder) {
+///         encoder
+///             .set("map", &self.map)
+///             .set("max_level", &self.max_level)
+///             .set("ranked", &self.ranked)
+///             .set_option("region", &self.region);
+///     }
+///
+///     fn decode(decoder: &MetadataDecoder<'_>) -> Result<Self> {
+///         Ok(Self {
+///             map: decoder.get("map")?,
+///             max_level: decoder.get("max_level")?,
+///             ranked: decoder.get("ranked")?,
+///             region: decoder.get_option("region")?,
+///         })
+///     }
+/// }
+///
+/// # fn example(discord: Discord<'_, ()>, lobby_id: LobbyID) -> Result<()> {
+/// let info = GameInfo {
+///     map: "dust".to_string(),
+///     max_level: 30,
+///     ranked: true,
+///     region: None,
+/// };
+///
+/// discord.update_lobby(
+///     lobby_id,
+///     LobbyTransaction::new().add_typed_metadata(&info)?,
+///     |discord, result| {
+///         if let Err(error) = result {
+///             eprintln!("failed to update lobby: {}", error);
+///         }
+///     },
+/// );
+///
+/// let info = discord.lobby_typed_metadata::<GameInfo>(lobby_id)?;
+/// # Ok(()) }
+/// ```
+pub trait LobbyMetadata: Sized {
+    /// Writes every field under its key
+    fn encode(&self, encoder: &mut MetadataEncoder);
+
+    /// Reads every field from its key
+    ///
+    /// ## Errors
+    ///
+    /// Should fail with the error of [`MetadataDecoder`](struct.MetadataDecoder.html).
+    fn decode(decoder: &MetadataDecoder<'_>) -> Result<Self>;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Discord, Error, LobbyMemberTransaction, LobbyMetadata, LobbyTransaction, MetadataDecoder,
+        MetadataEncoder, MockLobby, MockState, Result,
+    };
+
+    #[derive(Debug, PartialEq)]
+    struct Info {
+        map: String,
+        level: i32,
+        ranked: bool,
+        region: Option<String>,
+    }
+
+    impl LobbyMetadata for Info {
+        fn encode(&self, encoder: &mut MetadataEncoder) {
+            encoder
+                .set("map", &self.map)
+                .set("level", &self.level)
+                .set("ranked", &self.ranked)
+                .set_option("region", &self.region);
+        }
+
+        fn decode(decoder: &MetadataDecoder<'_>) -> Result<Self> {
+            Ok(Self {
+                map: decoder.get("map")?,
+                level: decoder.get("level")?,
+                ranked: decoder.get("ranked")?,
+                region: decoder.get_option("region")?,
+            })
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut discord = Discord::<()>::with_mock_state(
+            MockState::new()
+                .with_current_user(1, "alice", "0001")
+                .with_lobby(MockLobby::new(42, 1).with_metadata("region", "eu")),
+        );
+
+        let info = Info {
+            map: "dust".to_string(),
+            level: -3,
+            ranked: true,
+            region: None,
+        };
+
+        discord.update_lobby(
+            42,
+            LobbyTransaction::new().add_typed_metadata(&info).unwrap(),
+            |_, res| assert!(res.is_ok()),
+        );
+        discord.update_member(
+            42,
+            1,
+            LobbyMemberTransaction::new()
+                .add_typed_metadata(&info)
+                .unwrap(),
+            |_, res| assert!(res.is_ok()),
+        );
+        discord.run_callbacks().unwrap();
+
+        assert_eq!(discord.lobby_metadata(42, "ranked").unwrap(), "1");
+        assert_eq!(discord.lobby_typed_metadata::<Info>(42).unwrap(), info);
+        assert_eq!(
+            discord.lobby_member_typed_metadata::<Info>(42, 1).unwrap(),
+            info
+        );
+
+        discord.update_lobby(
+            42,
+            LobbyTransaction::new().add_metadata("level".into(), "high".into()),
+            |_, res| assert!(res.is_ok()),
+        );
+        discord.run_callbacks().unwrap();
+
+        assert_eq!(
+            discord.lobby_typed_metadata::<Info>(42),
+            Err(Error::InvalidPayload)
+        );
+        assert_eq!(
+            discord.lobby_member_typed_metadata::<Info>(42, 2),
+            Err(Error::NotFound)
+        );
+    }
+
+    #[test]
+    fn checks_limits() {
+        let mut info = Info {
+            map: "x".repeat(4096),
+            level: 0,
+            ranked: false,
+            region: None,
+        };
+
+        assert!(LobbyTransaction::new().add_typed_metadata(&info).is_err());
+
+        info.map = "x".repeat(4095);
+        assert!(LobbyTransaction::new().add_typed_metadata(&info).is_ok());
+
+        info.map = "du\0st".to_string();
+        assert!(LobbyMemberTransaction::new()
+            .add_typed_metadata(&info)
+            .is_err());
+    }
+}
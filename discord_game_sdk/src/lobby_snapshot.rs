@@ -0,0 +1,71 @@
+use crate::{Discord, Lobby, LobbyMetadata, MemberSnapshot, MetadataDecoder, Result, UserID};
+use std::collections::HashMap;
+
+/// Owned copy of a lobby, its metadata and its members, kept by a
+/// [`LobbyCache`](struct.LobbyCache.html)
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LobbySnapshot {
+    pub(crate) lobby: Lobby,
+    pub(crate) metadata: HashMap<String, String>,
+    pub(crate) members: HashMap<UserID, MemberSnapshot>,
+}
+
+impl LobbySnapshot {
+    /// Reads the whole lobby from the SDK
+    pub(crate) fn fetch<E>(discord: &Discord<'_, E>, lobby: Lobby) -> Result<Self> {
+        let lobby_id = lobby.id();
+        let metadata = discord
+            .iter_lobby_metadata(lobby_id)?
+            .collect::<Result<_>>()?;
+
+        let mut members = HashMap::new();
+
+        for user_id in discord.iter_lobby_member_ids(lobby_id)? {
+            let user_id = user_id?;
+            let metadata = discord
+                .iter_lobby_member_metadata(lobby_id, user_id)?
+                .collect::<Result<_>>()?;
+
+            let _ = members.insert(user_id, MemberSnapshot { user_id, metadata });
+        }
+
+        Ok(Self {
+            lobby,
+            metadata,
+            members,
+        })
+    }
+
+    /// The lobby, as returned by [`Discord::lobby`](struct.Discord.html#method.lobby)
+    pub fn lobby(&self) -> &Lobby {
+        &self.lobby
+    }
+
+    /// The metadata of the lobby
+    pub fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+
+    /// The metadata of the lobby, as a [`LobbyMetadata`](trait.LobbyMetadata.html)
+    ///
+    /// ## Errors
+    ///
+    /// [`Error::NotFound`](enum.Error.html#variant.NotFound) if a key is missing,
+    /// [`Error::InvalidPayload`](enum.Error.html#variant.InvalidPayload) if a value
+    /// could not be decoded.
+    pub fn typed_metadata<M: LobbyMetadata>(&self) -> Result<M> {
+        M::decode(&MetadataDecoder {
+            metadata: &self.metadata,
+        })
+    }
+
+    /// The members of the lobby, by user ID
+    pub fn members(&self) -> &HashMap<UserID, MemberSnapshot> {
+        &self.members
+    }
+
+    /// A member of the lobby
+    pub fn member(&self, user_id: UserID) -> Option<&MemberSnapshot> {
+        self.members.get(&user_id)
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{sys, to_result::ToResult, LobbyKind, Result, UserID};
+use crate::{sys, to_result::ToResult, LobbyKind, LobbyMetadata, MetadataEncoder, Result, UserID};
 use std::collections::HashMap;
 
 /// Lobby Transaction
@@ -66,6 +66,19 @@ impl LobbyTransaction {
         self
     }
 
+    /// Sets or deletes the metadata values of a [`LobbyMetadata`](trait.LobbyMetadata.html)
+    /// for the lobby
+    ///
+    /// ## Errors
+    ///
+    /// [`Error::InvalidPayload`](enum.Error.html#variant.InvalidPayload) if a key is empty,
+    /// or if a key or value is too long or contains a nul byte.
+    /// Nothing is added to the transaction then.
+    pub fn add_typed_metadata(&mut self, metadata: &impl LobbyMetadata) -> Result<&mut Self> {
+        MetadataEncoder::encode(metadata, &mut self.metadata)?;
+        Ok(self)
+    }
+
     /// Deletes metadata value under a given key for the lobby
     ///
     /// ## Performance
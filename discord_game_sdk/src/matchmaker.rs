@@ -0,0 +1,286 @@
+use crate::{Discord, Error, Lobby, LobbyTransaction, MatchmakingStep, Result, SearchQuery};
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    rc::Rc,
+};
+
+/// Finds a lobby to join, or creates one
+///
+/// Lobbies found by the search are tried in order, skipping locked and full ones.
+/// When every lobby rejects the user, the search is [retried](#method.retries),
+/// and when the retries are used up a lobby is created from the transaction template.
+///
+/// Used with [`Discord::matchmake`](struct.Discord.html#method.matchmake).
+///
+/// ```rust
+/// # use discord_game_sdk::*;
+/// let mut matchmaker = Matchmaker::new();
+/// matchmaker
+///     .search(
+///         SearchQuery::new()
+///             .filter_string("metadata.mode".into(), Comparison::Equal, "ranked".into())
+///             .limit(10),
+///     )
+///     .transaction(
+///         LobbyTransaction::new()
+///             .kind(LobbyKind::Public)
+///             .capacity(8)
+///             .add_metadata("mode".into(), "ranked".into()),
+///     )
+///     .retries(2);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Matchmaker {
+    pub(crate) search: SearchQuery,
+    pub(crate) transaction: LobbyTransaction,
+    pub(crate) retries: u32,
+    pub(crate) create: bool,
+}
+
+impl Matchmaker {
+    /// A matchmaker searching with an empty query, without retries,
+    /// and creating a lobby from an empty transaction
+    pub fn new() -> Self {
+        Self {
+            create: true,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the query used to search for lobbies
+    pub fn search(&mut self, search: &SearchQuery) -> &mut Self {
+        self.search = search.clone();
+        self
+    }
+
+    /// Sets the transaction used to create a lobby
+    pub fn transaction(&mut self, transaction: &LobbyTransaction) -> &mut Self {
+        self.transaction = transaction.clone();
+        self
+    }
+
+    /// Sets how many times to search again when no lobby found could be joined
+    ///
+    /// The search is retried right away, when it fails or when every lobby it found rejected
+    /// the user, as lobbies fill up and open between searches. A search failing with
+    /// [`Error::RateLimited`](enum.Error.html#variant.RateLimited) is not retried,
+    /// as a retry would hit the same limit, matchmaking fails with that error instead.
+    pub fn retries(&mut self, retries: u32) -> &mut Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Sets whether to create a lobby when no lobby could be joined,
+    /// or fail with [`Error::NotFound`](enum.Error.html#variant.NotFound)
+    pub fn create(&mut self, create: bool) -> &mut Self {
+        self.create = create;
+        self
+    }
+}
+
+type Progress<'d, E> = Box<dyn 'd + FnMut(&Discord<'d, E>, MatchmakingStep)>;
+type Callback<'d, E> = Box<dyn 'd + FnOnce(&Discord<'d, E>, Result<Lobby>)>;
+
+pub(crate) struct Run<'d, E> {
+    matchmaker: Matchmaker,
+    progress: RefCell<Progress<'d, E>>,
+    callback: Cell<Option<Callback<'d, E>>>,
+}
+
+impl<'d, E: 'd> Run<'d, E> {
+    pub(crate) fn start(
+        discord: &Discord<'d, E>,
+        matchmaker: &Matchmaker,
+        progress: impl 'd + FnMut(&Discord<'d, E>, MatchmakingStep),
+        callback: impl 'd + FnOnce(&Discord<'d, E>, Result<Lobby>),
+    ) {
+        let run = Rc::new(Self {
+            matchmaker: matchmaker.clone(),
+            progress: RefCell::new(Box::new(progress)),
+            callback: Cell::new(Some(Box::new(callback))),
+        });
+
+        Self::search(discord, run, 0);
+    }
+
+    fn report(&self, discord: &Discord<'d, E>, step: MatchmakingStep) {
+        (self.progress.borrow_mut())(discord, step)
+    }
+
+    fn finish(&self, discord: &Discord<'d, E>, result: Result<Lobby>) {
+        if let Some(callback) = self.callback.take() {
+            callback(discord, result)
+        }
+    }
+
+    fn search(discord: &Discord<'d, E>, run: Rc<Self>, attempt: u32) {
+        run.report(discord, MatchmakingStep::Searching { attempt });
+
+        let search = run.matchmaker.search.clone();
+
+        discord.lobby_search(&search, move |discord, res| match res {
+            Ok(()) => {
+                let candidates = discord
+                    .iter_lobbies()
+                    .filter_map(|lobby_id| discord.lobby(lobby_id.ok()?).ok())
+                    .filter(|lobby| !lobby.locked() && has_room(discord, lobby))
+                    .collect();
+
+                Self::connect(discord, run, candidates, attempt)
+            }
+
+            Err(error) if error != Error::RateLimited && attempt < run.matchmaker.retries => {
+                Self::search(discord, run, attempt + 1)
+            }
+
+            Err(error) => run.finish(discord, Err(error)),
+        });
+    }
+
+    fn connect(
+        discord: &Discord<'d, E>,
+        run: Rc<Self>,
+        mut candidates: VecDeque<Lobby>,
+        attempt: u32,
+    ) {
+        let lobby = match candidates.pop_front() {
+            Some(lobby) => lobby,
+            None if attempt < run.matchmaker.retries => {
+                return Self::search(discord, run, attempt + 1)
+            }
+            None if run.matchmaker.create => return Self::create(discord, run),
+            None => return run.finish(discord, Err(Error::NotFound)),
+        };
+
+        let lobby_id = lobby.id();
+        run.report(discord, MatchmakingStep::Connecting { lobby_id });
+
+        discord.connect_lobby_owned(lobby_id, lobby.secret(), move |discord, res| match res {
+            Ok(lobby) => run.finish(discord, Ok(lobby)),
+
+            // Someone else got the last slot, or the lobby changed since the search
+            Err(error @ Error::LobbyFull)
+            | Err(error @ Error::NotFound)
+            | Err(error @ Error::InvalidLobbySecret) => {
+                run.report(discord, MatchmakingStep::Rejected { lobby_id, error });
+                Self::connect(discord, run, candidates, attempt)
+            }
+
+            Err(error) => run.finish(discord, Err(error)),
+        });
+    }
+
+    fn create(discord: &Discord<'d, E>, run: Rc<Self>) {
+        run.report(discord, MatchmakingStep::Creating);
+
+        let transaction = run.matchmaker.transaction.clone();
+
+        discord.create_lobby_owned(&transaction, move |discord, res| run.finish(discord, res));
+    }
+}
+
+// Lobbies whose members cannot be counted are tried anyway, joining them tells
+fn has_room<E>(discord: &Discord<'_, E>, lobby: &Lobby) -> bool {
+    discord
+        .lobby_member_count(lobby.id())
+        .map(|count| count < lobby.capacity())
+        .unwrap_or(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Comparison, Discord, Error, LobbyKind, Matchmaker, MatchmakingStep, MockLobby, MockState,
+        SearchQuery,
+    };
+    use std::{cell::RefCell, rc::Rc};
+
+    #[test]
+    fn joins_or_creates() {
+        let public = |id, capacity| {
+            let mut lobby = MockLobby::new(id, 9);
+            lobby.with_kind(LobbyKind::Public).with_capacity(capacity);
+            lobby
+        };
+
+        let mut discord = Discord::<()>::with_mock_state(
+            MockState::new()
+                .with_current_user(1, "alice", "0001")
+                .with_lobby(&public(1, 1))
+                .with_lobby(public(2, 4).with_locked(true))
+                .with_lobby(&public(3, 4))
+                .with_lobby(&public(4, 4)),
+        );
+
+        let steps = Rc::new(RefCell::new(Vec::new()));
+        let lobby = Rc::new(RefCell::new(None));
+
+        let matchmake = |discord: &mut Discord<'_, ()>, matchmaker: &Matchmaker| {
+            let (steps_, lobby_) = (steps.clone(), lobby.clone());
+
+            discord.matchmake(
+                matchmaker,
+                move |_, step| steps_.borrow_mut().push(step),
+                move |_, res| *lobby_.borrow_mut() = Some(res.map(|lobby| lobby.id())),
+            );
+
+            while lobby.borrow().is_none() {
+                discord.run_callbacks().unwrap();
+            }
+
+            (steps.replace(Vec::new()), lobby.replace(None).unwrap())
+        };
+
+        discord.mock_fail("lobbies.connect_lobby", Error::LobbyFull, 1);
+
+        assert_eq!(
+            matchmake(&mut discord, &Matchmaker::new()),
+            (
+                vec![
+                    MatchmakingStep::Searching { attempt: 0 },
+                    MatchmakingStep::Connecting { lobby_id: 3 },
+                    MatchmakingStep::Rejected {
+                        lobby_id: 3,
+                        error: Error::LobbyFull
+                    },
+                    MatchmakingStep::Connecting { lobby_id: 4 },
+                ],
+                Ok(4)
+            )
+        );
+
+        let mut matchmaker = Matchmaker::new();
+        matchmaker
+            .search(SearchQuery::new().filter_number("capacity".into(), Comparison::GreaterThan, 4))
+            .retries(1);
+
+        let (steps, lobby) = matchmake(&mut discord, &matchmaker);
+        assert_eq!(
+            steps,
+            [
+                MatchmakingStep::Searching { attempt: 0 },
+                MatchmakingStep::Searching { attempt: 1 },
+                MatchmakingStep::Creating,
+            ]
+        );
+        assert!(lobby.is_ok());
+
+        matchmaker.create(false);
+        assert_eq!(matchmake(&mut discord, &matchmaker).1, Err(Error::NotFound));
+
+        discord.mock_fail("lobbies.search", Error::ServiceUnavailable, 1);
+        let (steps, lobby) = matchmake(&mut discord, &matchmaker);
+        assert_eq!(steps.len(), 2);
+        assert_eq!(lobby, Err(Error::NotFound));
+
+        discord.mock_fail("lobbies.search", Error::RateLimited, 1);
+        assert_eq!(
+            matchmake(&mut discord, &matchmaker),
+            (
+                vec![MatchmakingStep::Searching { attempt: 0 }],
+                Err(Error::RateLimited)
+            )
+        );
+    }
+}
@@ -0,0 +1,28 @@
+use crate::{Error, LobbyID};
+
+/// Progress of [`Discord::matchmake`](struct.Discord.html#method.matchmake)
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum MatchmakingStep {
+    /// Searching for lobbies, `attempt` starts at 0 and grows with each retry
+    Searching {
+        /// How many searches were made before this one
+        attempt: u32,
+    },
+
+    /// Joining one of the lobbies that were found
+    Connecting {
+        /// The lobby being joined
+        lobby_id: LobbyID,
+    },
+
+    /// A lobby could not be joined, the next one will be tried
+    Rejected {
+        /// The lobby that could not be joined
+        lobby_id: LobbyID,
+        /// Why it could not be joined, usually `LobbyFull`
+        error: Error,
+    },
+
+    /// No lobby could be joined, creating one from the template
+    Creating,
+}
@@ -0,0 +1,34 @@
+use crate::{LobbyMetadata, MetadataDecoder, Result, UserID};
+use std::collections::HashMap;
+
+/// Owned copy of a lobby member, kept by a [`LobbyCache`](struct.LobbyCache.html)
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MemberSnapshot {
+    pub(crate) user_id: UserID,
+    pub(crate) metadata: HashMap<String, String>,
+}
+
+impl MemberSnapshot {
+    /// The user ID of the member
+    pub fn user_id(&self) -> UserID {
+        self.user_id
+    }
+
+    /// The metadata of the member
+    pub fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+
+    /// The metadata of the member, as a [`LobbyMetadata`](trait.LobbyMetadata.html)
+    ///
+    /// ## Errors
+    ///
+    /// [`Error::NotFound`](enum.Error.html#variant.NotFound) if a key is missing,
+    /// [`Error::InvalidPayload`](enum.Error.html#variant.InvalidPayload) if a value
+    /// could not be decoded.
+    pub fn typed_metadata<M: LobbyMetadata>(&self) -> Result<M> {
+        M::decode(&MetadataDecoder {
+            metadata: &self.metadata,
+        })
+    }
+}
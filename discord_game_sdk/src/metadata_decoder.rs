@@ -0,0 +1,41 @@
+use crate::{Error, MetadataValue, Result};
+use std::collections::HashMap;
+
+/// Reads the keys and values of a [`LobbyMetadata`](trait.LobbyMetadata.html)
+#[derive(Clone, Copy, Debug)]
+pub struct MetadataDecoder<'a> {
+    pub(crate) metadata: &'a HashMap<String, String>,
+}
+
+impl<'a> MetadataDecoder<'a> {
+    /// Reads the value under a given key
+    ///
+    /// ## Errors
+    ///
+    /// [`Error::NotFound`](enum.Error.html#variant.NotFound) if there is no such key,
+    /// [`Error::InvalidPayload`](enum.Error.html#variant.InvalidPayload) if the value
+    /// could not be decoded.
+    pub fn get<V: MetadataValue>(&self, key: &str) -> Result<V> {
+        self.get_option(key)?.ok_or(Error::NotFound)
+    }
+
+    /// Reads the value under a given key, `None` if there is no such key
+    ///
+    /// ## Errors
+    ///
+    /// [`Error::InvalidPayload`](enum.Error.html#variant.InvalidPayload) if the value
+    /// could not be decoded.
+    pub fn get_option<V: MetadataValue>(&self, key: &str) -> Result<Option<V>> {
+        match self.metadata.get(key) {
+            Some(value) => V::from_metadata(value)
+                .map(Some)
+                .ok_or(Error::InvalidPayload),
+            None => Ok(None),
+        }
+    }
+
+    /// The raw keys and values
+    pub fn metadata(&self) -> &'a HashMap<String, String> {
+        self.metadata
+    }
+}
@@ -0,0 +1,74 @@
+use crate::{sys, Error, LobbyMetadata, MetadataValue, Result};
+use std::{collections::HashMap, mem::size_of};
+
+/// Collects the keys and values of a [`LobbyMetadata`](trait.LobbyMetadata.html)
+#[derive(Clone, Debug, Default)]
+pub struct MetadataEncoder {
+    pub(crate) metadata: Vec<(String, Option<String>)>,
+}
+
+impl MetadataEncoder {
+    /// Sets a value under a given key
+    pub fn set<V: MetadataValue>(&mut self, key: &str, value: &V) -> &mut Self {
+        self.metadata
+            .push((key.to_string(), Some(value.to_metadata())));
+        self
+    }
+
+    /// Sets a value under a given key, or deletes the key if there is none
+    pub fn set_option<V: MetadataValue>(&mut self, key: &str, value: &Option<V>) -> &mut Self {
+        self.metadata
+            .push((key.to_string(), value.as_ref().map(V::to_metadata)));
+        self
+    }
+
+    /// Encodes the metadata into the nul-terminated keys and values of a lobby or member
+    /// transaction, once it is checked to fit in the SDK's buffers
+    ///
+    /// ## Errors
+    ///
+    /// [`Error::InvalidPayload`](enum.Error.html#variant.InvalidPayload) if a key is empty,
+    /// or if a key or value is too long or contains a nul byte.
+    /// Nothing is added to the transaction then.
+    pub(crate) fn encode(
+        metadata: &impl LobbyMetadata,
+        transaction: &mut HashMap<String, Option<String>>,
+    ) -> Result<()> {
+        let mut encoder = Self::default();
+        metadata.encode(&mut encoder);
+
+        for (key, value) in &encoder.metadata {
+            if key.is_empty() {
+                return Err(Error::InvalidPayload);
+            }
+
+            check(key, size_of::<sys::DiscordMetadataKey>())?;
+
+            if let Some(value) = value {
+                check(value, size_of::<sys::DiscordMetadataValue>())?;
+            }
+        }
+
+        for (mut key, value) in encoder.metadata {
+            key.push('\0');
+
+            let value = value.map(|mut value| {
+                value.push('\0');
+                value
+            });
+
+            let _ = transaction.insert(key, value);
+        }
+
+        Ok(())
+    }
+}
+
+// The SDK keeps keys and values in nul-terminated buffers
+fn check(string: &str, buffer: usize) -> Result<()> {
+    if string.len() >= buffer || string.contains('\0') {
+        return Err(Error::InvalidPayload);
+    }
+
+    Ok(())
+}
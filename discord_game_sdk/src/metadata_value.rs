@@ -0,0 +1,93 @@
+use crate::Cast;
+
+/// A value that can be stored in lobby or member metadata
+///
+/// Numbers and booleans are stored so that [`SearchQuery`](struct.SearchQuery.html)
+/// can compare them with [`Cast::Number`](enum.Cast.html#variant.Number).
+///
+/// Enums can be stored by implementing this trait with their discriminant:
+///
+/// ```rust
+/// # use discord_game_sdk::*;
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// enum Mode {
+///     Casual = 0,
+///     Ranked = 1,
+/// }
+///
+/// impl MetadataValue for Mode {
+///     const CAST: Cast = Cast::Number;
+///
+///     fn to_metadata(&self) -> String {
+///         (*self as u8).to_metadata()
+///     }
+///
+///     fn from_metadata(value: &str) -> Option<Self> {
+///         match u8::from_metadata(value)? {
+///             0 => Some(Mode::Casual),
+///             1 => Some(Mode::Ranked),
+///             _ => None,
+///         }
+///     }
+/// }
+///
+/// assert_eq!(Mode::from_metadata(&Mode::Ranked.to_metadata()), Some(Mode::Ranked));
+/// ```
+pub trait MetadataValue: Sized {
+    /// How lobby searches should compare the stored value
+    const CAST: Cast;
+
+    /// Encodes the value as a metadata string
+    fn to_metadata(&self) -> String;
+
+    /// Decodes a metadata string, `None` if it is not a valid value
+    fn from_metadata(value: &str) -> Option<Self>;
+}
+
+impl MetadataValue for String {
+    const CAST: Cast = Cast::String;
+
+    fn to_metadata(&self) -> String {
+        self.clone()
+    }
+
+    fn from_metadata(value: &str) -> Option<Self> {
+        Some(value.to_string())
+    }
+}
+
+impl MetadataValue for bool {
+    const CAST: Cast = Cast::Number;
+
+    fn to_metadata(&self) -> String {
+        if *self { "1" } else { "0" }.to_string()
+    }
+
+    fn from_metadata(value: &str) -> Option<Self> {
+        match value {
+            "1" => Some(true),
+            "0" => Some(false),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! number {
+    ($($ty:ty),*) => {
+        $(
+            impl MetadataValue for $ty {
+                const CAST: Cast = Cast::Number;
+
+                fn to_metadata(&self) -> String {
+                    self.to_string()
+                }
+
+                fn from_metadata(value: &str) -> Option<Self> {
+                    value.parse().ok()
+                }
+            }
+        )*
+    };
+}
+
+number!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
@@ -192,8 +192,7 @@ impl<'d, E> Discord<'d, E> {
     pub fn iter_user_achievements(
         &self,
     ) -> impl '_
-           + Iterator<Item = Result<UserAchievement>>
-           + DoubleEndedIterator
+           + DoubleEndedIterator<Item = Result<UserAchievement>>
            + ExactSizeIterator
            + std::iter::FusedIterator
            + std::fmt::Debug {
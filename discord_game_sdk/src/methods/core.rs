@@ -2,9 +2,13 @@ use crate::{
     discord::{Discord, DiscordInner},
     events, sys,
     to_result::ToResult,
-    utils, ClientID, CreateFlags, EventHandler, Result,
+    utils, ClientID, CreateFlags, DiscordHandle, EventHandler, Result,
+};
+use std::{
+    cell::{RefCell, UnsafeCell},
+    marker::PhantomData,
+    sync::Arc,
 };
-use std::{cell::UnsafeCell, convert::TryFrom, marker::PhantomData};
 
 /// # Core
 ///
@@ -72,6 +76,11 @@ impl<E> Discord<'_, E> {
             core: std::ptr::null_mut(),
             client_id,
             event_handler: UnsafeCell::new(None),
+            commands: Arc::default(),
+            network_stats: RefCell::new(None),
+            send_queue: RefCell::new(None),
+            #[cfg(any(test, feature = "mock"))]
+            is_mock: false,
 
             achievement_events: events::achievement::<E>(),
             activity_events: events::activity::<E>(),
@@ -109,7 +118,7 @@ impl<E> Discord<'_, E> {
     ) -> sys::DiscordCreateParams {
         sys::DiscordCreateParams {
             client_id: self.client_id(),
-            flags: u64::try_from(flags).unwrap(),
+            flags: u64::from(flags),
 
             events: std::ptr::null_mut(),
             event_data: self.0 as *mut std::ffi::c_void,
@@ -155,7 +164,7 @@ impl<E> Discord<'_, E> {
         }
     }
 
-    fn set_log_hook(&self) {
+    pub(crate) fn set_log_hook(&self) {
         extern "C" fn log_hook(
             _: *mut std::ffi::c_void,
             level: sys::EDiscordLogLevel,
@@ -213,10 +222,35 @@ impl<E> Discord<'_, E> {
     ///
     /// If the Discord client was closed, [`Error::NotRunning`](enum.Error.html#variant.NotRunning) will be returned.
     ///
+    /// Commands sent by [`DiscordHandle`](struct.DiscordHandle.html)s are run first.
+    ///
+    /// If the [send queue](#method.enable_send_queue) is enabled, it is flushed last,
+    /// and the errors of the flush are logged.
+    ///
     /// > [Method in official docs](https://discordapp.com/developers/docs/game-sdk/discord#runcallbacks)
     // We require &mut self to prevent calling during callbacks
     pub fn run_callbacks(&mut self) -> Result<()> {
-        unsafe { (*self.inner().core).run_callbacks.unwrap()(self.inner().core).into_result() }
+        let commands = self.inner().commands.lock().unwrap().split_off(0);
+
+        for command in commands {
+            command(self);
+        }
+
+        unsafe { (*self.inner().core).run_callbacks.unwrap()(self.inner().core).into_result()? }
+
+        if self.inner().send_queue.borrow().is_some() {
+            if let Err(error) = self.flush_send_queue() {
+                log::warn!("failed to flush the send queue: {}", error);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// A handle to this instance that can be sent to other threads, see
+    /// [`DiscordHandle`](struct.DiscordHandle.html)
+    pub fn handle(&self) -> DiscordHandle<E> {
+        DiscordHandle(Arc::downgrade(&self.inner().commands))
     }
 
     pub(crate) unsafe fn achievement_manager(&self) -> *mut sys::IDiscordAchievementManager {
@@ -0,0 +1,67 @@
+use crate::{Discord, Event, EventQueue};
+use std::collections::{vec_deque, VecDeque};
+
+/// # Event Queue
+///
+/// Instead of implementing [`EventHandler`](trait.EventHandler.html), events can be queued as
+/// owned [`Event`](enum.Event.html)s by an [`EventQueue`](struct.EventQueue.html),
+/// and read after [`run_callbacks`](#method.run_callbacks) returns.
+///
+/// These methods are available for any event handler that implements `AsRef<EventQueue>`,
+/// such as a custom handler holding a queue.
+///
+/// ```rust
+/// # use discord_game_sdk::*;
+/// # fn example(mut discord: Discord<'_, EventQueue>) -> Result<()> {
+/// *discord.event_handler_mut() = Some(EventQueue::new());
+///
+/// discord.run_callbacks()?;
+///
+/// for event in discord.drain_events() {
+///     match event {
+///         Event::LobbyMessage {
+///             lobby_id,
+///             member_id,
+///             data,
+///         } => println!("{} in {}: {:?}", member_id, lobby_id, data),
+///
+///         _ => {}
+///     }
+/// }
+/// # Ok(()) }
+/// ```
+impl<'d, E: AsRef<EventQueue>> Discord<'d, E> {
+    /// Removes all events from the queue, oldest first
+    ///
+    /// Empty when no event handler is set, and from within callbacks.
+    pub fn drain_events(&self) -> vec_deque::IntoIter<Event> {
+        match self.event_handler() {
+            Some(handler) => handler.as_ref().drain(),
+            None => VecDeque::new().into_iter(),
+        }
+    }
+
+    /// A [`Stream`](https://docs.rs/futures-core/0.3/futures_core/stream/trait.Stream.html)
+    /// of the events of the queue
+    ///
+    /// Ends immediately when no event handler is set,
+    /// or when called from within callbacks.
+    ///
+    /// Requires the `futures` feature.
+    ///
+    /// ```rust
+    /// # use discord_game_sdk::*;
+    /// # use futures_core::Stream;
+    /// # fn example(discord: Discord<'_, EventQueue>) -> impl Stream<Item = Event> {
+    /// // Drive with `run_callbacks`, see `DiscordDriver`
+    /// let events = discord.event_stream();
+    /// # events }
+    /// ```
+    #[cfg(feature = "futures")]
+    pub fn event_stream(&self) -> crate::EventStream {
+        crate::EventStream(match self.event_handler() {
+            Some(handler) => std::rc::Rc::downgrade(&handler.as_ref().0),
+            None => std::rc::Weak::new(),
+        })
+    }
+}
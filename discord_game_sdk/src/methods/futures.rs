@@ -0,0 +1,335 @@
+use crate::{
+    Action, Activity, CallbackFuture, Discord, FetchKind, ImageHandle, InputMode, Lobby, LobbyID,
+    LobbyMemberTransaction, LobbyTransaction, Matchmaker, MatchmakingStep, OAuth2Token,
+    RequestReply, SearchQuery, Snowflake, User, UserID,
+};
+use std::borrow::Cow;
+
+/// # Futures
+///
+/// Requires the `futures` feature.
+///
+/// Every method that takes a callback has a counterpart that returns a
+/// [`CallbackFuture`](struct.CallbackFuture.html) instead, with an owned output.
+/// They are named after the method with an `_async` suffix, except for the storage methods
+/// which already have one, whose counterparts have a `_future` suffix.
+///
+/// The futures only make progress during [`run_callbacks`](#method.run_callbacks),
+/// the executor polling them must keep calling it.
+///
+/// ```rust
+/// # use discord_game_sdk::*;
+/// # async fn example(discord: Discord<'_, ()>) -> Result<()> {
+/// let lobby = discord.create_lobby_async(&LobbyTransaction::new()).await?;
+///
+/// discord
+///     .send_lobby_message_async(lobby.id(), b"hello")
+///     .await?;
+/// # Ok(()) }
+/// ```
+impl<'d, E> Discord<'d, E> {
+    /// Async version of [`set_user_achievement`](#method.set_user_achievement).
+    pub fn set_user_achievement_async(
+        &self,
+        achievement_id: Snowflake,
+        percent_complete: u8,
+    ) -> CallbackFuture<()> {
+        let (future, resolve) = CallbackFuture::new();
+        self.set_user_achievement(achievement_id, percent_complete, |_, res| resolve(res));
+        future
+    }
+
+    /// Async version of [`fetch_user_achievements`](#method.fetch_user_achievements).
+    pub fn fetch_user_achievements_async(&self) -> CallbackFuture<()> {
+        let (future, resolve) = CallbackFuture::new();
+        self.fetch_user_achievements(|_, res| resolve(res));
+        future
+    }
+
+    /// Async version of [`update_activity`](#method.update_activity).
+    pub fn update_activity_async(&self, activity: &Activity) -> CallbackFuture<()> {
+        let (future, resolve) = CallbackFuture::new();
+        self.update_activity(activity, |_, res| resolve(res));
+        future
+    }
+
+    /// Async version of [`clear_activity`](#method.clear_activity).
+    pub fn clear_activity_async(&self) -> CallbackFuture<()> {
+        let (future, resolve) = CallbackFuture::new();
+        self.clear_activity(|_, res| resolve(res));
+        future
+    }
+
+    /// Async version of [`send_request_reply`](#method.send_request_reply).
+    pub fn send_request_reply_async(
+        &self,
+        user_id: UserID,
+        reply: RequestReply,
+    ) -> CallbackFuture<()> {
+        let (future, resolve) = CallbackFuture::new();
+        self.send_request_reply(user_id, reply, |_, res| resolve(res));
+        future
+    }
+
+    /// Async version of [`send_invite`](#method.send_invite).
+    pub fn send_invite_async<'s>(
+        &self,
+        user_id: UserID,
+        action: Action,
+        content: impl Into<Cow<'s, str>>,
+    ) -> CallbackFuture<()> {
+        let (future, resolve) = CallbackFuture::new();
+        self.send_invite(user_id, action, content, |_, res| resolve(res));
+        future
+    }
+
+    /// Async version of [`accept_invite`](#method.accept_invite).
+    pub fn accept_invite_async(&self, user_id: UserID) -> CallbackFuture<()> {
+        let (future, resolve) = CallbackFuture::new();
+        self.accept_invite(user_id, |_, res| resolve(res));
+        future
+    }
+
+    /// Async version of [`validate_or_exit`](#method.validate_or_exit).
+    pub fn validate_or_exit_async(&self) -> CallbackFuture<()> {
+        let (future, resolve) = CallbackFuture::new();
+        self.validate_or_exit(|_, res| resolve(res));
+        future
+    }
+
+    /// Async version of [`oauth2_token`](#method.oauth2_token).
+    pub fn oauth2_token_async(&self) -> CallbackFuture<OAuth2Token> {
+        let (future, resolve) = CallbackFuture::new();
+        self.oauth2_token_owned(|_, res| resolve(res));
+        future
+    }
+
+    /// Async version of [`app_ticket`](#method.app_ticket).
+    pub fn app_ticket_async(&self) -> CallbackFuture<String> {
+        let (future, resolve) = CallbackFuture::new();
+        self.app_ticket_owned(|_, res| resolve(res));
+        future
+    }
+
+    /// Async version of [`fetch_image`](#method.fetch_image).
+    pub fn fetch_image_async(
+        &self,
+        handle: ImageHandle,
+        refresh: FetchKind,
+    ) -> CallbackFuture<ImageHandle> {
+        let (future, resolve) = CallbackFuture::new();
+        self.fetch_image(handle, refresh, |_, res| resolve(res));
+        future
+    }
+
+    /// Async version of [`create_lobby`](#method.create_lobby).
+    pub fn create_lobby_async(&self, transaction: &LobbyTransaction) -> CallbackFuture<Lobby> {
+        let (future, resolve) = CallbackFuture::new();
+        self.create_lobby_owned(transaction, |_, res| resolve(res));
+        future
+    }
+
+    /// Async version of [`update_lobby`](#method.update_lobby).
+    pub fn update_lobby_async(
+        &self,
+        lobby_id: LobbyID,
+        transaction: &LobbyTransaction,
+    ) -> CallbackFuture<()> {
+        let (future, resolve) = CallbackFuture::new();
+        self.update_lobby(lobby_id, transaction, |_, res| resolve(res));
+        future
+    }
+
+    /// Async version of [`delete_lobby`](#method.delete_lobby).
+    pub fn delete_lobby_async(&self, lobby_id: LobbyID) -> CallbackFuture<()> {
+        let (future, resolve) = CallbackFuture::new();
+        self.delete_lobby(lobby_id, |_, res| resolve(res));
+        future
+    }
+
+    /// Async version of [`connect_lobby`](#method.connect_lobby).
+    pub fn connect_lobby_async<'s>(
+        &self,
+        lobby_id: LobbyID,
+        secret: impl Into<Cow<'s, str>>,
+    ) -> CallbackFuture<Lobby> {
+        let (future, resolve) = CallbackFuture::new();
+        self.connect_lobby_owned(lobby_id, secret, |_, res| resolve(res));
+        future
+    }
+
+    /// Async version of
+    /// [`connect_lobby_with_activity_secret`](#method.connect_lobby_with_activity_secret).
+    pub fn connect_lobby_with_activity_secret_async<'s>(
+        &self,
+        activity_secret: impl Into<Cow<'s, str>>,
+    ) -> CallbackFuture<Lobby> {
+        let (future, resolve) = CallbackFuture::new();
+        self.connect_lobby_with_activity_secret_owned(activity_secret, |_, res| resolve(res));
+        future
+    }
+
+    /// Async version of [`disconnect_lobby`](#method.disconnect_lobby).
+    pub fn disconnect_lobby_async(&self, lobby_id: LobbyID) -> CallbackFuture<()> {
+        let (future, resolve) = CallbackFuture::new();
+        self.disconnect_lobby(lobby_id, |_, res| resolve(res));
+        future
+    }
+
+    /// Async version of [`update_member`](#method.update_member).
+    pub fn update_member_async(
+        &self,
+        lobby_id: LobbyID,
+        user_id: UserID,
+        transaction: &LobbyMemberTransaction,
+    ) -> CallbackFuture<()> {
+        let (future, resolve) = CallbackFuture::new();
+        self.update_member(lobby_id, user_id, transaction, |_, res| resolve(res));
+        future
+    }
+
+    /// Async version of [`send_lobby_message`](#method.send_lobby_message).
+    pub fn send_lobby_message_async(
+        &self,
+        lobby_id: LobbyID,
+        buffer: impl AsRef<[u8]>,
+    ) -> CallbackFuture<()> {
+        let (future, resolve) = CallbackFuture::new();
+        self.send_lobby_message(lobby_id, buffer, |_, res| resolve(res));
+        future
+    }
+
+    /// Async version of [`lobby_search`](#method.lobby_search).
+    pub fn lobby_search_async(&self, search: &SearchQuery) -> CallbackFuture<()> {
+        let (future, resolve) = CallbackFuture::new();
+        self.lobby_search(search, |_, res| resolve(res));
+        future
+    }
+
+    /// Async version of [`matchmake`](#method.matchmake).
+    pub fn matchmake_async(
+        &self,
+        matchmaker: &Matchmaker,
+        progress: impl 'd + FnMut(&Discord<'d, E>, MatchmakingStep),
+    ) -> CallbackFuture<Lobby>
+    where
+        E: 'd,
+    {
+        let (future, resolve) = CallbackFuture::new();
+        self.matchmake(matchmaker, progress, |_, res| resolve(res));
+        future
+    }
+
+    /// Async version of [`connect_lobby_voice`](#method.connect_lobby_voice).
+    pub fn connect_lobby_voice_async(&self, lobby_id: LobbyID) -> CallbackFuture<()> {
+        let (future, resolve) = CallbackFuture::new();
+        self.connect_lobby_voice(lobby_id, |_, res| resolve(res));
+        future
+    }
+
+    /// Async version of [`disconnect_lobby_voice`](#method.disconnect_lobby_voice).
+    pub fn disconnect_lobby_voice_async(&self, lobby_id: LobbyID) -> CallbackFuture<()> {
+        let (future, resolve) = CallbackFuture::new();
+        self.disconnect_lobby_voice(lobby_id, |_, res| resolve(res));
+        future
+    }
+
+    /// Async version of [`set_overlay_opened`](#method.set_overlay_opened).
+    pub fn set_overlay_opened_async(&self, opened: bool) -> CallbackFuture<()> {
+        let (future, resolve) = CallbackFuture::new();
+        self.set_overlay_opened(opened, |_, res| resolve(res));
+        future
+    }
+
+    /// Async version of [`open_invite_overlay`](#method.open_invite_overlay).
+    pub fn open_invite_overlay_async(&self, action: Action) -> CallbackFuture<()> {
+        let (future, resolve) = CallbackFuture::new();
+        self.open_invite_overlay(action, |_, res| resolve(res));
+        future
+    }
+
+    /// Async version of [`open_guild_invite_overlay`](#method.open_guild_invite_overlay).
+    pub fn open_guild_invite_overlay_async<'s>(
+        &self,
+        code: impl Into<Cow<'s, str>>,
+    ) -> CallbackFuture<()> {
+        let (future, resolve) = CallbackFuture::new();
+        self.open_guild_invite_overlay(code, |_, res| resolve(res));
+        future
+    }
+
+    /// Async version of [`open_voice_settings`](#method.open_voice_settings).
+    pub fn open_voice_settings_async(&self) -> CallbackFuture<()> {
+        let (future, resolve) = CallbackFuture::new();
+        self.open_voice_settings(|_, res| resolve(res));
+        future
+    }
+
+    /// Future version of [`read_file_async`](#method.read_file_async).
+    pub fn read_file_future<'s>(
+        &self,
+        filename: impl Into<Cow<'s, str>>,
+    ) -> CallbackFuture<Vec<u8>> {
+        let (future, resolve) = CallbackFuture::new();
+        self.read_file_async_owned(filename, |_, res| resolve(res));
+        future
+    }
+
+    /// Future version of [`read_file_async_partial`](#method.read_file_async_partial).
+    pub fn read_file_partial_future<'s>(
+        &self,
+        filename: impl Into<Cow<'s, str>>,
+        offset: u64,
+        length: u64,
+    ) -> CallbackFuture<Vec<u8>> {
+        let (future, resolve) = CallbackFuture::new();
+        self.read_file_async_partial_owned(filename, offset, length, |_, res| resolve(res));
+        future
+    }
+
+    /// Future version of [`write_file_async`](#method.write_file_async).
+    pub fn write_file_future<'s>(
+        &self,
+        filename: impl Into<Cow<'s, str>>,
+        buffer: impl AsRef<[u8]>,
+    ) -> CallbackFuture<()> {
+        let (future, resolve) = CallbackFuture::new();
+        self.write_file_async(filename, buffer, |_, res| resolve(res));
+        future
+    }
+
+    /// Async version of [`fetch_skus`](#method.fetch_skus).
+    pub fn fetch_skus_async(&self) -> CallbackFuture<()> {
+        let (future, resolve) = CallbackFuture::new();
+        self.fetch_skus(|_, res| resolve(res));
+        future
+    }
+
+    /// Async version of [`fetch_entitlements`](#method.fetch_entitlements).
+    pub fn fetch_entitlements_async(&self) -> CallbackFuture<()> {
+        let (future, resolve) = CallbackFuture::new();
+        self.fetch_entitlements(|_, res| resolve(res));
+        future
+    }
+
+    /// Async version of [`start_purchase`](#method.start_purchase).
+    pub fn start_purchase_async(&self, sku_id: Snowflake) -> CallbackFuture<()> {
+        let (future, resolve) = CallbackFuture::new();
+        self.start_purchase(sku_id, |_, res| resolve(res));
+        future
+    }
+
+    /// Async version of [`user`](#method.user).
+    pub fn user_async(&self, user_id: UserID) -> CallbackFuture<User> {
+        let (future, resolve) = CallbackFuture::new();
+        self.user_owned(user_id, |_, res| resolve(res));
+        future
+    }
+
+    /// Async version of [`set_input_mode`](#method.set_input_mode).
+    pub fn set_input_mode_async(&self, input_mode: InputMode) -> CallbackFuture<()> {
+        let (future, resolve) = CallbackFuture::new();
+        self.set_input_mode(input_mode, |_, res| resolve(res));
+        future
+    }
+}
@@ -69,6 +69,8 @@ impl<'d, E> Discord<'d, E> {
     /// The image must be [fetched](#method.fetch_image) first.
     ///
     /// > [Method in official docs](https://discordapp.com/developers/docs/game-sdk/images#getdata)
+    // `u32::MAX` requires Rust 1.43
+    #[allow(clippy::legacy_numeric_constants)]
     pub fn image(&self, handle: ImageHandle) -> Result<Image> {
         let (width, height) = self.image_dimensions(handle.clone())?;
         let mut data = vec![0; 4 * width as usize * height as usize];
@@ -1,9 +1,11 @@
 use crate::{
-    iter, sys, to_result::ToResult, utils, Discord, Lobby, LobbyID, LobbyMemberTransaction,
-    LobbyTransaction, NetworkChannelID, Reliability, Result, SearchQuery, UserID,
+    iter, send_queue::Layer, sys, to_result::ToResult, utils, Discord, Endpoint, Lobby, LobbyID,
+    LobbyMemberTransaction, LobbyMetadata, LobbyTransaction, MetadataDecoder, NetworkChannelID,
+    Reliability, Result, SearchQuery, User, UserID,
 };
 use std::{
     borrow::Cow,
+    collections::HashMap,
     convert::{TryFrom, TryInto},
     mem::size_of,
 };
@@ -337,8 +339,7 @@ impl<'d, E> Discord<'d, E> {
         lobby_id: LobbyID,
     ) -> Result<
         impl '_
-            + Iterator<Item = Result<(String, String)>>
-            + DoubleEndedIterator
+            + DoubleEndedIterator<Item = Result<(String, String)>>
             + ExactSizeIterator
             + std::iter::FusedIterator
             + std::fmt::Debug,
@@ -349,6 +350,23 @@ impl<'d, E> Discord<'d, E> {
         ))
     }
 
+    /// Reads the metadata of a given lobby as a [`LobbyMetadata`](trait.LobbyMetadata.html)
+    ///
+    /// ## Errors
+    ///
+    /// [`Error::NotFound`](enum.Error.html#variant.NotFound) if a key is missing,
+    /// [`Error::InvalidPayload`](enum.Error.html#variant.InvalidPayload) if a value
+    /// could not be decoded.
+    pub fn lobby_typed_metadata<M: LobbyMetadata>(&self, lobby_id: LobbyID) -> Result<M> {
+        let metadata = self
+            .iter_lobby_metadata(lobby_id)?
+            .collect::<Result<HashMap<_, _>>>()?;
+
+        M::decode(&MetadataDecoder {
+            metadata: &metadata,
+        })
+    }
+
     /// Updates lobby member info for a given member of the lobby.
     ///
     /// > [Method in official docs](https://discordapp.com/developers/docs/game-sdk/lobbies#updatemember)
@@ -426,8 +444,7 @@ impl<'d, E> Discord<'d, E> {
         lobby_id: LobbyID,
     ) -> Result<
         impl '_
-            + Iterator<Item = Result<UserID>>
-            + DoubleEndedIterator
+            + DoubleEndedIterator<Item = Result<UserID>>
             + ExactSizeIterator
             + std::iter::FusedIterator
             + std::fmt::Debug,
@@ -438,6 +455,51 @@ impl<'d, E> Discord<'d, E> {
         ))
     }
 
+    /// Returns the user of a lobby member.
+    ///
+    /// > [Method in official docs](https://discordapp.com/developers/docs/game-sdk/lobbies#getmemberuser)
+    ///
+    /// ```rust
+    /// # use discord_game_sdk::*;
+    /// # fn example(discord: Discord<'_, ()>, lobby_id: LobbyID, user_id: UserID) -> Result<()> {
+    /// let user = discord.lobby_member_user(lobby_id, user_id)?;
+    /// println!("{}#{}", user.username(), user.discriminator());
+    /// # Ok(()) }
+    /// ```
+    pub fn lobby_member_user(&self, lobby_id: LobbyID, user_id: UserID) -> Result<User> {
+        let mut user = User(sys::DiscordUser::default());
+
+        unsafe {
+            let mgr = self.lobby_manager();
+
+            (*mgr).get_member_user.unwrap()(mgr, lobby_id, user_id, &mut user.0).into_result()?;
+        }
+
+        Ok(user)
+    }
+
+    /// Returns an `Iterator` over the user IDs and users of the members of a lobby.
+    pub fn iter_lobby_members(
+        &self,
+        lobby_id: LobbyID,
+    ) -> Result<
+        impl '_
+            + DoubleEndedIterator<Item = Result<(UserID, User)>>
+            + ExactSizeIterator
+            + std::iter::FusedIterator
+            + std::fmt::Debug,
+    > {
+        Ok(iter::Collection::new(
+            Box::new(move |i| {
+                let discord = self.ref_copy();
+                let user_id = discord.lobby_member_id_at(lobby_id, i)?;
+
+                Ok((user_id, discord.lobby_member_user(lobby_id, user_id)?))
+            }),
+            self.lobby_member_count(lobby_id)?,
+        ))
+    }
+
     /// Returns member metadata value for a given key.
     ///
     /// ## Performance
@@ -544,8 +606,7 @@ impl<'d, E> Discord<'d, E> {
         user_id: UserID,
     ) -> Result<
         impl '_
-            + Iterator<Item = Result<(String, String)>>
-            + DoubleEndedIterator
+            + DoubleEndedIterator<Item = Result<(String, String)>>
             + ExactSizeIterator
             + std::iter::FusedIterator
             + std::fmt::Debug,
@@ -559,6 +620,27 @@ impl<'d, E> Discord<'d, E> {
         ))
     }
 
+    /// Reads the metadata of a given lobby member as a [`LobbyMetadata`](trait.LobbyMetadata.html)
+    ///
+    /// ## Errors
+    ///
+    /// [`Error::NotFound`](enum.Error.html#variant.NotFound) if a key is missing,
+    /// [`Error::InvalidPayload`](enum.Error.html#variant.InvalidPayload) if a value
+    /// could not be decoded.
+    pub fn lobby_member_typed_metadata<M: LobbyMetadata>(
+        &self,
+        lobby_id: LobbyID,
+        user_id: UserID,
+    ) -> Result<M> {
+        let metadata = self
+            .iter_lobby_member_metadata(lobby_id, user_id)?
+            .collect::<Result<HashMap<_, _>>>()?;
+
+        M::decode(&MetadataDecoder {
+            metadata: &metadata,
+        })
+    }
+
     /// Sends a message to the lobby on behalf of the current user.
     ///
     /// You must be connected to the lobby you are messaging.
@@ -568,6 +650,8 @@ impl<'d, E> Discord<'d, E> {
     /// [`send_lobby_network_message`](#method.send_lobby_network_message) instead.
     ///
     /// > [Method in official docs](https://discordapp.com/developers/docs/game-sdk/lobbies#sendlobbymessage)
+    // `u32::MAX` requires Rust 1.43
+    #[allow(clippy::legacy_numeric_constants)]
     pub fn send_lobby_message(
         &self,
         lobby_id: LobbyID,
@@ -677,8 +761,7 @@ impl<'d, E> Discord<'d, E> {
     pub fn iter_lobbies(
         &self,
     ) -> impl '_
-           + Iterator<Item = Result<LobbyID>>
-           + DoubleEndedIterator
+           + DoubleEndedIterator<Item = Result<LobbyID>>
            + ExactSizeIterator
            + std::iter::FusedIterator
            + std::fmt::Debug {
@@ -775,17 +858,31 @@ impl<'d, E> Discord<'d, E> {
         channel_id: NetworkChannelID,
         reliable: Reliability,
     ) -> Result<()> {
-        unsafe {
+        let res = unsafe {
             let mgr = self.lobby_manager();
 
             (*mgr).open_network_channel.unwrap()(mgr, lobby_id, channel_id, reliable.into())
                 .into_result()
+        };
+
+        if res.is_ok() {
+            self.inner().record_network_stats(|stats| {
+                stats.lobby_mut(lobby_id, channel_id).reliability = Some(reliable)
+            });
+
+            if let Some(queue) = self.inner().send_queue.borrow_mut().as_mut() {
+                queue.opened(Layer::LobbyNetworking, channel_id, reliable);
+            }
         }
+
+        res
     }
 
     /// Sends a network message.
     ///
     /// > [Method in official docs](https://discordapp.com/developers/docs/game-sdk/lobbies#sendnetworkmessage)
+    // `u32::MAX` requires Rust 1.43
+    #[allow(clippy::legacy_numeric_constants)]
     pub fn send_lobby_network_message(
         &self,
         lobby_id: LobbyID,
@@ -795,7 +892,7 @@ impl<'d, E> Discord<'d, E> {
     ) -> Result<()> {
         debug_assert!(u32::try_from(buffer.len()).is_ok());
 
-        unsafe {
+        let res = unsafe {
             let mgr = self.lobby_manager();
 
             (*mgr).send_network_message.unwrap()(
@@ -809,6 +906,40 @@ impl<'d, E> Discord<'d, E> {
                 buffer.len().try_into().unwrap_or(u32::max_value()),
             )
             .into_result()
-        }
+        };
+
+        self.inner().record_network_stats(|stats| {
+            stats
+                .lobby_mut(lobby_id, channel_id)
+                .sent(buffer.len(), &res)
+        });
+
+        res
+    }
+
+    /// Queues a network message, see [`SendQueue`](struct.SendQueue.html)
+    ///
+    /// Sent right away with [`send_lobby_network_message`](#method.send_lobby_network_message)
+    /// if the queue is not enabled.
+    ///
+    /// ## Errors
+    ///
+    /// Only those of [`send_lobby_network_message`](#method.send_lobby_network_message),
+    /// the errors of queued messages are logged.
+    pub fn queue_lobby_network_message(
+        &self,
+        lobby_id: LobbyID,
+        user_id: UserID,
+        channel_id: NetworkChannelID,
+        buffer: impl Into<Vec<u8>>,
+    ) -> Result<()> {
+        self.queue(
+            Endpoint::Lobby {
+                lobby_id,
+                member_id: user_id,
+                channel_id,
+            },
+            buffer.into(),
+        )
     }
 }
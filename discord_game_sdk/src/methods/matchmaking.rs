@@ -0,0 +1,49 @@
+use crate::{matchmaker::Run, Discord, Lobby, Matchmaker, MatchmakingStep, Result};
+
+/// # Matchmaking
+///
+/// Finds a public lobby to join with [`lobby_search`](#method.lobby_search) and
+/// [`connect_lobby`](#method.connect_lobby), or creates one with
+/// [`create_lobby`](#method.create_lobby), as configured by a
+/// [`Matchmaker`](struct.Matchmaker.html).
+///
+/// ```rust
+/// # use discord_game_sdk::*;
+/// # fn example(discord: Discord<'_, ()>) -> Result<()> {
+/// let mut matchmaker = Matchmaker::new();
+/// matchmaker
+///     .search(SearchQuery::new().filter_number("slots".into(), Comparison::GreaterThan, 0))
+///     .transaction(LobbyTransaction::new().kind(LobbyKind::Public))
+///     .retries(1);
+///
+/// discord.matchmake(
+///     &matchmaker,
+///     |discord, step| println!("{:?}", step),
+///     |discord, lobby| match lobby {
+///         Ok(lobby) => println!("playing in lobby {}", lobby.id()),
+///         Err(error) => eprintln!("failed to find a lobby: {}", error),
+///     },
+/// );
+/// # Ok(()) }
+/// ```
+impl<'d, E> Discord<'d, E> {
+    /// Joins or creates a lobby as configured by a [`Matchmaker`](struct.Matchmaker.html)
+    ///
+    /// `progress` is called at each step, `callback` once with the lobby the user is now in.
+    ///
+    /// ## Errors
+    ///
+    /// The first error that is not a lobby rejecting the user, once the retries are used up.
+    /// [`Error::NotFound`](enum.Error.html#variant.NotFound) if no lobby could be joined
+    /// and the matchmaker does not create one.
+    pub fn matchmake(
+        &self,
+        matchmaker: &Matchmaker,
+        progress: impl 'd + FnMut(&Discord<'d, E>, MatchmakingStep),
+        callback: impl 'd + FnOnce(&Discord<'d, E>, Result<Lobby>),
+    ) where
+        E: 'd,
+    {
+        Run::start(self, matchmaker, progress, callback)
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{to_result::ToResult, Discord, NetworkChannelID, NetworkPeerID, Reliability, Result};
+use crate::{
+    send_queue::Layer, to_result::ToResult, Discord, Endpoint, NetworkChannelID, NetworkPeerID,
+    NetworkStats, Reliability, Result, SendQueue,
+};
 use std::{
     borrow::Cow,
     convert::{TryFrom, TryInto},
@@ -110,11 +113,23 @@ impl<E> Discord<'_, E> {
         channel_id: NetworkChannelID,
         reliable: Reliability,
     ) -> Result<()> {
-        unsafe {
+        let res = unsafe {
             let mgr = self.network_manager();
 
             (*mgr).open_channel.unwrap()(mgr, peer_id, channel_id, reliable.into()).into_result()
+        };
+
+        if res.is_ok() {
+            self.inner().record_network_stats(|stats| {
+                stats.peer_mut(peer_id, channel_id).reliability = Some(reliable)
+            });
+
+            if let Some(queue) = self.inner().send_queue.borrow_mut().as_mut() {
+                queue.opened(Layer::Networking, channel_id, reliable);
+            }
         }
+
+        res
     }
 
     /// Close the connection to a given user by peer ID on the given channel.
@@ -135,6 +150,8 @@ impl<E> Discord<'_, E> {
     /// Sends data to a given peer ID through the given channel.
     ///
     /// > [Method in official docs](https://discordapp.com/developers/docs/game-sdk/networking#sendmessage)
+    // `u32::MAX` requires Rust 1.43
+    #[allow(clippy::legacy_numeric_constants)]
     pub fn send_message(
         &self,
         peer_id: NetworkPeerID,
@@ -145,7 +162,7 @@ impl<E> Discord<'_, E> {
 
         debug_assert!(u32::try_from(buffer.len()).is_ok());
 
-        unsafe {
+        let res = unsafe {
             let mgr = self.network_manager();
 
             (*mgr).send_message.unwrap()(
@@ -158,6 +175,135 @@ impl<E> Discord<'_, E> {
                 buffer.len().try_into().unwrap_or(u32::max_value()),
             )
             .into_result()
+        };
+
+        self.inner().record_network_stats(|stats| {
+            stats.peer_mut(peer_id, channel_id).sent(buffer.len(), &res)
+        });
+
+        res
+    }
+
+    /// Queues data to be sent to a given peer ID through the given channel,
+    /// see [`SendQueue`](struct.SendQueue.html)
+    ///
+    /// Sent right away with [`send_message`](#method.send_message) if the queue is not enabled.
+    ///
+    /// ## Errors
+    ///
+    /// Only those of [`send_message`](#method.send_message), the errors of queued messages
+    /// are logged.
+    pub fn queue_message(
+        &self,
+        peer_id: NetworkPeerID,
+        channel_id: NetworkChannelID,
+        buffer: impl Into<Vec<u8>>,
+    ) -> Result<()> {
+        self.queue(
+            Endpoint::Peer {
+                peer_id,
+                channel_id,
+            },
+            buffer.into(),
+        )
+    }
+
+    /// Starts holding the messages of [`queue_message`](#method.queue_message) and
+    /// [`queue_lobby_network_message`](#method.queue_lobby_network_message) until the end of
+    /// [`run_callbacks`](#method.run_callbacks), see [`SendQueue`](struct.SendQueue.html)
+    ///
+    /// Replaces the previous queue, and its messages.
+    /// Channels opened before are considered reliable, as the SDK does not tell the reliability
+    /// of a channel: their messages are never dropped for being too many.
+    pub fn enable_send_queue(&self, queue: &SendQueue) {
+        *self.inner().send_queue.borrow_mut() = Some(queue.clone());
+    }
+
+    /// Stops holding messages, and discards those still queued
+    pub fn disable_send_queue(&self) {
+        *self.inner().send_queue.borrow_mut() = None;
+    }
+
+    /// Sends as many queued messages as the budgets allow, and flushes the network
+    ///
+    /// Already called by [`run_callbacks`](#method.run_callbacks).
+    ///
+    /// ## Errors
+    ///
+    /// Only those of [`flush_network`](#method.flush_network) and
+    /// [`flush_lobby_network`](#method.flush_lobby_network). Both are flushed even if the
+    /// first fails, and the first error is returned.
+    pub fn flush_send_queue(&self) -> Result<()> {
+        if let Some(queue) = self.inner().send_queue.borrow_mut().as_mut() {
+            queue.flush(self);
+        }
+
+        let network = self.flush_network();
+        let lobby_network = self.flush_lobby_network();
+
+        network.and(lobby_network)
+    }
+
+    /// How many messages of the channels with this ID are queued, in both layers
+    pub fn queued_messages(&self, channel_id: NetworkChannelID) -> usize {
+        self.inner()
+            .send_queue
+            .borrow()
+            .as_ref()
+            .map_or(0, |queue| queue.queued(channel_id))
+    }
+
+    /// How many queued messages of the channels with this ID, in both layers, were dropped
+    /// since the queue was enabled, because they failed to send or too many were queued
+    pub fn dropped_messages(&self, channel_id: NetworkChannelID) -> u64 {
+        self.inner()
+            .send_queue
+            .borrow()
+            .as_ref()
+            .map_or(0, |queue| queue.dropped(channel_id))
+    }
+
+    pub(crate) fn queue(&self, endpoint: Endpoint, buffer: Vec<u8>) -> Result<()> {
+        if let Some(queue) = self.inner().send_queue.borrow_mut().as_mut() {
+            queue.push(endpoint, buffer);
+            return Ok(());
+        }
+
+        endpoint.send(self, &buffer)
+    }
+
+    /// Starts counting the traffic of the networking and lobby networking layers
+    ///
+    /// Does nothing if the counters are already enabled.
+    /// Channels opened before are counted too, but without their reliability.
+    ///
+    /// ## Performance
+    ///
+    /// Every message sent or received then updates a hash map.
+    pub fn enable_network_stats(&self) {
+        let mut stats = self.inner().network_stats.borrow_mut();
+
+        if stats.is_none() {
+            *stats = Some(NetworkStats::default());
         }
     }
+
+    /// Stops counting the traffic and discards the counters
+    pub fn disable_network_stats(&self) {
+        *self.inner().network_stats.borrow_mut() = None;
+    }
+
+    /// A snapshot of the traffic counters, `None` if they are not enabled
+    ///
+    /// ## Performance
+    ///
+    /// The counters are cloned.
+    pub fn network_stats(&self) -> Option<NetworkStats> {
+        self.inner().network_stats.borrow().clone()
+    }
+
+    /// Resets the traffic counters to zero, keeping the reliability of known channels
+    pub fn reset_network_stats(&self) {
+        self.inner().record_network_stats(NetworkStats::reset);
+    }
 }
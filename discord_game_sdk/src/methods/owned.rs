@@ -0,0 +1,217 @@
+use crate::{Discord, Lobby, LobbyID, LobbyTransaction, OAuth2Token, Result, User, UserID};
+use std::borrow::Cow;
+
+/// # Owned Results
+///
+/// Some callbacks receive references that are only valid during the callback.
+/// These methods pass an owned copy instead, which can be moved out of the callback,
+/// into a channel or the game state for example.
+///
+/// They are named after the method with an `_owned` suffix.
+///
+/// ```rust
+/// # use discord_game_sdk::*;
+/// # fn example(discord: Discord<'_, ()>) -> Result<()> {
+/// let (sender, receiver) = std::sync::mpsc::channel();
+///
+/// discord.read_file_async_owned("profile_1.save", move |_, contents| {
+///     let _ = sender.send(contents);
+/// });
+/// # Ok(()) }
+/// ```
+// `Result::cloned` requires Rust 1.59
+#[allow(clippy::map_clone)]
+impl<'d, E> Discord<'d, E> {
+    /// Owned version of [`oauth2_token`](#method.oauth2_token).
+    pub fn oauth2_token_owned(
+        &self,
+        callback: impl 'd + FnOnce(&Discord<'d, E>, Result<OAuth2Token>),
+    ) {
+        self.oauth2_token(|discord, res| callback(discord, res.map(OAuth2Token::clone)))
+    }
+
+    /// Owned version of [`app_ticket`](#method.app_ticket).
+    pub fn app_ticket_owned(&self, callback: impl 'd + FnOnce(&Discord<'d, E>, Result<String>)) {
+        self.app_ticket(|discord, res| callback(discord, res.map(str::to_string)))
+    }
+
+    /// Owned version of [`create_lobby`](#method.create_lobby).
+    pub fn create_lobby_owned(
+        &self,
+        transaction: &LobbyTransaction,
+        callback: impl 'd + FnOnce(&Discord<'d, E>, Result<Lobby>),
+    ) {
+        self.create_lobby(transaction, |discord, res| {
+            callback(discord, res.map(Lobby::clone))
+        })
+    }
+
+    /// Owned version of [`connect_lobby`](#method.connect_lobby).
+    pub fn connect_lobby_owned<'s>(
+        &self,
+        lobby_id: LobbyID,
+        secret: impl Into<Cow<'s, str>>,
+        callback: impl 'd + FnOnce(&Discord<'d, E>, Result<Lobby>),
+    ) {
+        self.connect_lobby(lobby_id, secret, |discord, res| {
+            callback(discord, res.map(Lobby::clone))
+        })
+    }
+
+    /// Owned version of
+    /// [`connect_lobby_with_activity_secret`](#method.connect_lobby_with_activity_secret).
+    pub fn connect_lobby_with_activity_secret_owned<'s>(
+        &self,
+        activity_secret: impl Into<Cow<'s, str>>,
+        callback: impl 'd + FnOnce(&Discord<'d, E>, Result<Lobby>),
+    ) {
+        self.connect_lobby_with_activity_secret(activity_secret, |discord, res| {
+            callback(discord, res.map(Lobby::clone))
+        })
+    }
+
+    /// Owned version of [`read_file_async`](#method.read_file_async).
+    pub fn read_file_async_owned<'s>(
+        &self,
+        filename: impl Into<Cow<'s, str>>,
+        callback: impl 'd + FnOnce(&Discord<'d, E>, Result<Vec<u8>>),
+    ) {
+        self.read_file_async(filename, |discord, res| {
+            callback(discord, res.map(<[u8]>::to_vec))
+        })
+    }
+
+    /// Owned version of [`read_file_async_partial`](#method.read_file_async_partial).
+    pub fn read_file_async_partial_owned<'s>(
+        &self,
+        filename: impl Into<Cow<'s, str>>,
+        offset: u64,
+        length: u64,
+        callback: impl 'd + FnOnce(&Discord<'d, E>, Result<Vec<u8>>),
+    ) {
+        self.read_file_async_partial(filename, offset, length, |discord, res| {
+            callback(discord, res.map(<[u8]>::to_vec))
+        })
+    }
+
+    /// Owned version of [`user`](#method.user).
+    pub fn user_owned(
+        &self,
+        user_id: UserID,
+        callback: impl 'd + FnOnce(&Discord<'d, E>, Result<User>),
+    ) {
+        self.user(user_id, |discord, res| {
+            callback(discord, res.map(User::clone))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Discord, Error, LobbyTransaction, MockLobby, MockState, Result};
+    use std::{cell::RefCell, fmt::Debug, rc::Rc};
+
+    type Results<T> = Rc<RefCell<Vec<Result<T>>>>;
+
+    fn push<T>(results: &Results<T>) -> impl FnOnce(Result<T>) {
+        let results = results.clone();
+        move |res| results.borrow_mut().push(res)
+    }
+
+    // Calls a method and its owned version with the same arguments
+    macro_rules! both {
+        ($discord:ident.$method:ident($($arg:expr),*), $owned:ident) => {{
+            let results = Results::default();
+            let (first, second) = (push(&results), push(&results));
+            $discord.$method($($arg,)* move |_, res| first(res.map(ToOwned::to_owned)));
+            $discord.$owned($($arg,)* move |_, res| second(res));
+            results
+        }};
+    }
+
+    // The borrowed callback runs first, then the owned one
+    fn assert_matching<T: Debug + PartialEq>(results: &Results<T>) {
+        let results = results.borrow();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0], results[1]);
+    }
+
+    #[test]
+    fn match_borrowed() {
+        let mut discord = Discord::<()>::with_mock_state(
+            MockState::new()
+                .with_user(2, "friend", "0002")
+                .with_file("profile.save", b"contents")
+                .with_lobby(MockLobby::new(10, 2).with_secret("secret")),
+        );
+
+        let tokens = both!(discord.oauth2_token(), oauth2_token_owned);
+        let tickets = both!(discord.app_ticket(), app_ticket_owned);
+        let users = both!(discord.user(2), user_owned);
+        let missing_users = both!(discord.user(3), user_owned);
+
+        let contents = both!(
+            discord.read_file_async("profile.save"),
+            read_file_async_owned
+        );
+        let partial_contents = both!(
+            discord.read_file_async_partial("profile.save", 2, 4),
+            read_file_async_partial_owned
+        );
+        let missing_contents = both!(
+            discord.read_file_async("missing.save"),
+            read_file_async_owned
+        );
+
+        let lobbies = both!(discord.connect_lobby(10, "secret"), connect_lobby_owned);
+        let activity_lobbies = both!(
+            discord.connect_lobby_with_activity_secret("10:secret"),
+            connect_lobby_with_activity_secret_owned
+        );
+        let rejected_lobbies = both!(discord.connect_lobby(10, "wrong"), connect_lobby_owned);
+
+        discord.run_callbacks().unwrap();
+
+        assert_matching(&tokens);
+        assert_matching(&tickets);
+        assert_matching(&users);
+        assert_matching(&missing_users);
+        assert_matching(&contents);
+        assert_matching(&partial_contents);
+        assert_matching(&missing_contents);
+        assert_matching(&lobbies);
+        assert_matching(&activity_lobbies);
+        assert_matching(&rejected_lobbies);
+
+        assert_eq!(users.borrow()[0].as_ref().unwrap().username(), "friend");
+        assert_eq!(missing_users.borrow()[0], Err(Error::NotFound));
+        assert_eq!(contents.borrow()[0], Ok(b"contents".to_vec()));
+        assert_eq!(partial_contents.borrow()[0], Ok(b"nten".to_vec()));
+        assert_eq!(missing_contents.borrow()[0], Err(Error::NotFound));
+        assert_eq!(lobbies.borrow()[0].as_ref().unwrap().id(), 10);
+        assert_eq!(rejected_lobbies.borrow()[0], Err(Error::InvalidLobbySecret));
+    }
+
+    #[test]
+    fn create_lobby() {
+        let mut discord = Discord::<()>::mock();
+        let transaction = LobbyTransaction::new();
+
+        let lobbies = both!(discord.create_lobby(&transaction), create_lobby_owned);
+
+        discord.mock_fail("lobbies.create_lobby", Error::ServiceUnavailable, 2);
+        let failures = both!(discord.create_lobby(&transaction), create_lobby_owned);
+
+        discord.run_callbacks().unwrap();
+
+        // Each call creates its own lobby
+        assert_eq!(lobbies.borrow().len(), 2);
+        for lobby in lobbies.borrow().iter() {
+            let lobby = lobby.as_ref().unwrap();
+            assert_eq!(discord.lobby(lobby.id()).as_ref(), Ok(lobby));
+        }
+
+        assert_matching(&failures);
+        assert_eq!(failures.borrow()[0], Err(Error::ServiceUnavailable));
+    }
+}
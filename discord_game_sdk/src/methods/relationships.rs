@@ -126,8 +126,7 @@ impl<E> Discord<'_, E> {
         &self,
     ) -> Result<
         impl '_
-            + Iterator<Item = Result<Relationship>>
-            + DoubleEndedIterator
+            + DoubleEndedIterator<Item = Result<Relationship>>
             + ExactSizeIterator
             + std::iter::FusedIterator
             + std::fmt::Debug,
@@ -31,6 +31,8 @@ impl<'d, E> Discord<'d, E> {
     /// let contents = &contents[0..end as usize];
     /// # Ok(()) }
     /// ```
+    // `u32::MAX` requires Rust 1.43
+    #[allow(clippy::legacy_numeric_constants)]
     pub fn read_file<'s>(
         &self,
         filename: impl Into<Cow<'s, str>>,
@@ -63,7 +65,7 @@ impl<'d, E> Discord<'d, E> {
         }
 
         // XXX: u32 should be u64
-        Ok(read.try_into().unwrap())
+        Ok(u64::from(read))
     }
 
     /// Reads data asynchronously from the game's allocated save file into a buffer.
@@ -181,6 +183,8 @@ impl<'d, E> Discord<'d, E> {
     /// discord.write_file("profile_1.save\0", contents)?;
     /// # Ok(()) }
     /// ```
+    // `u32::MAX` requires Rust 1.43
+    #[allow(clippy::legacy_numeric_constants)]
     pub fn write_file<'s>(
         &self,
         filename: impl Into<Cow<'s, str>>,
@@ -233,6 +237,8 @@ impl<'d, E> Discord<'d, E> {
     /// });
     /// # Ok(()) }
     /// ```
+    // `u32::MAX` requires Rust 1.43
+    #[allow(clippy::legacy_numeric_constants)]
     pub fn write_file_async<'s>(
         &self,
         filename: impl Into<Cow<'s, str>>,
@@ -418,8 +424,7 @@ impl<'d, E> Discord<'d, E> {
     pub fn iter_file_stats(
         &self,
     ) -> impl '_
-           + Iterator<Item = Result<FileStat>>
-           + DoubleEndedIterator
+           + DoubleEndedIterator<Item = Result<FileStat>>
            + ExactSizeIterator
            + std::iter::FusedIterator
            + std::fmt::Debug {
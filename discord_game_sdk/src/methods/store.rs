@@ -143,8 +143,7 @@ impl<'d, E> Discord<'d, E> {
     pub fn iter_skus(
         &self,
     ) -> impl '_
-           + Iterator<Item = Result<Sku>>
-           + DoubleEndedIterator
+           + DoubleEndedIterator<Item = Result<Sku>>
            + ExactSizeIterator
            + std::iter::FusedIterator
            + std::fmt::Debug {
@@ -289,8 +288,7 @@ impl<'d, E> Discord<'d, E> {
     pub fn iter_entitlements(
         &self,
     ) -> impl '_
-           + Iterator<Item = Result<Entitlement>>
-           + DoubleEndedIterator
+           + DoubleEndedIterator<Item = Result<Entitlement>>
            + ExactSizeIterator
            + std::iter::FusedIterator
            + std::fmt::Debug {
@@ -0,0 +1,145 @@
+use crate::{
+    mock::ffi::{write_str, Instance, ResultCallback},
+    sys,
+};
+use std::{convert::TryFrom, ffi::c_void};
+
+pub(crate) const VTABLE: sys::IDiscordAchievementManager = sys::IDiscordAchievementManager {
+    set_user_achievement: Some(set_user_achievement),
+    fetch_user_achievements: Some(fetch_user_achievements),
+    count_user_achievements: Some(count_user_achievements),
+    get_user_achievement: Some(get_user_achievement),
+    get_user_achievement_at: Some(get_user_achievement_at),
+};
+
+unsafe extern "C" fn set_user_achievement(
+    mgr: *mut sys::IDiscordAchievementManager,
+    achievement_id: sys::DiscordSnowflake,
+    percent_complete: u8,
+    callback_data: *mut c_void,
+    callback: ResultCallback,
+) {
+    let _fault =
+        Instance::from_manager(mgr).fault_next_callback("achievements.set_user_achievement");
+
+    Instance::from_manager(mgr).respond(callback_data, callback, move |instance| {
+        let achievement = {
+            let mut state = instance.state.borrow_mut();
+
+            let achievement = match state
+                .achievements
+                .list
+                .iter_mut()
+                .find(|achievement| achievement.achievement_id == achievement_id)
+            {
+                Some(achievement) => achievement,
+                None => return sys::DiscordResult_NotFound,
+            };
+
+            // Progress never goes backwards
+            if percent_complete > achievement.percent_complete {
+                achievement.percent_complete = percent_complete;
+
+                if percent_complete >= 100 {
+                    write_str(&mut achievement.unlocked_at, "1970-01-01T00:00:00+00:00");
+                }
+            }
+
+            *achievement
+        };
+
+        instance.event(instance.params.achievement_events, |events, data| unsafe {
+            // SDK gives out a pointer to a copy, the state isn't borrowed during the event
+            let mut achievement = achievement;
+            events.on_user_achievement_update.unwrap()(data, &mut achievement)
+        });
+
+        sys::DiscordResult_Ok
+    })
+}
+
+unsafe extern "C" fn fetch_user_achievements(
+    mgr: *mut sys::IDiscordAchievementManager,
+    callback_data: *mut c_void,
+    callback: ResultCallback,
+) {
+    let _fault =
+        Instance::from_manager(mgr).fault_next_callback("achievements.fetch_user_achievements");
+
+    Instance::from_manager(mgr).respond(callback_data, callback, |instance| {
+        instance.state.borrow_mut().achievements.fetched = true;
+
+        sys::DiscordResult_Ok
+    })
+}
+
+unsafe extern "C" fn count_user_achievements(
+    mgr: *mut sys::IDiscordAchievementManager,
+    count: *mut i32,
+) {
+    let state = Instance::from_manager(mgr).state.borrow();
+
+    *count = if state.achievements.fetched {
+        i32::try_from(state.achievements.list.len()).unwrap()
+    } else {
+        0
+    };
+}
+
+unsafe extern "C" fn get_user_achievement(
+    mgr: *mut sys::IDiscordAchievementManager,
+    user_achievement_id: sys::DiscordSnowflake,
+    user_achievement: *mut sys::DiscordUserAchievement,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("achievements.get_user_achievement") {
+        return res;
+    }
+
+    let state = Instance::from_manager(mgr).state.borrow();
+
+    if !state.achievements.fetched {
+        return sys::DiscordResult_NotFetched;
+    }
+
+    match state
+        .achievements
+        .list
+        .iter()
+        .find(|achievement| achievement.achievement_id == user_achievement_id)
+    {
+        Some(achievement) => {
+            *user_achievement = *achievement;
+            sys::DiscordResult_Ok
+        }
+
+        None => sys::DiscordResult_NotFound,
+    }
+}
+
+unsafe extern "C" fn get_user_achievement_at(
+    mgr: *mut sys::IDiscordAchievementManager,
+    index: i32,
+    user_achievement: *mut sys::DiscordUserAchievement,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("achievements.get_user_achievement_at") {
+        return res;
+    }
+
+    let state = Instance::from_manager(mgr).state.borrow();
+
+    if !state.achievements.fetched {
+        return sys::DiscordResult_NotFetched;
+    }
+
+    match usize::try_from(index)
+        .ok()
+        .and_then(|index| state.achievements.list.get(index))
+    {
+        Some(achievement) => {
+            *user_achievement = *achievement;
+            sys::DiscordResult_Ok
+        }
+
+        None => sys::DiscordResult_NotFound,
+    }
+}
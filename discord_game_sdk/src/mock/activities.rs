@@ -0,0 +1,160 @@
+use crate::{
+    mock::ffi::{read_str, Instance, ResultCallback},
+    sys,
+};
+use std::ffi::c_void;
+
+pub(crate) const VTABLE: sys::IDiscordActivityManager = sys::IDiscordActivityManager {
+    register_command: Some(register_command),
+    register_steam: Some(register_steam),
+    update_activity: Some(update_activity),
+    clear_activity: Some(clear_activity),
+    send_request_reply: Some(send_request_reply),
+    send_invite: Some(send_invite),
+    accept_invite: Some(accept_invite),
+};
+
+unsafe extern "C" fn register_command(
+    mgr: *mut sys::IDiscordActivityManager,
+    command: *const u8,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("activities.register_command") {
+        return res;
+    }
+
+    let command = read_str(command);
+
+    if command.is_empty() {
+        return sys::DiscordResult_InvalidCommand;
+    }
+
+    Instance::from_manager(mgr)
+        .state
+        .borrow_mut()
+        .activities
+        .command = Some(command);
+
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn register_steam(
+    mgr: *mut sys::IDiscordActivityManager,
+    steam_id: u32,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("activities.register_steam") {
+        return res;
+    }
+
+    Instance::from_manager(mgr)
+        .state
+        .borrow_mut()
+        .activities
+        .steam_id = Some(steam_id);
+
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn update_activity(
+    mgr: *mut sys::IDiscordActivityManager,
+    activity: *mut sys::DiscordActivity,
+    callback_data: *mut c_void,
+    callback: ResultCallback,
+) {
+    let _fault = Instance::from_manager(mgr).fault_next_callback("activities.update_activity");
+
+    let instance = Instance::from_manager(mgr);
+    let mut activity = *activity;
+    activity.application_id = instance.params.client_id;
+
+    instance.respond(callback_data, callback, move |instance| {
+        instance.state.borrow_mut().activities.current = Some(activity);
+
+        sys::DiscordResult_Ok
+    })
+}
+
+unsafe extern "C" fn clear_activity(
+    mgr: *mut sys::IDiscordActivityManager,
+    callback_data: *mut c_void,
+    callback: ResultCallback,
+) {
+    let _fault = Instance::from_manager(mgr).fault_next_callback("activities.clear_activity");
+
+    Instance::from_manager(mgr).respond(callback_data, callback, |instance| {
+        instance.state.borrow_mut().activities.current = None;
+
+        sys::DiscordResult_Ok
+    })
+}
+
+unsafe extern "C" fn send_request_reply(
+    mgr: *mut sys::IDiscordActivityManager,
+    user_id: sys::DiscordUserId,
+    reply: sys::EDiscordActivityJoinRequestReply,
+    callback_data: *mut c_void,
+    callback: ResultCallback,
+) {
+    let _fault = Instance::from_manager(mgr).fault_next_callback("activities.send_request_reply");
+
+    Instance::from_manager(mgr).respond(callback_data, callback, move |instance| {
+        if instance.find_user(user_id).is_none() {
+            return sys::DiscordResult_NotFound;
+        }
+
+        let mut state = instance.state.borrow_mut();
+
+        state.activities.replies.push((user_id, reply));
+
+        sys::DiscordResult_Ok
+    })
+}
+
+unsafe extern "C" fn send_invite(
+    mgr: *mut sys::IDiscordActivityManager,
+    user_id: sys::DiscordUserId,
+    type_: sys::EDiscordActivityActionType,
+    content: *const u8,
+    callback_data: *mut c_void,
+    callback: ResultCallback,
+) {
+    let _fault = Instance::from_manager(mgr).fault_next_callback("activities.send_invite");
+
+    let content = read_str(content);
+
+    Instance::from_manager(mgr).respond(callback_data, callback, move |instance| {
+        if instance.state.borrow().activities.current.is_none() {
+            return sys::DiscordResult_NoEligibleActivity;
+        }
+
+        if instance.find_user(user_id).is_none() {
+            return sys::DiscordResult_NotFound;
+        }
+
+        let mut state = instance.state.borrow_mut();
+
+        state.activities.invites.push((user_id, type_, content));
+
+        sys::DiscordResult_Ok
+    })
+}
+
+unsafe extern "C" fn accept_invite(
+    mgr: *mut sys::IDiscordActivityManager,
+    user_id: sys::DiscordUserId,
+    callback_data: *mut c_void,
+    callback: ResultCallback,
+) {
+    let _fault = Instance::from_manager(mgr).fault_next_callback("activities.accept_invite");
+
+    Instance::from_manager(mgr).respond(callback_data, callback, move |instance| {
+        if instance.find_user(user_id).is_none() {
+            return sys::DiscordResult_InvalidInvite;
+        }
+
+        let mut state = instance.state.borrow_mut();
+
+        state.activities.accepted.push(user_id);
+
+        sys::DiscordResult_Ok
+    })
+}
@@ -0,0 +1,71 @@
+use crate::{
+    mock::ffi::{write_str, Instance, ResultCallback},
+    sys,
+};
+use std::ffi::c_void;
+
+pub(crate) const VTABLE: sys::IDiscordApplicationManager = sys::IDiscordApplicationManager {
+    validate_or_exit: Some(validate_or_exit),
+    get_current_locale: Some(get_current_locale),
+    get_current_branch: Some(get_current_branch),
+    get_oauth2_token: Some(get_oauth2_token),
+    get_ticket: Some(get_ticket),
+};
+
+unsafe extern "C" fn validate_or_exit(
+    mgr: *mut sys::IDiscordApplicationManager,
+    callback_data: *mut c_void,
+    callback: ResultCallback,
+) {
+    let _fault = Instance::from_manager(mgr).fault_next_callback("applications.validate_or_exit");
+
+    Instance::from_manager(mgr).respond(callback_data, callback, |_| sys::DiscordResult_Ok)
+}
+
+unsafe extern "C" fn get_current_locale(
+    mgr: *mut sys::IDiscordApplicationManager,
+    locale: *mut sys::DiscordLocale,
+) {
+    let state = Instance::from_manager(mgr).state.borrow();
+
+    write_str(&mut *locale, &state.applications.locale);
+}
+
+unsafe extern "C" fn get_current_branch(
+    mgr: *mut sys::IDiscordApplicationManager,
+    branch: *mut sys::DiscordBranch,
+) {
+    let state = Instance::from_manager(mgr).state.borrow();
+
+    write_str(&mut *branch, &state.applications.branch);
+}
+
+unsafe extern "C" fn get_oauth2_token(
+    mgr: *mut sys::IDiscordApplicationManager,
+    callback_data: *mut c_void,
+    callback: Option<
+        unsafe extern "C" fn(*mut c_void, sys::EDiscordResult, *mut sys::DiscordOAuth2Token),
+    >,
+) {
+    let _fault = Instance::from_manager(mgr).fault_next_callback("applications.get_oauth2_token");
+
+    Instance::from_manager(mgr).defer(move |instance, res| {
+        let mut token = instance.state.borrow().applications.oauth2_token;
+
+        callback.unwrap()(callback_data, res, &mut token)
+    })
+}
+
+unsafe extern "C" fn get_ticket(
+    mgr: *mut sys::IDiscordApplicationManager,
+    callback_data: *mut c_void,
+    callback: Option<unsafe extern "C" fn(*mut c_void, sys::EDiscordResult, *const u8)>,
+) {
+    let _fault = Instance::from_manager(mgr).fault_next_callback("applications.get_ticket");
+
+    Instance::from_manager(mgr).defer(move |instance, res| {
+        let ticket = format!("{}\0", instance.state.borrow().applications.ticket);
+
+        callback.unwrap()(callback_data, res, ticket.as_ptr())
+    })
+}
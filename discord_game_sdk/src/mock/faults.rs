@@ -0,0 +1,199 @@
+use crate::{mock::MockOrder, sys};
+use std::collections::BTreeMap;
+
+/// How a mock instance misbehaves, configured through `Discord::mock_fail` and friends
+pub(crate) struct Faults {
+    calls: BTreeMap<&'static str, (sys::EDiscordResult, u32)>,
+    next_callback: Option<sys::EDiscordResult>,
+    pub(crate) latency: u32,
+    order: MockOrder,
+    rng: u64,
+}
+
+impl Faults {
+    pub(crate) fn new() -> Self {
+        Self {
+            calls: BTreeMap::new(),
+            next_callback: None,
+            latency: 0,
+            order: MockOrder::Fifo,
+            rng: 0,
+        }
+    }
+
+    /// Makes the next `times` calls to `call` fail with `result`
+    ///
+    /// Panics if `call` is not one of `CALLS`
+    pub(crate) fn set(&mut self, call: &str, result: sys::EDiscordResult, times: u32) {
+        let call = match CALLS.binary_search(&call) {
+            Ok(index) => CALLS[index],
+            Err(_) => panic!("unknown mock call {:?}", call),
+        };
+
+        if times == 0 {
+            let _ = self.calls.remove(call);
+        } else {
+            let _ = self.calls.insert(call, (result, times));
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.calls.clear();
+        self.next_callback = None;
+    }
+
+    /// The result `call` should fail with, if any
+    pub(crate) fn take(&mut self, call: &str) -> Option<sys::EDiscordResult> {
+        let (result, remaining) = self.calls.get_mut(call)?;
+        let result = *result;
+
+        *remaining -= 1;
+
+        if *remaining == 0 {
+            let _ = self.calls.remove(call);
+        }
+
+        Some(result)
+    }
+
+    /// Remembers the result the callback of `call` should receive, used by the next `defer`
+    pub(crate) fn prepare_callback(&mut self, call: &str) {
+        self.next_callback = self.take(call);
+    }
+
+    pub(crate) fn take_callback(&mut self) -> Option<sys::EDiscordResult> {
+        self.next_callback.take()
+    }
+
+    pub(crate) fn set_order(&mut self, order: MockOrder) {
+        if let MockOrder::Shuffle(seed) = order {
+            // xorshift never leaves 0
+            self.rng = seed | 1;
+        }
+
+        self.order = order;
+    }
+
+    /// Puts the tasks that are due in the order they should run
+    pub(crate) fn reorder<T>(&mut self, tasks: &mut [T]) {
+        match self.order {
+            MockOrder::Fifo => {}
+
+            MockOrder::Lifo => tasks.reverse(),
+
+            MockOrder::Shuffle(_) => {
+                for i in (1..tasks.len()).rev() {
+                    let j = (self.next_random() % (i as u64 + 1)) as usize;
+                    tasks.swap(i, j);
+                }
+            }
+        }
+    }
+
+    // xorshift64
+    fn next_random(&mut self) -> u64 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        self.rng
+    }
+}
+
+/// Every call that can be made to fail, sorted
+pub(crate) const CALLS: &[&str] = &[
+    "achievements.fetch_user_achievements",
+    "achievements.get_user_achievement",
+    "achievements.get_user_achievement_at",
+    "achievements.set_user_achievement",
+    "activities.accept_invite",
+    "activities.clear_activity",
+    "activities.register_command",
+    "activities.register_steam",
+    "activities.send_invite",
+    "activities.send_request_reply",
+    "activities.update_activity",
+    "applications.get_oauth2_token",
+    "applications.get_ticket",
+    "applications.validate_or_exit",
+    "images.fetch",
+    "images.get_data",
+    "images.get_dimensions",
+    "lobbies.connect_lobby",
+    "lobbies.connect_lobby_with_activity_secret",
+    "lobbies.connect_network",
+    "lobbies.connect_voice",
+    "lobbies.create_lobby",
+    "lobbies.delete_lobby",
+    "lobbies.disconnect_lobby",
+    "lobbies.disconnect_network",
+    "lobbies.disconnect_voice",
+    "lobbies.flush_network",
+    "lobbies.get_lobby",
+    "lobbies.get_lobby_activity_secret",
+    "lobbies.get_lobby_create_transaction",
+    "lobbies.get_lobby_id",
+    "lobbies.get_lobby_metadata_key",
+    "lobbies.get_lobby_metadata_value",
+    "lobbies.get_lobby_update_transaction",
+    "lobbies.get_member_metadata_key",
+    "lobbies.get_member_metadata_value",
+    "lobbies.get_member_update_transaction",
+    "lobbies.get_member_user",
+    "lobbies.get_member_user_id",
+    "lobbies.get_search_query",
+    "lobbies.lobby_metadata_count",
+    "lobbies.member_count",
+    "lobbies.member_metadata_count",
+    "lobbies.open_network_channel",
+    "lobbies.search",
+    "lobbies.send_lobby_message",
+    "lobbies.send_network_message",
+    "lobbies.update_lobby",
+    "lobbies.update_member",
+    "networking.close_channel",
+    "networking.close_peer",
+    "networking.flush",
+    "networking.open_channel",
+    "networking.open_peer",
+    "networking.send_message",
+    "networking.update_peer",
+    "overlay.open_activity_invite",
+    "overlay.open_guild_invite",
+    "overlay.open_voice_settings",
+    "overlay.set_locked",
+    "relationships.count",
+    "relationships.get",
+    "relationships.get_at",
+    "storage.delete",
+    "storage.exists",
+    "storage.get_path",
+    "storage.read",
+    "storage.read_async",
+    "storage.read_async_partial",
+    "storage.stat",
+    "storage.stat_at",
+    "storage.write",
+    "storage.write_async",
+    "store.fetch_entitlements",
+    "store.fetch_skus",
+    "store.get_entitlement",
+    "store.get_entitlement_at",
+    "store.get_sku",
+    "store.get_sku_at",
+    "store.has_sku_entitlement",
+    "store.start_purchase",
+    "users.current_user_has_flag",
+    "users.get_current_user",
+    "users.get_current_user_premium_type",
+    "users.get_user",
+    "voice.get_input_mode",
+    "voice.get_local_volume",
+    "voice.is_local_mute",
+    "voice.is_self_deaf",
+    "voice.is_self_mute",
+    "voice.set_input_mode",
+    "voice.set_local_mute",
+    "voice.set_local_volume",
+    "voice.set_self_deaf",
+    "voice.set_self_mute",
+];
@@ -1,171 +1,469 @@
-use crate::sys;
-use std::ffi::c_void;
-
-const CORE: &sys::IDiscordCore = &sys::IDiscordCore {
-    destroy: {
-        unsafe extern "C" fn destroy(_: *mut sys::IDiscordCore) {
-            while let Some(closure) = STATE.as_mut().unwrap().queue.pop() {
-                closure()
-            }
+use crate::{
+    mock::{faults::Faults, hub::Hub, inject, state::State},
+    sys, Event, UserID,
+};
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    ffi::c_void,
+    rc::Rc,
+};
 
-            drop(STATE.take());
-        }
+pub(crate) type ResultCallback = Option<unsafe extern "C" fn(*mut c_void, sys::EDiscordResult)>;
 
-        Some(destroy)
-    },
+// Tasks are run with `DiscordResult_Ok` during `run_callbacks`, or with the result of an injected
+// fault, and with `DiscordResult_TransactionAborted` when the instance is destroyed
+type Task = Box<dyn FnOnce(&Instance, sys::EDiscordResult)>;
 
-    run_callbacks: {
-        unsafe extern "C" fn run_callbacks(_: *mut sys::IDiscordCore) -> sys::EDiscordResult {
-            while let Some(closure) = STATE.as_mut().unwrap().queue.pop() {
-                closure()
-            }
+type LogHook = Option<unsafe extern "C" fn(*mut c_void, sys::EDiscordLogLevel, *const u8)>;
+
+/// A manager vtable followed by a pointer back to the instance that owns it
+///
+/// The SDK hands out `*mut IDiscordXManager`, we need to find our way back to `Instance` from it
+#[repr(C)]
+pub(crate) struct Manager<T> {
+    interface: T,
+    instance: *const Instance,
+}
+
+/// Clears the fault prepared by `Instance::fault_next_callback` if it was not used by `defer`,
+/// for calls that fail before deferring their callback
+#[must_use]
+pub(crate) struct CallbackFault<'a>(&'a Instance);
+
+impl Drop for CallbackFault<'_> {
+    fn drop(&mut self) {
+        let _ = self.0.faults.borrow_mut().take_callback();
+    }
+}
+
+/// The in-process replacement for what `sys::DiscordCreate` gives us
+///
+/// `core` must stay the first field, `*mut sys::IDiscordCore` and `*mut Instance` are the same pointer
+#[repr(C)]
+pub(crate) struct Instance {
+    core: sys::IDiscordCore,
+
+    achievements: Manager<sys::IDiscordAchievementManager>,
+    activities: Manager<sys::IDiscordActivityManager>,
+    applications: Manager<sys::IDiscordApplicationManager>,
+    images: Manager<sys::IDiscordImageManager>,
+    lobbies: Manager<sys::IDiscordLobbyManager>,
+    networking: Manager<sys::IDiscordNetworkManager>,
+    overlay: Manager<sys::IDiscordOverlayManager>,
+    relationships: Manager<sys::IDiscordRelationshipManager>,
+    storage: Manager<sys::IDiscordStorageManager>,
+    store: Manager<sys::IDiscordStoreManager>,
+    users: Manager<sys::IDiscordUserManager>,
+    voice: Manager<sys::IDiscordVoiceManager>,
+
+    pub(crate) params: sys::DiscordCreateParams,
+    pub(crate) state: RefCell<State>,
+    pub(crate) hub: Rc<RefCell<Hub>>,
+    pub(crate) faults: RefCell<Faults>,
+    // Number of calls to `run_callbacks` so far, tasks are queued with the tick they are due on
+    tick: Cell<u64>,
+    queue: RefCell<VecDeque<(u64, Task)>>,
+    log_hook: RefCell<(sys::EDiscordLogLevel, *mut c_void, LogHook)>,
+}
 
-            sys::DiscordResult_Ok
+impl Instance {
+    pub(crate) unsafe fn from_manager<'a, T>(manager: *mut T) -> &'a Self {
+        &*(*(manager as *const Manager<T>)).instance
+    }
+
+    pub(crate) unsafe fn from_core<'a>(core: *mut sys::IDiscordCore) -> &'a Self {
+        &*(core as *const Self)
+    }
+
+    pub(crate) fn user_id(&self) -> UserID {
+        self.state.borrow().users.current.id
+    }
+
+    /// Looks up users we know of, including those of the other instances on the hub
+    pub(crate) fn find_user(&self, user_id: UserID) -> Option<sys::DiscordUser> {
+        if let Some(user) = self.state.borrow().find_user(user_id) {
+            return Some(user);
         }
 
-        Some(run_callbacks)
-    },
+        let hub = self.hub.borrow();
 
-    get_achievement_manager: {
-        unsafe extern "C" fn get_achievement_manager(
-            _: *mut sys::IDiscordCore,
-        ) -> *mut sys::IDiscordAchievementManager {
-            ACHIEVEMENT_MANAGER as *const _ as *mut _
+        if let Some(member) = hub
+            .lobbies
+            .values()
+            .flat_map(|lobby| lobby.members.iter())
+            .find(|member| member.user.id == user_id)
+        {
+            return Some(member.user);
         }
 
-        Some(get_achievement_manager)
-    },
-
-    set_log_hook: None,
-    get_application_manager: None,
-    get_user_manager: None,
-    get_image_manager: None,
-    get_activity_manager: None,
-    get_relationship_manager: None,
-    get_lobby_manager: None,
-    get_network_manager: None,
-    get_overlay_manager: None,
-    get_storage_manager: None,
-    get_store_manager: None,
-    get_voice_manager: None,
-};
+        hub.instance(user_id)
+            .map(|instance| instance.state.borrow().current_user())
+    }
+
+    /// What `MockState` shows, lobbies come from the hub
+    pub(crate) fn snapshot(&self) -> State {
+        let mut state = self.state.borrow().snapshot();
+        state.lobbies.list = self.hub.borrow().lobbies.clone();
+        state
+    }
+
+    /// Schedules `event` on the other instances on the hub whose user is one of `user_ids`
+    pub(crate) fn broadcast(&self, user_ids: &[UserID], event: Event) {
+        let hub = self.hub.borrow();
 
-const ACHIEVEMENT_MANAGER: &sys::IDiscordAchievementManager = &sys::IDiscordAchievementManager {
-    set_user_achievement: {
-        unsafe extern "C" fn set_user_achievements(
-            _: *mut sys::IDiscordAchievementManager,
-            achievement_id: sys::DiscordSnowflake,
-            percent_complete: u8,
-            callback_data: *mut c_void,
-            callback: Option<unsafe extern "C" fn(*mut c_void, sys::EDiscordResult)>,
-        ) {
-            STATE.as_mut().unwrap().queue.push(Box::new(move || {
-                let state = STATE.as_mut().unwrap();
-
-                for achievement in state.achievements.iter_mut() {
-                    if achievement.achievement_id == achievement_id {
-                        achievement.percent_complete = percent_complete;
-
-                        (*state.params.achievement_events)
-                            .on_user_achievement_update
-                            .unwrap()(
-                            state.params.event_data, achievement as *const _ as *mut _
-                        )
-                    }
-                }
-
-                callback.unwrap()(callback_data, sys::DiscordResult_Ok);
-            }))
+        for instance in hub.instances() {
+            if !std::ptr::eq(instance, self) && user_ids.contains(&instance.user_id()) {
+                let event = event.clone();
+                instance.emit(move |instance| inject::fire(instance, event));
+            }
         }
+    }
+
+    /// The result `call` should fail with instead of running, if a fault was injected
+    pub(crate) fn fault(&self, call: &str) -> Option<sys::EDiscordResult> {
+        self.faults.borrow_mut().take(call)
+    }
+
+    /// Makes the next `defer` fail if a fault was injected for `call`, as long as the returned
+    /// guard is alive, so that the fault never reaches the callback of another call
+    pub(crate) fn fault_next_callback(&self, call: &str) -> CallbackFault<'_> {
+        self.faults.borrow_mut().prepare_callback(call);
+        CallbackFault(self)
+    }
+
+    /// Schedules `task` for a later call to `run_callbacks`, depending on the latency
+    pub(crate) fn defer(&self, task: impl 'static + FnOnce(&Self, sys::EDiscordResult)) {
+        let fault = self.faults.borrow_mut().take_callback();
 
-        Some(set_user_achievements)
-    },
-
-    fetch_user_achievements: {
-        unsafe extern "C" fn fetch_user_achievements(
-            _: *mut sys::IDiscordAchievementManager,
-            callback_data: *mut c_void,
-            callback: Option<unsafe extern "C" fn(*mut c_void, sys::EDiscordResult)>,
-        ) {
-            STATE.as_mut().unwrap().queue.push(Box::new(move || {
-                callback.unwrap()(callback_data, sys::DiscordResult_Ok);
-            }))
+        match fault {
+            Some(fault) => self.push(Box::new(move |instance, res| {
+                task(
+                    instance,
+                    if res == sys::DiscordResult_Ok {
+                        fault
+                    } else {
+                        res
+                    },
+                )
+            })),
+
+            None => self.push(Box::new(task)),
         }
+    }
 
-        Some(fetch_user_achievements)
-    },
-
-    get_user_achievement: {
-        unsafe extern "C" fn get_user_achievement(
-            _: *mut sys::IDiscordAchievementManager,
-            user_achievement_id: sys::DiscordSnowflake,
-            user_achievement: *mut sys::DiscordUserAchievement,
-        ) -> sys::EDiscordResult {
-            for achievement in &STATE.as_ref().unwrap().achievements {
-                if achievement.achievement_id == user_achievement_id {
-                    *user_achievement = *achievement;
-
-                    return sys::DiscordResult_Ok;
-                }
+    fn push(&self, task: Task) {
+        let due = self.tick.get() + 1 + u64::from(self.faults.borrow().latency);
+
+        self.queue.borrow_mut().push_back((due, task));
+    }
+
+    /// Schedules `action` for the next call to `run_callbacks`, its result is passed to `callback`
+    pub(crate) fn respond(
+        &self,
+        callback_data: *mut c_void,
+        callback: ResultCallback,
+        action: impl 'static + FnOnce(&Self) -> sys::EDiscordResult,
+    ) {
+        self.defer(move |instance, res| {
+            let res = if res == sys::DiscordResult_Ok {
+                action(instance)
+            } else {
+                res
+            };
+
+            unsafe { callback.unwrap()(callback_data, res) }
+        })
+    }
+
+    /// Schedules an event for the next call to `run_callbacks`, dropped if the instance is destroyed
+    pub(crate) fn emit(&self, event: impl 'static + FnOnce(&Self)) {
+        self.push(Box::new(move |instance, res| {
+            if res == sys::DiscordResult_Ok {
+                event(instance)
             }
+        }))
+    }
 
-            sys::DiscordResult_NotFound
+    /// Calls into an event vtable given to us in `sys::DiscordCreateParams`
+    pub(crate) fn event<T>(&self, events: *mut T, fire: impl FnOnce(&T, *mut c_void)) {
+        if !events.is_null() {
+            fire(unsafe { &*events }, self.params.event_data)
         }
+    }
 
-        Some(get_user_achievement)
-    },
+    pub(crate) fn log(&self, level: sys::EDiscordLogLevel, message: &str) {
+        let (min_level, hook_data, hook) = *self.log_hook.borrow();
 
-    count_user_achievements: {
-        unsafe extern "C" fn count_user_achievements(
-            _: *mut sys::IDiscordAchievementManager,
-            count: *mut i32,
-        ) {
-            *count = STATE.as_ref().unwrap().achievements.len() as i32;
+        if let Some(hook) = hook {
+            if level <= min_level {
+                let message = format!("{}\0", message);
+                unsafe { hook(hook_data, level, message.as_ptr()) }
+            }
         }
-        Some(count_user_achievements)
-    },
-
-    get_user_achievement_at: {
-        unsafe extern "C" fn get_user_achievement_at(
-            _: *mut sys::IDiscordAchievementManager,
-            index: i32,
-            user_achievement: *mut sys::DiscordUserAchievement,
-        ) -> sys::EDiscordResult {
-            *user_achievement = STATE.as_ref().unwrap().achievements[index as usize];
-
-            sys::DiscordResult_Ok
+    }
+
+    fn run_callbacks(&self) {
+        let tick = self.tick.get() + 1;
+        self.tick.set(tick);
+
+        // Tasks queued while running callbacks are for a later call
+        let mut tasks = Vec::new();
+
+        let queue = self.queue.replace(VecDeque::new());
+
+        for (due, task) in queue {
+            if due <= tick {
+                tasks.push(task);
+            } else {
+                self.queue.borrow_mut().push_back((due, task));
+            }
         }
 
-        Some(get_user_achievement_at)
-    },
-};
+        self.faults.borrow_mut().reorder(&mut tasks);
 
-#[derive(Default)]
-struct State {
-    params: sys::DiscordCreateParams,
-    achievements: Vec<sys::DiscordUserAchievement>,
-    queue: Vec<Box<dyn FnOnce()>>,
+        for task in tasks {
+            task(self, sys::DiscordResult_Ok);
+        }
+    }
+
+    fn abort_all(&self) {
+        loop {
+            let task = match self.queue.borrow_mut().pop_front() {
+                Some((_, task)) => task,
+                None => break,
+            };
+
+            task(self, sys::DiscordResult_TransactionAborted);
+        }
+    }
 }
 
-static mut STATE: Option<State> = None;
+/// Boxes a vtable followed by its `data`, for the SDK's short-lived objects
+///
+/// These are lobby transactions and search queries, they are consumed by the manager method
+/// that takes them or freed when the instance is destroyed
+#[repr(C)]
+pub(crate) struct Object<T, D> {
+    pub(crate) interface: T,
+    pub(crate) data: D,
+}
+
+impl<T, D> Object<T, D> {
+    pub(crate) fn boxed(interface: T, data: D) -> *mut T {
+        Box::into_raw(Box::new(Self { interface, data })) as *mut T
+    }
+
+    pub(crate) unsafe fn data<'a>(ptr: *mut T) -> &'a mut D {
+        &mut (*(ptr as *mut Self)).data
+    }
 
-pub(crate) unsafe fn create_mock(params: sys::DiscordCreateParams) -> *mut sys::IDiscordCore {
-    if STATE.is_some() {
-        panic!("can only hold one instance lol");
+    pub(crate) unsafe fn take(ptr: *mut T) -> D {
+        Box::from_raw(ptr as *mut Self).data
     }
+}
+
+pub(crate) fn write_str(charbuf: &mut [u8], value: &str) {
+    let mut len = value.len().min(charbuf.len() - 1);
+
+    while !value.is_char_boundary(len) {
+        len -= 1;
+    }
+
+    charbuf[..len].copy_from_slice(&value.as_bytes()[..len]);
+    charbuf[len] = 0;
+}
+
+pub(crate) unsafe fn read_str(ptr: *const u8) -> String {
+    if ptr.is_null() {
+        return String::new();
+    }
+
+    std::ffi::CStr::from_ptr(ptr as *const i8)
+        .to_string_lossy()
+        .into_owned()
+}
+
+pub(crate) unsafe fn read_bytes(data: *const u8, data_length: u32) -> Vec<u8> {
+    if data.is_null() || data_length == 0 {
+        return Vec::new();
+    }
+
+    std::slice::from_raw_parts(data, data_length as usize).to_vec()
+}
+
+pub(crate) unsafe fn create_mock(
+    params: sys::DiscordCreateParams,
+    mut state: State,
+    hub: Rc<RefCell<Hub>>,
+) -> *mut sys::IDiscordCore {
+    fn manager<T>(interface: T) -> Manager<T> {
+        Manager {
+            interface,
+            instance: std::ptr::null(),
+        }
+    }
+
+    // The hub owns the lobbies from now on
+    let lobbies = state.lobbies.list.clone();
+    state.lobbies.list.clear();
+
+    let ptr = Box::into_raw(Box::new(Instance {
+        core: CORE,
+
+        achievements: manager(super::achievements::VTABLE),
+        activities: manager(super::activities::VTABLE),
+        applications: manager(super::applications::VTABLE),
+        images: manager(super::images::VTABLE),
+        lobbies: manager(super::lobbies::VTABLE),
+        networking: manager(super::networking::VTABLE),
+        overlay: manager(super::overlay::VTABLE),
+        relationships: manager(super::relationships::VTABLE),
+        storage: manager(super::storage::VTABLE),
+        store: manager(super::store::VTABLE),
+        users: manager(super::users::VTABLE),
+        voice: manager(super::voice::VTABLE),
 
-    STATE = Some(State {
         params,
-        achievements: (0..10)
-            .map(|achievement_id| sys::DiscordUserAchievement {
-                user_id: 0,
-                achievement_id,
-                percent_complete: 0,
-                unlocked_at: [0; 64],
-            })
-            .collect(),
-        ..Default::default()
+        state: RefCell::new(state),
+        hub,
+        faults: RefCell::new(Faults::new()),
+        tick: Cell::new(0),
+        queue: RefCell::new(VecDeque::new()),
+        log_hook: RefCell::new((sys::DiscordLogLevel_Error, std::ptr::null_mut(), None)),
+    }));
+
+    (*ptr).achievements.instance = ptr;
+    (*ptr).activities.instance = ptr;
+    (*ptr).applications.instance = ptr;
+    (*ptr).images.instance = ptr;
+    (*ptr).lobbies.instance = ptr;
+    (*ptr).networking.instance = ptr;
+    (*ptr).overlay.instance = ptr;
+    (*ptr).relationships.instance = ptr;
+    (*ptr).storage.instance = ptr;
+    (*ptr).store.instance = ptr;
+    (*ptr).users.instance = ptr;
+    (*ptr).voice.instance = ptr;
+
+    let instance = &*ptr;
+
+    instance.hub.borrow_mut().join(instance, lobbies);
+
+    // The real SDK sends these shortly after creation
+
+    instance.emit(|instance| {
+        instance.event(instance.params.user_events, |events, data| unsafe {
+            events.on_current_user_update.unwrap()(data)
+        })
+    });
+
+    instance.emit(|instance| {
+        instance.event(instance.params.relationship_events, |events, data| unsafe {
+            events.on_refresh.unwrap()(data)
+        })
+    });
+
+    instance.emit(|instance| {
+        let route = format!("{}\0", instance.state.borrow().network.route);
+
+        instance.event(instance.params.network_events, |events, data| unsafe {
+            events.on_route_update.unwrap()(data, route.as_ptr())
+        })
     });
 
-    CORE as *const _ as *mut _
+    // SAFETY: `destroy` turns this back into a `Box`, it must keep the provenance of `ptr`
+    ptr as *mut sys::IDiscordCore
+}
+
+const CORE: sys::IDiscordCore = sys::IDiscordCore {
+    destroy: Some(destroy),
+    run_callbacks: Some(run_callbacks),
+    set_log_hook: Some(set_log_hook),
+    get_application_manager: Some(get_application_manager),
+    get_user_manager: Some(get_user_manager),
+    get_image_manager: Some(get_image_manager),
+    get_activity_manager: Some(get_activity_manager),
+    get_relationship_manager: Some(get_relationship_manager),
+    get_lobby_manager: Some(get_lobby_manager),
+    get_network_manager: Some(get_network_manager),
+    get_overlay_manager: Some(get_overlay_manager),
+    get_storage_manager: Some(get_storage_manager),
+    get_store_manager: Some(get_store_manager),
+    get_voice_manager: Some(get_voice_manager),
+    get_achievement_manager: Some(get_achievement_manager),
+};
+
+unsafe extern "C" fn destroy(core: *mut sys::IDiscordCore) {
+    let instance = Instance::from_core(core);
+
+    instance.abort_all();
+
+    // Like closing the client, the other instances see us leave our lobbies
+    super::lobbies::disconnect_all(instance);
+    instance.hub.borrow_mut().leave(instance);
+
+    let instance = Box::from_raw(core as *mut Instance);
+
+    for tx in instance.state.borrow_mut().lobbies.pending.drain(..) {
+        super::lobbies::free_pending(tx);
+    }
+
+    drop(instance);
+}
+
+unsafe extern "C" fn run_callbacks(core: *mut sys::IDiscordCore) -> sys::EDiscordResult {
+    Instance::from_core(core).run_callbacks();
+
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn set_log_hook(
+    core: *mut sys::IDiscordCore,
+    min_level: sys::EDiscordLogLevel,
+    hook_data: *mut c_void,
+    hook: LogHook,
+) {
+    let instance = Instance::from_core(core);
+
+    *instance.log_hook.borrow_mut() = (min_level, hook_data, hook);
+
+    instance.log(sys::DiscordLogLevel_Info, "mock instance created");
 }
+
+macro_rules! get_manager {
+    ($name:ident, $field:ident, $ty:ty) => {
+        unsafe extern "C" fn $name(core: *mut sys::IDiscordCore) -> *mut $ty {
+            &Instance::from_core(core).$field as *const Manager<$ty> as *mut $ty
+        }
+    };
+}
+
+get_manager!(
+    get_achievement_manager,
+    achievements,
+    sys::IDiscordAchievementManager
+);
+get_manager!(
+    get_activity_manager,
+    activities,
+    sys::IDiscordActivityManager
+);
+get_manager!(
+    get_application_manager,
+    applications,
+    sys::IDiscordApplicationManager
+);
+get_manager!(get_image_manager, images, sys::IDiscordImageManager);
+get_manager!(get_lobby_manager, lobbies, sys::IDiscordLobbyManager);
+get_manager!(get_network_manager, networking, sys::IDiscordNetworkManager);
+get_manager!(get_overlay_manager, overlay, sys::IDiscordOverlayManager);
+get_manager!(
+    get_relationship_manager,
+    relationships,
+    sys::IDiscordRelationshipManager
+);
+get_manager!(get_storage_manager, storage, sys::IDiscordStorageManager);
+get_manager!(get_store_manager, store, sys::IDiscordStoreManager);
+get_manager!(get_user_manager, users, sys::IDiscordUserManager);
+get_manager!(get_voice_manager, voice, sys::IDiscordVoiceManager);
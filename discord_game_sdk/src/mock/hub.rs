@@ -0,0 +1,65 @@
+use crate::{
+    mock::{ffi::Instance, state::Lobby},
+    LobbyID, NetworkPeerID, UserID,
+};
+use std::collections::BTreeMap;
+
+/// What the mock instances sharing a `MockHub` have in common
+pub(crate) struct Hub {
+    pub(crate) lobbies: BTreeMap<LobbyID, Lobby>,
+    pub(crate) next_lobby_id: LobbyID,
+    // Added by `create_mock` and removed by `destroy`, always valid
+    instances: Vec<*const Instance>,
+}
+
+impl Hub {
+    pub(crate) fn new() -> Self {
+        Self {
+            lobbies: BTreeMap::new(),
+            next_lobby_id: 1,
+            instances: Vec::new(),
+        }
+    }
+
+    /// Adds an instance and the lobbies it was seeded with
+    ///
+    /// Panics if another instance runs as the same user
+    pub(crate) fn join(&mut self, instance: &Instance, lobbies: BTreeMap<LobbyID, Lobby>) {
+        let user_id = instance.user_id();
+
+        assert!(
+            self.instance(user_id).is_none(),
+            "another mock instance runs as user {}",
+            user_id
+        );
+
+        for (id, lobby) in lobbies {
+            self.next_lobby_id = self.next_lobby_id.max(id + 1);
+            let _ = self.lobbies.insert(id, lobby);
+        }
+
+        self.instances.push(instance);
+    }
+
+    pub(crate) fn leave(&mut self, instance: &Instance) {
+        let ptr = instance as *const Instance;
+
+        self.instances.retain(|instance| *instance != ptr);
+    }
+
+    /// The instance running as `user_id`
+    pub(crate) fn instance(&self, user_id: UserID) -> Option<&Instance> {
+        self.instances()
+            .find(|instance| instance.user_id() == user_id)
+    }
+
+    /// The instance with the network peer ID `peer_id`
+    pub(crate) fn peer(&self, peer_id: NetworkPeerID) -> Option<&Instance> {
+        self.instances()
+            .find(|instance| instance.state.borrow().network.peer_id == peer_id)
+    }
+
+    pub(crate) fn instances(&self) -> impl Iterator<Item = &Instance> {
+        self.instances.iter().map(|instance| unsafe { &**instance })
+    }
+}
@@ -0,0 +1,101 @@
+use crate::{mock::ffi::Instance, sys};
+use std::ffi::c_void;
+
+pub(crate) const VTABLE: sys::IDiscordImageManager = sys::IDiscordImageManager {
+    fetch: Some(fetch),
+    get_dimensions: Some(get_dimensions),
+    get_data: Some(get_data),
+};
+
+// Avatars are square and sized as requested, with pixels derived from the handle
+fn pixel(handle: &sys::DiscordImageHandle, index: usize) -> u8 {
+    match index % 4 {
+        3 => 0xFF,
+        channel => handle.id.wrapping_add((index / 4 + channel) as i64) as u8,
+    }
+}
+
+unsafe extern "C" fn fetch(
+    mgr: *mut sys::IDiscordImageManager,
+    handle: sys::DiscordImageHandle,
+    refresh: bool,
+    callback_data: *mut c_void,
+    callback: Option<
+        unsafe extern "C" fn(*mut c_void, sys::EDiscordResult, sys::DiscordImageHandle),
+    >,
+) {
+    let _fault = Instance::from_manager(mgr).fault_next_callback("images.fetch");
+
+    Instance::from_manager(mgr).defer(move |instance, res| {
+        let res = if res != sys::DiscordResult_Ok {
+            res
+        } else if handle.type_ != sys::DiscordImageType_User || handle.size == 0 {
+            sys::DiscordResult_InvalidPayload
+        } else if instance.find_user(handle.id).is_none() {
+            sys::DiscordResult_NotFound
+        } else {
+            let images = &mut instance.state.borrow_mut().images;
+
+            if refresh || !images.fetched.contains(&handle) {
+                images.fetched.retain(|fetched| *fetched != handle);
+                images.fetched.push(handle);
+            }
+
+            sys::DiscordResult_Ok
+        };
+
+        callback.unwrap()(callback_data, res, handle)
+    })
+}
+
+unsafe extern "C" fn get_dimensions(
+    mgr: *mut sys::IDiscordImageManager,
+    handle: sys::DiscordImageHandle,
+    dimensions: *mut sys::DiscordImageDimensions,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("images.get_dimensions") {
+        return res;
+    }
+
+    let state = Instance::from_manager(mgr).state.borrow();
+
+    if !state.images.fetched.contains(&handle) {
+        return sys::DiscordResult_NotFetched;
+    }
+
+    (*dimensions).width = handle.size;
+    (*dimensions).height = handle.size;
+
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn get_data(
+    mgr: *mut sys::IDiscordImageManager,
+    handle: sys::DiscordImageHandle,
+    data: *mut u8,
+    data_length: u32,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("images.get_data") {
+        return res;
+    }
+
+    let state = Instance::from_manager(mgr).state.borrow();
+
+    if !state.images.fetched.contains(&handle) {
+        return sys::DiscordResult_NotFetched;
+    }
+
+    let len = 4 * handle.size as usize * handle.size as usize;
+
+    if (data_length as usize) < len {
+        return sys::DiscordResult_InsufficientBuffer;
+    }
+
+    let data = std::slice::from_raw_parts_mut(data, len);
+
+    for (index, byte) in data.iter_mut().enumerate() {
+        *byte = pixel(&handle, index);
+    }
+
+    sys::DiscordResult_Ok
+}
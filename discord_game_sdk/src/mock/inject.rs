@@ -0,0 +1,210 @@
+use crate::{mock::ffi::Instance, Event};
+use std::convert::TryFrom;
+
+fn c_string(value: &str) -> Vec<u8> {
+    let mut bytes = value.as_bytes().to_vec();
+    bytes.push(0);
+    bytes
+}
+
+fn len(data: &[u8]) -> u32 {
+    u32::try_from(data.len()).unwrap()
+}
+
+/// Calls the SDK event matching `event` on the instance's event vtables
+pub(crate) fn fire(instance: &Instance, event: Event) {
+    let params = &instance.params;
+
+    match event {
+        Event::UserAchievementUpdate { user_achievement } => {
+            let mut user_achievement = user_achievement.0;
+
+            instance.event(params.achievement_events, |events, data| unsafe {
+                events.on_user_achievement_update.unwrap()(data, &mut user_achievement)
+            })
+        }
+
+        Event::ActivityJoin { secret } => {
+            let secret = c_string(&secret);
+
+            instance.event(params.activity_events, |events, data| unsafe {
+                events.on_activity_join.unwrap()(data, secret.as_ptr())
+            })
+        }
+
+        Event::ActivitySpectate { secret } => {
+            let secret = c_string(&secret);
+
+            instance.event(params.activity_events, |events, data| unsafe {
+                events.on_activity_spectate.unwrap()(data, secret.as_ptr())
+            })
+        }
+
+        Event::ActivityJoinRequest { user } => {
+            let mut user = user.0;
+
+            instance.event(params.activity_events, |events, data| unsafe {
+                events.on_activity_join_request.unwrap()(data, &mut user)
+            })
+        }
+
+        Event::ActivityInvite {
+            kind,
+            user,
+            activity,
+        } => {
+            let mut user = user.0;
+            let mut activity = activity.0;
+
+            instance.event(params.activity_events, |events, data| unsafe {
+                events.on_activity_invite.unwrap()(data, kind.into(), &mut user, &mut activity)
+            })
+        }
+
+        Event::LobbyUpdate { lobby_id } => instance
+            .event(params.lobby_events, |events, data| unsafe {
+                events.on_lobby_update.unwrap()(data, lobby_id)
+            }),
+
+        Event::LobbyDelete { lobby_id, reason } => instance
+            .event(params.lobby_events, |events, data| unsafe {
+                events.on_lobby_delete.unwrap()(data, lobby_id, reason)
+            }),
+
+        Event::MemberConnect {
+            lobby_id,
+            member_id,
+        } => instance.event(params.lobby_events, |events, data| unsafe {
+            events.on_member_connect.unwrap()(data, lobby_id, member_id)
+        }),
+
+        Event::MemberUpdate {
+            lobby_id,
+            member_id,
+        } => instance.event(params.lobby_events, |events, data| unsafe {
+            events.on_member_update.unwrap()(data, lobby_id, member_id)
+        }),
+
+        Event::MemberDisconnect {
+            lobby_id,
+            member_id,
+        } => instance.event(params.lobby_events, |events, data| unsafe {
+            events.on_member_disconnect.unwrap()(data, lobby_id, member_id)
+        }),
+
+        Event::LobbyMessage {
+            lobby_id,
+            member_id,
+            mut data,
+        } => {
+            let data_len = len(&data);
+
+            instance.event(params.lobby_events, |events, event_data| unsafe {
+                events.on_lobby_message.unwrap()(
+                    event_data,
+                    lobby_id,
+                    member_id,
+                    data.as_mut_ptr(),
+                    data_len,
+                )
+            })
+        }
+
+        Event::Speaking {
+            lobby_id,
+            member_id,
+            speaking,
+        } => instance.event(params.lobby_events, |events, data| unsafe {
+            events.on_speaking.unwrap()(data, lobby_id, member_id, speaking)
+        }),
+
+        Event::LobbyNetworkMessage {
+            lobby_id,
+            member_id,
+            channel_id,
+            mut data,
+        } => {
+            let data_len = len(&data);
+
+            instance.event(params.lobby_events, |events, event_data| unsafe {
+                events.on_network_message.unwrap()(
+                    event_data,
+                    lobby_id,
+                    member_id,
+                    channel_id,
+                    data.as_mut_ptr(),
+                    data_len,
+                )
+            })
+        }
+
+        Event::NetworkMessage {
+            peer_id,
+            channel_id,
+            mut data,
+        } => {
+            let data_len = len(&data);
+
+            instance.event(params.network_events, |events, event_data| unsafe {
+                events.on_message.unwrap()(
+                    event_data,
+                    peer_id,
+                    channel_id,
+                    data.as_mut_ptr(),
+                    data_len,
+                )
+            })
+        }
+
+        Event::NetworkRouteUpdate { route } => {
+            let route = c_string(&route);
+
+            instance.event(params.network_events, |events, data| unsafe {
+                events.on_route_update.unwrap()(data, route.as_ptr())
+            })
+        }
+
+        // `events::overlay` negates what the SDK gives it
+        Event::OverlayToggle { closed } => instance
+            .event(params.overlay_events, |events, data| unsafe {
+                events.on_toggle.unwrap()(data, !closed)
+            }),
+
+        Event::RelationshipsRefresh => instance
+            .event(params.relationship_events, |events, data| unsafe {
+                events.on_refresh.unwrap()(data)
+            }),
+
+        Event::RelationshipUpdate { relationship } => {
+            let mut relationship = relationship.0;
+
+            instance.event(params.relationship_events, |events, data| unsafe {
+                events.on_relationship_update.unwrap()(data, &mut relationship)
+            })
+        }
+
+        Event::EntitlementCreate { entitlement } => {
+            let mut entitlement = entitlement.0;
+
+            instance.event(params.store_events, |events, data| unsafe {
+                events.on_entitlement_create.unwrap()(data, &mut entitlement)
+            })
+        }
+
+        Event::EntitlementDelete { entitlement } => {
+            let mut entitlement = entitlement.0;
+
+            instance.event(params.store_events, |events, data| unsafe {
+                events.on_entitlement_delete.unwrap()(data, &mut entitlement)
+            })
+        }
+
+        Event::CurrentUserUpdate => instance.event(params.user_events, |events, data| unsafe {
+            events.on_current_user_update.unwrap()(data)
+        }),
+
+        Event::VoiceSettingsUpdate => instance.event(params.voice_events, |events, data| unsafe {
+            events.on_settings_update.unwrap()(data)
+        }),
+    }
+}
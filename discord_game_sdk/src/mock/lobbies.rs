@@ -0,0 +1,1441 @@
+use crate::{
+    mock::{
+        ffi::{read_bytes, read_str, write_str, Instance, Object, ResultCallback},
+        inject,
+        state::{Lobby, Member, Pending},
+    },
+    sys, Event, LobbyID, UserID,
+};
+use std::{
+    cmp::Ordering, collections::BTreeMap, convert::TryFrom, ffi::c_void,
+    result::Result as StdResult,
+};
+
+type LobbyCallback =
+    Option<unsafe extern "C" fn(*mut c_void, sys::EDiscordResult, *mut sys::DiscordLobby)>;
+
+type Result<T> = StdResult<T, sys::EDiscordResult>;
+
+pub(crate) const VTABLE: sys::IDiscordLobbyManager = sys::IDiscordLobbyManager {
+    get_lobby_create_transaction: Some(get_lobby_create_transaction),
+    get_lobby_update_transaction: Some(get_lobby_update_transaction),
+    get_member_update_transaction: Some(get_member_update_transaction),
+    create_lobby: Some(create_lobby),
+    update_lobby: Some(update_lobby),
+    delete_lobby: Some(delete_lobby),
+    connect_lobby: Some(connect_lobby),
+    connect_lobby_with_activity_secret: Some(connect_lobby_with_activity_secret),
+    disconnect_lobby: Some(disconnect_lobby),
+    get_lobby: Some(get_lobby),
+    get_lobby_activity_secret: Some(get_lobby_activity_secret),
+    get_lobby_metadata_value: Some(get_lobby_metadata_value),
+    get_lobby_metadata_key: Some(get_lobby_metadata_key),
+    lobby_metadata_count: Some(lobby_metadata_count),
+    member_count: Some(member_count),
+    get_member_user_id: Some(get_member_user_id),
+    get_member_user: Some(get_member_user),
+    get_member_metadata_value: Some(get_member_metadata_value),
+    get_member_metadata_key: Some(get_member_metadata_key),
+    member_metadata_count: Some(member_metadata_count),
+    update_member: Some(update_member),
+    send_lobby_message: Some(send_lobby_message),
+    get_search_query: Some(get_search_query),
+    search: Some(search),
+    lobby_count: Some(lobby_count),
+    get_lobby_id: Some(get_lobby_id),
+    connect_voice: Some(connect_voice),
+    disconnect_voice: Some(disconnect_voice),
+    connect_network: Some(connect_network),
+    disconnect_network: Some(disconnect_network),
+    flush_network: Some(flush_network),
+    open_network_channel: Some(open_network_channel),
+    send_network_message: Some(send_network_message),
+};
+
+// Transactions and search queries
+
+#[derive(Default)]
+struct LobbyTransaction {
+    kind: Option<sys::EDiscordLobbyType>,
+    owner: Option<UserID>,
+    capacity: Option<u32>,
+    locked: Option<bool>,
+    metadata: Vec<(String, Option<String>)>,
+}
+
+#[derive(Default)]
+struct MemberTransaction {
+    metadata: Vec<(String, Option<String>)>,
+}
+
+#[derive(Default)]
+struct SearchQuery {
+    filters: Vec<(
+        String,
+        sys::EDiscordLobbySearchComparison,
+        sys::EDiscordLobbySearchCast,
+        String,
+    )>,
+    sorts: Vec<(String, sys::EDiscordLobbySearchCast, String)>,
+    limit: Option<u32>,
+}
+
+type LobbyTransactionObject = Object<sys::IDiscordLobbyTransaction, LobbyTransaction>;
+type MemberTransactionObject = Object<sys::IDiscordLobbyMemberTransaction, MemberTransaction>;
+type SearchQueryObject = Object<sys::IDiscordLobbySearchQuery, SearchQuery>;
+
+const LOBBY_TRANSACTION: sys::IDiscordLobbyTransaction = sys::IDiscordLobbyTransaction {
+    set_type: Some(tx_set_type),
+    set_owner: Some(tx_set_owner),
+    set_capacity: Some(tx_set_capacity),
+    set_metadata: Some(tx_set_metadata),
+    delete_metadata: Some(tx_delete_metadata),
+    set_locked: Some(tx_set_locked),
+};
+
+const MEMBER_TRANSACTION: sys::IDiscordLobbyMemberTransaction =
+    sys::IDiscordLobbyMemberTransaction {
+        set_metadata: Some(member_tx_set_metadata),
+        delete_metadata: Some(member_tx_delete_metadata),
+    };
+
+const SEARCH_QUERY: sys::IDiscordLobbySearchQuery = sys::IDiscordLobbySearchQuery {
+    filter: Some(query_filter),
+    sort: Some(query_sort),
+    limit: Some(query_limit),
+    distance: Some(query_distance),
+};
+
+unsafe extern "C" fn tx_set_type(
+    tx: *mut sys::IDiscordLobbyTransaction,
+    type_: sys::EDiscordLobbyType,
+) -> sys::EDiscordResult {
+    LobbyTransactionObject::data(tx).kind = Some(type_);
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn tx_set_owner(
+    tx: *mut sys::IDiscordLobbyTransaction,
+    owner_id: sys::DiscordUserId,
+) -> sys::EDiscordResult {
+    LobbyTransactionObject::data(tx).owner = Some(owner_id);
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn tx_set_capacity(
+    tx: *mut sys::IDiscordLobbyTransaction,
+    capacity: u32,
+) -> sys::EDiscordResult {
+    LobbyTransactionObject::data(tx).capacity = Some(capacity);
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn tx_set_metadata(
+    tx: *mut sys::IDiscordLobbyTransaction,
+    key: *mut u8,
+    value: *mut u8,
+) -> sys::EDiscordResult {
+    LobbyTransactionObject::data(tx)
+        .metadata
+        .push((read_str(key), Some(read_str(value))));
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn tx_delete_metadata(
+    tx: *mut sys::IDiscordLobbyTransaction,
+    key: *mut u8,
+) -> sys::EDiscordResult {
+    LobbyTransactionObject::data(tx)
+        .metadata
+        .push((read_str(key), None));
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn tx_set_locked(
+    tx: *mut sys::IDiscordLobbyTransaction,
+    locked: bool,
+) -> sys::EDiscordResult {
+    LobbyTransactionObject::data(tx).locked = Some(locked);
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn member_tx_set_metadata(
+    tx: *mut sys::IDiscordLobbyMemberTransaction,
+    key: *mut u8,
+    value: *mut u8,
+) -> sys::EDiscordResult {
+    MemberTransactionObject::data(tx)
+        .metadata
+        .push((read_str(key), Some(read_str(value))));
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn member_tx_delete_metadata(
+    tx: *mut sys::IDiscordLobbyMemberTransaction,
+    key: *mut u8,
+) -> sys::EDiscordResult {
+    MemberTransactionObject::data(tx)
+        .metadata
+        .push((read_str(key), None));
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn query_filter(
+    query: *mut sys::IDiscordLobbySearchQuery,
+    key: *mut u8,
+    comparison: sys::EDiscordLobbySearchComparison,
+    cast: sys::EDiscordLobbySearchCast,
+    value: *mut u8,
+) -> sys::EDiscordResult {
+    SearchQueryObject::data(query)
+        .filters
+        .push((read_str(key), comparison, cast, read_str(value)));
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn query_sort(
+    query: *mut sys::IDiscordLobbySearchQuery,
+    key: *mut u8,
+    cast: sys::EDiscordLobbySearchCast,
+    value: *mut u8,
+) -> sys::EDiscordResult {
+    SearchQueryObject::data(query)
+        .sorts
+        .push((read_str(key), cast, read_str(value)));
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn query_limit(
+    query: *mut sys::IDiscordLobbySearchQuery,
+    limit: u32,
+) -> sys::EDiscordResult {
+    SearchQueryObject::data(query).limit = Some(limit);
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn query_distance(
+    _: *mut sys::IDiscordLobbySearchQuery,
+    _: sys::EDiscordLobbySearchDistance,
+) -> sys::EDiscordResult {
+    // Everybody is in the same place
+    sys::DiscordResult_Ok
+}
+
+pub(crate) unsafe fn free_pending(pending: Pending) {
+    match pending {
+        Pending::Lobby(tx) => drop(LobbyTransactionObject::take(tx)),
+        Pending::Member(tx) => drop(MemberTransactionObject::take(tx)),
+        Pending::Search(query) => drop(SearchQueryObject::take(query)),
+    }
+}
+
+fn track(instance: &Instance, pending: Pending) {
+    instance.state.borrow_mut().lobbies.pending.push(pending);
+}
+
+// The SDK only hands transactions back to us after they've been given out by `track`
+fn untrack(instance: &Instance, pending: Pending) {
+    let key = |pending: &Pending| match *pending {
+        Pending::Lobby(ptr) => ptr as usize,
+        Pending::Member(ptr) => ptr as usize,
+        Pending::Search(ptr) => ptr as usize,
+    };
+
+    let ptr = key(&pending);
+
+    instance
+        .state
+        .borrow_mut()
+        .lobbies
+        .pending
+        .retain(|pending| key(pending) != ptr);
+}
+
+// Helpers
+
+fn metadata_at(metadata: &BTreeMap<String, String>, index: i32) -> Result<&str> {
+    usize::try_from(index)
+        .ok()
+        .and_then(|index| metadata.keys().nth(index))
+        .map(|key| key.as_str())
+        .ok_or(sys::DiscordResult_NotFound)
+}
+
+fn apply_metadata(metadata: &mut BTreeMap<String, String>, changes: Vec<(String, Option<String>)>) {
+    for (key, value) in changes {
+        match value {
+            Some(value) => {
+                let _ = metadata.insert(key, value);
+            }
+
+            None => {
+                let _ = metadata.remove(&key);
+            }
+        }
+    }
+}
+
+fn fire_lobby_update(instance: &Instance, lobby_id: LobbyID) {
+    instance.event(instance.params.lobby_events, |events, data| unsafe {
+        events.on_lobby_update.unwrap()(data, lobby_id)
+    });
+
+    instance.broadcast(
+        &member_ids(instance, lobby_id),
+        Event::LobbyUpdate { lobby_id },
+    );
+}
+
+fn fire_member_update(instance: &Instance, lobby_id: LobbyID, user_id: UserID) {
+    instance.event(instance.params.lobby_events, |events, data| unsafe {
+        events.on_member_update.unwrap()(data, lobby_id, user_id)
+    });
+
+    instance.broadcast(
+        &member_ids(instance, lobby_id),
+        Event::MemberUpdate {
+            lobby_id,
+            member_id: user_id,
+        },
+    );
+}
+
+fn member_ids(instance: &Instance, lobby_id: LobbyID) -> Vec<UserID> {
+    instance
+        .hub
+        .borrow()
+        .lobbies
+        .get(&lobby_id)
+        .map(|lobby| lobby.members.iter().map(|member| member.user.id).collect())
+        .unwrap_or_default()
+}
+
+fn respond_lobby(
+    instance: &Instance,
+    callback_data: *mut c_void,
+    callback: LobbyCallback,
+    action: impl 'static + FnOnce(&Instance) -> Result<sys::DiscordLobby>,
+) {
+    instance.defer(move |instance, res| {
+        let res = if res == sys::DiscordResult_Ok {
+            action(instance)
+        } else {
+            Err(res)
+        };
+
+        let (res, mut lobby) = match res {
+            Ok(lobby) => (sys::DiscordResult_Ok, lobby),
+            Err(res) => (res, sys::DiscordLobby::default()),
+        };
+
+        unsafe { callback.unwrap()(callback_data, res, &mut lobby) }
+    })
+}
+
+fn with_lobby<R>(
+    instance: &Instance,
+    lobby_id: LobbyID,
+    f: impl FnOnce(&Lobby) -> Result<R>,
+) -> Result<R> {
+    match instance.hub.borrow().lobbies.get(&lobby_id) {
+        Some(lobby) => f(lobby),
+        None => Err(sys::DiscordResult_NotFound),
+    }
+}
+
+fn with_member<R>(
+    instance: &Instance,
+    lobby_id: LobbyID,
+    user_id: UserID,
+    f: impl FnOnce(&Member) -> Result<R>,
+) -> Result<R> {
+    with_lobby(instance, lobby_id, |lobby| {
+        match lobby
+            .members
+            .iter()
+            .find(|member| member.user.id == user_id)
+        {
+            Some(member) => f(member),
+            None => Err(sys::DiscordResult_NotFound),
+        }
+    })
+}
+
+// Lobbies we're a member of
+fn with_own_lobby<R>(
+    instance: &Instance,
+    lobby_id: LobbyID,
+    f: impl FnOnce(&mut Lobby) -> Result<R>,
+) -> Result<R> {
+    let user_id = instance.user_id();
+
+    match instance.hub.borrow_mut().lobbies.get_mut(&lobby_id) {
+        Some(lobby) if lobby.is_member(user_id) => f(lobby),
+        _ => Err(sys::DiscordResult_NotFound),
+    }
+}
+
+// Our own membership in lobbies we're a member of
+fn with_own_member<R>(
+    instance: &Instance,
+    lobby_id: LobbyID,
+    f: impl FnOnce(&mut Member) -> Result<R>,
+) -> Result<R> {
+    let user_id = instance.user_id();
+
+    with_own_lobby(instance, lobby_id, |lobby| {
+        f(lobby.member_mut(user_id).unwrap())
+    })
+}
+
+fn into_result(res: Result<()>) -> sys::EDiscordResult {
+    match res {
+        Ok(()) => sys::DiscordResult_Ok,
+        Err(res) => res,
+    }
+}
+
+impl Lobby {
+    pub(crate) fn is_member(&self, user_id: UserID) -> bool {
+        self.members.iter().any(|member| member.user.id == user_id)
+    }
+
+    fn member_mut(&mut self, user_id: UserID) -> Option<&mut Member> {
+        self.members
+            .iter_mut()
+            .find(|member| member.user.id == user_id)
+    }
+
+    pub(crate) fn activity_secret(&self) -> String {
+        format!(
+            "{}:{}",
+            self.lobby.id,
+            crate::utils::charbuf_to_str(&self.lobby.secret)
+        )
+    }
+
+    // Lobby search filters and sorts work on metadata and a few properties of the lobby
+    fn search_value(&self, key: &str) -> Option<String> {
+        match key {
+            "owner_id" => Some(self.lobby.owner_id.to_string()),
+            "capacity" => Some(self.lobby.capacity.to_string()),
+            "slots" => Some(
+                (self.lobby.capacity as usize)
+                    .saturating_sub(self.members.len())
+                    .to_string(),
+            ),
+            _ => {
+                let key = match key.find('.') {
+                    Some(dot) if &key[..dot] == "metadata" => &key[dot + 1..],
+                    _ => key,
+                };
+
+                self.metadata.get(key).cloned()
+            }
+        }
+    }
+}
+
+fn compare(value: &str, reference: &str, cast: sys::EDiscordLobbySearchCast) -> Option<Ordering> {
+    if cast == sys::DiscordLobbySearchCast_Number {
+        let value = value.parse::<f64>().ok()?;
+        let reference = reference.parse::<f64>().ok()?;
+        value.partial_cmp(&reference)
+    } else {
+        Some(value.cmp(reference))
+    }
+}
+
+fn matches(
+    lobby: &Lobby,
+    filter: &(
+        String,
+        sys::EDiscordLobbySearchComparison,
+        sys::EDiscordLobbySearchCast,
+        String,
+    ),
+) -> bool {
+    let (key, comparison, cast, reference) = filter;
+
+    let ordering = match lobby
+        .search_value(key)
+        .and_then(|value| compare(&value, reference, *cast))
+    {
+        Some(ordering) => ordering,
+        None => return false,
+    };
+
+    match *comparison {
+        sys::DiscordLobbySearchComparison_LessThanOrEqual => ordering != Ordering::Greater,
+        sys::DiscordLobbySearchComparison_LessThan => ordering == Ordering::Less,
+        sys::DiscordLobbySearchComparison_Equal => ordering == Ordering::Equal,
+        sys::DiscordLobbySearchComparison_GreaterThan => ordering == Ordering::Greater,
+        sys::DiscordLobbySearchComparison_GreaterThanOrEqual => ordering != Ordering::Less,
+        sys::DiscordLobbySearchComparison_NotEqual => ordering != Ordering::Equal,
+        _ => false,
+    }
+}
+
+// Sorting is by proximity to the given value, numbers by distance and strings by equality
+// `f64::INFINITY` requires Rust 1.43
+#[allow(clippy::legacy_numeric_constants)]
+fn proximity(lobby: &Lobby, sort: &(String, sys::EDiscordLobbySearchCast, String)) -> f64 {
+    let (key, cast, reference) = sort;
+
+    match lobby.search_value(key) {
+        Some(value) if *cast == sys::DiscordLobbySearchCast_Number => {
+            match (value.parse::<f64>(), reference.parse::<f64>()) {
+                (Ok(value), Ok(reference)) => (value - reference).abs(),
+                _ => std::f64::INFINITY,
+            }
+        }
+        Some(ref value) if value == reference => 0.0,
+        Some(_) => 1.0,
+        None => std::f64::INFINITY,
+    }
+}
+
+fn run_search(instance: &Instance, query: &SearchQuery) -> Vec<LobbyID> {
+    let hub = instance.hub.borrow();
+
+    let mut results = hub
+        .lobbies
+        .values()
+        .filter(|lobby| lobby.lobby.type_ == sys::DiscordLobbyType_Public)
+        .filter(|lobby| query.filters.iter().all(|filter| matches(lobby, filter)))
+        .collect::<Vec<_>>();
+
+    // Stable sort, lobbies keep their creation order on ties
+    results.sort_by(|a, b| {
+        for sort in &query.sorts {
+            let ordering = proximity(a, sort)
+                .partial_cmp(&proximity(b, sort))
+                .unwrap_or(Ordering::Equal);
+
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+
+        Ordering::Equal
+    });
+
+    if let Some(limit) = query.limit {
+        results.truncate(limit as usize);
+    }
+
+    results.into_iter().map(|lobby| lobby.lobby.id).collect()
+}
+
+fn connect(instance: &Instance, lobby_id: LobbyID, secret: &str) -> Result<sys::DiscordLobby> {
+    let current_user = instance.state.borrow().current_user();
+
+    let (lobby, members) = {
+        let mut hub = instance.hub.borrow_mut();
+
+        let lobby = hub
+            .lobbies
+            .get_mut(&lobby_id)
+            .ok_or(sys::DiscordResult_NotFound)?;
+
+        if crate::utils::charbuf_to_str(&lobby.lobby.secret) != secret {
+            return Err(sys::DiscordResult_InvalidLobbySecret);
+        }
+
+        if lobby.is_member(current_user.id) {
+            return Ok(lobby.lobby);
+        }
+
+        if lobby.lobby.locked {
+            return Err(sys::DiscordResult_InvalidPermissions);
+        }
+
+        if lobby.members.len() >= lobby.lobby.capacity as usize {
+            return Err(sys::DiscordResult_LobbyFull);
+        }
+
+        lobby
+            .members
+            .push(Member::new(current_user, BTreeMap::new()));
+
+        (
+            lobby.lobby,
+            lobby
+                .members
+                .iter()
+                .map(|member| member.user.id)
+                .collect::<Vec<_>>(),
+        )
+    };
+
+    instance.broadcast(
+        &members,
+        Event::MemberConnect {
+            lobby_id,
+            member_id: current_user.id,
+        },
+    );
+
+    Ok(lobby)
+}
+
+// Removes the current user from a lobby, the lobby goes away with its last member
+fn leave(instance: &Instance, lobby_id: LobbyID) -> Result<()> {
+    let user_id = instance.user_id();
+
+    let (members, new_owner) = {
+        let mut hub = instance.hub.borrow_mut();
+
+        let lobby = match hub.lobbies.get_mut(&lobby_id) {
+            Some(lobby) if lobby.is_member(user_id) => lobby,
+            _ => return Err(sys::DiscordResult_NotFound),
+        };
+
+        lobby.members.retain(|member| member.user.id != user_id);
+
+        let members = lobby
+            .members
+            .iter()
+            .map(|member| member.user.id)
+            .collect::<Vec<_>>();
+
+        let mut new_owner = false;
+
+        if members.is_empty() {
+            let _ = hub.lobbies.remove(&lobby_id);
+        } else if lobby.lobby.owner_id == user_id {
+            // Ownership goes to whoever has been there the longest
+            lobby.lobby.owner_id = members[0];
+            new_owner = true;
+        }
+
+        (members, new_owner)
+    };
+
+    instance.broadcast(
+        &members,
+        Event::MemberDisconnect {
+            lobby_id,
+            member_id: user_id,
+        },
+    );
+
+    if new_owner {
+        instance.broadcast(&members, Event::LobbyUpdate { lobby_id });
+    }
+
+    Ok(())
+}
+
+/// Leaves every lobby, for when the instance is destroyed
+pub(crate) fn disconnect_all(instance: &Instance) {
+    let user_id = instance.user_id();
+
+    let lobby_ids = instance
+        .hub
+        .borrow()
+        .lobbies
+        .values()
+        .filter(|lobby| lobby.is_member(user_id))
+        .map(|lobby| lobby.lobby.id)
+        .collect::<Vec<_>>();
+
+    for lobby_id in lobby_ids {
+        let _ = leave(instance, lobby_id);
+    }
+}
+
+// Vtable
+
+unsafe extern "C" fn get_lobby_create_transaction(
+    mgr: *mut sys::IDiscordLobbyManager,
+    transaction: *mut *mut sys::IDiscordLobbyTransaction,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("lobbies.get_lobby_create_transaction") {
+        return res;
+    }
+
+    let tx = LobbyTransactionObject::boxed(LOBBY_TRANSACTION, LobbyTransaction::default());
+    track(Instance::from_manager(mgr), Pending::Lobby(tx));
+    *transaction = tx;
+
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn get_lobby_update_transaction(
+    mgr: *mut sys::IDiscordLobbyManager,
+    lobby_id: sys::DiscordLobbyId,
+    transaction: *mut *mut sys::IDiscordLobbyTransaction,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("lobbies.get_lobby_update_transaction") {
+        return res;
+    }
+
+    let instance = Instance::from_manager(mgr);
+
+    if let Err(res) = with_lobby(instance, lobby_id, |_| Ok(())) {
+        return res;
+    }
+
+    let tx = LobbyTransactionObject::boxed(LOBBY_TRANSACTION, LobbyTransaction::default());
+    track(instance, Pending::Lobby(tx));
+    *transaction = tx;
+
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn get_member_update_transaction(
+    mgr: *mut sys::IDiscordLobbyManager,
+    lobby_id: sys::DiscordLobbyId,
+    user_id: sys::DiscordUserId,
+    transaction: *mut *mut sys::IDiscordLobbyMemberTransaction,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("lobbies.get_member_update_transaction") {
+        return res;
+    }
+
+    let instance = Instance::from_manager(mgr);
+
+    if let Err(res) = with_member(instance, lobby_id, user_id, |_| Ok(())) {
+        return res;
+    }
+
+    let tx = MemberTransactionObject::boxed(MEMBER_TRANSACTION, MemberTransaction::default());
+    track(instance, Pending::Member(tx));
+    *transaction = tx;
+
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn create_lobby(
+    mgr: *mut sys::IDiscordLobbyManager,
+    transaction: *mut sys::IDiscordLobbyTransaction,
+    callback_data: *mut c_void,
+    callback: LobbyCallback,
+) {
+    let _fault = Instance::from_manager(mgr).fault_next_callback("lobbies.create_lobby");
+
+    let instance = Instance::from_manager(mgr);
+    untrack(instance, Pending::Lobby(transaction));
+    let tx = LobbyTransactionObject::take(transaction);
+
+    respond_lobby(instance, callback_data, callback, move |instance| {
+        let current_user = instance.state.borrow().current_user();
+        let mut hub = instance.hub.borrow_mut();
+
+        let id = hub.next_lobby_id;
+        hub.next_lobby_id += 1;
+
+        let mut lobby = sys::DiscordLobby {
+            id,
+            type_: tx.kind.unwrap_or(sys::DiscordLobbyType_Private),
+            owner_id: current_user.id,
+            capacity: tx.capacity.unwrap_or(16),
+            locked: tx.locked.unwrap_or(false),
+            ..sys::DiscordLobby::default()
+        };
+        write_str(&mut lobby.secret, &format!("mock-secret-{}", id));
+
+        let mut metadata = BTreeMap::new();
+        apply_metadata(&mut metadata, tx.metadata);
+
+        let _ = hub.lobbies.insert(
+            id,
+            Lobby {
+                lobby,
+                metadata,
+                members: vec![Member::new(current_user, BTreeMap::new())],
+            },
+        );
+
+        Ok(lobby)
+    })
+}
+
+unsafe extern "C" fn update_lobby(
+    mgr: *mut sys::IDiscordLobbyManager,
+    lobby_id: sys::DiscordLobbyId,
+    transaction: *mut sys::IDiscordLobbyTransaction,
+    callback_data: *mut c_void,
+    callback: ResultCallback,
+) {
+    let _fault = Instance::from_manager(mgr).fault_next_callback("lobbies.update_lobby");
+
+    let instance = Instance::from_manager(mgr);
+    untrack(instance, Pending::Lobby(transaction));
+    let tx = LobbyTransactionObject::take(transaction);
+
+    instance.respond(callback_data, callback, move |instance| {
+        let current_user = instance.state.borrow().current_user();
+
+        let res = with_own_lobby(instance, lobby_id, |lobby| {
+            if lobby.lobby.owner_id != current_user.id {
+                return Err(sys::DiscordResult_InvalidPermissions);
+            }
+
+            if let Some(owner) = tx.owner {
+                if !lobby.is_member(owner) {
+                    return Err(sys::DiscordResult_InvalidPayload);
+                }
+
+                lobby.lobby.owner_id = owner;
+            }
+
+            if let Some(kind) = tx.kind {
+                lobby.lobby.type_ = kind;
+            }
+
+            if let Some(capacity) = tx.capacity {
+                lobby.lobby.capacity = capacity;
+            }
+
+            if let Some(locked) = tx.locked {
+                lobby.lobby.locked = locked;
+            }
+
+            apply_metadata(&mut lobby.metadata, tx.metadata);
+
+            Ok(())
+        });
+
+        if res.is_ok() {
+            fire_lobby_update(instance, lobby_id);
+        }
+
+        into_result(res)
+    })
+}
+
+unsafe extern "C" fn delete_lobby(
+    mgr: *mut sys::IDiscordLobbyManager,
+    lobby_id: sys::DiscordLobbyId,
+    callback_data: *mut c_void,
+    callback: ResultCallback,
+) {
+    let _fault = Instance::from_manager(mgr).fault_next_callback("lobbies.delete_lobby");
+
+    Instance::from_manager(mgr).respond(callback_data, callback, move |instance| {
+        let user_id = instance.user_id();
+
+        let removed = instance.hub.borrow_mut().lobbies.remove(&lobby_id);
+
+        let members = match removed {
+            Some(lobby) if lobby.lobby.owner_id != user_id => {
+                let _ = instance.hub.borrow_mut().lobbies.insert(lobby_id, lobby);
+                return sys::DiscordResult_InvalidPermissions;
+            }
+            Some(lobby) => lobby
+                .members
+                .iter()
+                .map(|member| member.user.id)
+                .collect::<Vec<_>>(),
+            None => return sys::DiscordResult_NotFound,
+        };
+
+        instance
+            .state
+            .borrow_mut()
+            .lobbies
+            .search_results
+            .retain(|id| *id != lobby_id);
+
+        instance.event(instance.params.lobby_events, |events, data| unsafe {
+            events.on_lobby_delete.unwrap()(data, lobby_id, 0)
+        });
+
+        instance.broadcast(
+            &members,
+            Event::LobbyDelete {
+                lobby_id,
+                reason: 0,
+            },
+        );
+
+        sys::DiscordResult_Ok
+    })
+}
+
+unsafe extern "C" fn connect_lobby(
+    mgr: *mut sys::IDiscordLobbyManager,
+    lobby_id: sys::DiscordLobbyId,
+    secret: *mut u8,
+    callback_data: *mut c_void,
+    callback: LobbyCallback,
+) {
+    let _fault = Instance::from_manager(mgr).fault_next_callback("lobbies.connect_lobby");
+
+    let secret = read_str(secret);
+
+    respond_lobby(
+        Instance::from_manager(mgr),
+        callback_data,
+        callback,
+        move |instance| connect(instance, lobby_id, &secret),
+    )
+}
+
+unsafe extern "C" fn connect_lobby_with_activity_secret(
+    mgr: *mut sys::IDiscordLobbyManager,
+    activity_secret: *mut u8,
+    callback_data: *mut c_void,
+    callback: LobbyCallback,
+) {
+    let _fault = Instance::from_manager(mgr)
+        .fault_next_callback("lobbies.connect_lobby_with_activity_secret");
+
+    let activity_secret = read_str(activity_secret);
+
+    respond_lobby(
+        Instance::from_manager(mgr),
+        callback_data,
+        callback,
+        move |instance| {
+            let mut parts = activity_secret.splitn(2, ':');
+
+            match (parts.next().and_then(|id| id.parse().ok()), parts.next()) {
+                (Some(lobby_id), Some(secret)) => connect(instance, lobby_id, secret),
+                _ => Err(sys::DiscordResult_InvalidLobbySecret),
+            }
+        },
+    )
+}
+
+unsafe extern "C" fn disconnect_lobby(
+    mgr: *mut sys::IDiscordLobbyManager,
+    lobby_id: sys::DiscordLobbyId,
+    callback_data: *mut c_void,
+    callback: ResultCallback,
+) {
+    let _fault = Instance::from_manager(mgr).fault_next_callback("lobbies.disconnect_lobby");
+
+    Instance::from_manager(mgr).respond(callback_data, callback, move |instance| {
+        into_result(leave(instance, lobby_id))
+    })
+}
+
+unsafe extern "C" fn get_lobby(
+    mgr: *mut sys::IDiscordLobbyManager,
+    lobby_id: sys::DiscordLobbyId,
+    lobby: *mut sys::DiscordLobby,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("lobbies.get_lobby") {
+        return res;
+    }
+
+    into_result(with_lobby(Instance::from_manager(mgr), lobby_id, |found| {
+        *lobby = found.lobby;
+        Ok(())
+    }))
+}
+
+unsafe extern "C" fn get_lobby_activity_secret(
+    mgr: *mut sys::IDiscordLobbyManager,
+    lobby_id: sys::DiscordLobbyId,
+    secret: *mut sys::DiscordLobbySecret,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("lobbies.get_lobby_activity_secret") {
+        return res;
+    }
+
+    into_result(with_lobby(Instance::from_manager(mgr), lobby_id, |lobby| {
+        write_str(&mut *secret, &lobby.activity_secret());
+        Ok(())
+    }))
+}
+
+unsafe extern "C" fn get_lobby_metadata_value(
+    mgr: *mut sys::IDiscordLobbyManager,
+    lobby_id: sys::DiscordLobbyId,
+    key: *mut u8,
+    value: *mut sys::DiscordMetadataValue,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("lobbies.get_lobby_metadata_value") {
+        return res;
+    }
+
+    let key = read_str(key);
+
+    into_result(with_lobby(Instance::from_manager(mgr), lobby_id, |lobby| {
+        let found = lobby
+            .metadata
+            .get(&key)
+            .ok_or(sys::DiscordResult_NotFound)?;
+        write_str(&mut *value, found);
+        Ok(())
+    }))
+}
+
+unsafe extern "C" fn get_lobby_metadata_key(
+    mgr: *mut sys::IDiscordLobbyManager,
+    lobby_id: sys::DiscordLobbyId,
+    index: i32,
+    key: *mut sys::DiscordMetadataKey,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("lobbies.get_lobby_metadata_key") {
+        return res;
+    }
+
+    into_result(with_lobby(Instance::from_manager(mgr), lobby_id, |lobby| {
+        write_str(&mut *key, metadata_at(&lobby.metadata, index)?);
+        Ok(())
+    }))
+}
+
+unsafe extern "C" fn lobby_metadata_count(
+    mgr: *mut sys::IDiscordLobbyManager,
+    lobby_id: sys::DiscordLobbyId,
+    count: *mut i32,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("lobbies.lobby_metadata_count") {
+        return res;
+    }
+
+    into_result(with_lobby(Instance::from_manager(mgr), lobby_id, |lobby| {
+        *count = i32::try_from(lobby.metadata.len()).unwrap();
+        Ok(())
+    }))
+}
+
+unsafe extern "C" fn member_count(
+    mgr: *mut sys::IDiscordLobbyManager,
+    lobby_id: sys::DiscordLobbyId,
+    count: *mut i32,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("lobbies.member_count") {
+        return res;
+    }
+
+    into_result(with_lobby(Instance::from_manager(mgr), lobby_id, |lobby| {
+        *count = i32::try_from(lobby.members.len()).unwrap();
+        Ok(())
+    }))
+}
+
+unsafe extern "C" fn get_member_user_id(
+    mgr: *mut sys::IDiscordLobbyManager,
+    lobby_id: sys::DiscordLobbyId,
+    index: i32,
+    user_id: *mut sys::DiscordUserId,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("lobbies.get_member_user_id") {
+        return res;
+    }
+
+    into_result(with_lobby(Instance::from_manager(mgr), lobby_id, |lobby| {
+        let member = usize::try_from(index)
+            .ok()
+            .and_then(|index| lobby.members.get(index))
+            .ok_or(sys::DiscordResult_NotFound)?;
+
+        *user_id = member.user.id;
+        Ok(())
+    }))
+}
+
+unsafe extern "C" fn get_member_user(
+    mgr: *mut sys::IDiscordLobbyManager,
+    lobby_id: sys::DiscordLobbyId,
+    user_id: sys::DiscordUserId,
+    user: *mut sys::DiscordUser,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("lobbies.get_member_user") {
+        return res;
+    }
+
+    into_result(with_member(
+        Instance::from_manager(mgr),
+        lobby_id,
+        user_id,
+        |member| {
+            *user = member.user;
+            Ok(())
+        },
+    ))
+}
+
+unsafe extern "C" fn get_member_metadata_value(
+    mgr: *mut sys::IDiscordLobbyManager,
+    lobby_id: sys::DiscordLobbyId,
+    user_id: sys::DiscordUserId,
+    key: *mut u8,
+    value: *mut sys::DiscordMetadataValue,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("lobbies.get_member_metadata_value") {
+        return res;
+    }
+
+    let key = read_str(key);
+
+    into_result(with_member(
+        Instance::from_manager(mgr),
+        lobby_id,
+        user_id,
+        |member| {
+            let found = member
+                .metadata
+                .get(&key)
+                .ok_or(sys::DiscordResult_NotFound)?;
+            write_str(&mut *value, found);
+            Ok(())
+        },
+    ))
+}
+
+unsafe extern "C" fn get_member_metadata_key(
+    mgr: *mut sys::IDiscordLobbyManager,
+    lobby_id: sys::DiscordLobbyId,
+    user_id: sys::DiscordUserId,
+    index: i32,
+    key: *mut sys::DiscordMetadataKey,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("lobbies.get_member_metadata_key") {
+        return res;
+    }
+
+    into_result(with_member(
+        Instance::from_manager(mgr),
+        lobby_id,
+        user_id,
+        |member| {
+            write_str(&mut *key, metadata_at(&member.metadata, index)?);
+            Ok(())
+        },
+    ))
+}
+
+unsafe extern "C" fn member_metadata_count(
+    mgr: *mut sys::IDiscordLobbyManager,
+    lobby_id: sys::DiscordLobbyId,
+    user_id: sys::DiscordUserId,
+    count: *mut i32,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("lobbies.member_metadata_count") {
+        return res;
+    }
+
+    into_result(with_member(
+        Instance::from_manager(mgr),
+        lobby_id,
+        user_id,
+        |member| {
+            *count = i32::try_from(member.metadata.len()).unwrap();
+            Ok(())
+        },
+    ))
+}
+
+unsafe extern "C" fn update_member(
+    mgr: *mut sys::IDiscordLobbyManager,
+    lobby_id: sys::DiscordLobbyId,
+    user_id: sys::DiscordUserId,
+    transaction: *mut sys::IDiscordLobbyMemberTransaction,
+    callback_data: *mut c_void,
+    callback: ResultCallback,
+) {
+    let _fault = Instance::from_manager(mgr).fault_next_callback("lobbies.update_member");
+
+    let instance = Instance::from_manager(mgr);
+    untrack(instance, Pending::Member(transaction));
+    let tx = MemberTransactionObject::take(transaction);
+
+    instance.respond(callback_data, callback, move |instance| {
+        let current_user = instance.state.borrow().current_user();
+
+        let res = with_own_lobby(instance, lobby_id, |lobby| {
+            // Only the member themselves and the owner may update a member
+            if user_id != current_user.id && lobby.lobby.owner_id != current_user.id {
+                return Err(sys::DiscordResult_InvalidPermissions);
+            }
+
+            let member = lobby
+                .member_mut(user_id)
+                .ok_or(sys::DiscordResult_NotFound)?;
+
+            apply_metadata(&mut member.metadata, tx.metadata);
+
+            Ok(())
+        });
+
+        if res.is_ok() {
+            fire_member_update(instance, lobby_id, user_id);
+        }
+
+        into_result(res)
+    })
+}
+
+unsafe extern "C" fn send_lobby_message(
+    mgr: *mut sys::IDiscordLobbyManager,
+    lobby_id: sys::DiscordLobbyId,
+    data: *mut u8,
+    data_length: u32,
+    callback_data: *mut c_void,
+    callback: ResultCallback,
+) {
+    let _fault = Instance::from_manager(mgr).fault_next_callback("lobbies.send_lobby_message");
+
+    let data = read_bytes(data, data_length);
+
+    Instance::from_manager(mgr).respond(callback_data, callback, move |instance| {
+        if let Err(res) = with_own_lobby(instance, lobby_id, |_| Ok(())) {
+            return res;
+        }
+
+        instance.broadcast(
+            &member_ids(instance, lobby_id),
+            Event::LobbyMessage {
+                lobby_id,
+                member_id: instance.user_id(),
+                data: data.clone(),
+            },
+        );
+
+        instance
+            .state
+            .borrow_mut()
+            .lobbies
+            .messages
+            .push((lobby_id, data));
+
+        sys::DiscordResult_Ok
+    })
+}
+
+unsafe extern "C" fn get_search_query(
+    mgr: *mut sys::IDiscordLobbyManager,
+    query: *mut *mut sys::IDiscordLobbySearchQuery,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("lobbies.get_search_query") {
+        return res;
+    }
+
+    let search = SearchQueryObject::boxed(SEARCH_QUERY, SearchQuery::default());
+    track(Instance::from_manager(mgr), Pending::Search(search));
+    *query = search;
+
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn search(
+    mgr: *mut sys::IDiscordLobbyManager,
+    query: *mut sys::IDiscordLobbySearchQuery,
+    callback_data: *mut c_void,
+    callback: ResultCallback,
+) {
+    let _fault = Instance::from_manager(mgr).fault_next_callback("lobbies.search");
+
+    let instance = Instance::from_manager(mgr);
+    untrack(instance, Pending::Search(query));
+    let query = SearchQueryObject::take(query);
+
+    instance.respond(callback_data, callback, move |instance| {
+        let results = run_search(instance, &query);
+        instance.state.borrow_mut().lobbies.search_results = results;
+
+        sys::DiscordResult_Ok
+    })
+}
+
+unsafe extern "C" fn lobby_count(mgr: *mut sys::IDiscordLobbyManager, count: *mut i32) {
+    let state = Instance::from_manager(mgr).state.borrow();
+
+    *count = i32::try_from(state.lobbies.search_results.len()).unwrap();
+}
+
+unsafe extern "C" fn get_lobby_id(
+    mgr: *mut sys::IDiscordLobbyManager,
+    index: i32,
+    lobby_id: *mut sys::DiscordLobbyId,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("lobbies.get_lobby_id") {
+        return res;
+    }
+
+    let state = Instance::from_manager(mgr).state.borrow();
+
+    match usize::try_from(index)
+        .ok()
+        .and_then(|index| state.lobbies.search_results.get(index))
+    {
+        Some(id) => {
+            *lobby_id = *id;
+            sys::DiscordResult_Ok
+        }
+
+        None => sys::DiscordResult_NotFound,
+    }
+}
+
+unsafe extern "C" fn connect_voice(
+    mgr: *mut sys::IDiscordLobbyManager,
+    lobby_id: sys::DiscordLobbyId,
+    callback_data: *mut c_void,
+    callback: ResultCallback,
+) {
+    let _fault = Instance::from_manager(mgr).fault_next_callback("lobbies.connect_voice");
+
+    Instance::from_manager(mgr).respond(callback_data, callback, move |instance| {
+        into_result(with_own_member(instance, lobby_id, |member| {
+            member.voice = true;
+            Ok(())
+        }))
+    })
+}
+
+unsafe extern "C" fn disconnect_voice(
+    mgr: *mut sys::IDiscordLobbyManager,
+    lobby_id: sys::DiscordLobbyId,
+    callback_data: *mut c_void,
+    callback: ResultCallback,
+) {
+    let _fault = Instance::from_manager(mgr).fault_next_callback("lobbies.disconnect_voice");
+
+    Instance::from_manager(mgr).respond(callback_data, callback, move |instance| {
+        into_result(with_own_member(instance, lobby_id, |member| {
+            member.voice = false;
+            Ok(())
+        }))
+    })
+}
+
+unsafe extern "C" fn connect_network(
+    mgr: *mut sys::IDiscordLobbyManager,
+    lobby_id: sys::DiscordLobbyId,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("lobbies.connect_network") {
+        return res;
+    }
+
+    into_result(with_own_member(
+        Instance::from_manager(mgr),
+        lobby_id,
+        |member| {
+            member.network = true;
+            Ok(())
+        },
+    ))
+}
+
+unsafe extern "C" fn disconnect_network(
+    mgr: *mut sys::IDiscordLobbyManager,
+    lobby_id: sys::DiscordLobbyId,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("lobbies.disconnect_network") {
+        return res;
+    }
+
+    into_result(with_own_member(
+        Instance::from_manager(mgr),
+        lobby_id,
+        |member| {
+            member.network = false;
+            member.channels.clear();
+            Ok(())
+        },
+    ))
+}
+
+unsafe extern "C" fn flush_network(mgr: *mut sys::IDiscordLobbyManager) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("lobbies.flush_network") {
+        return res;
+    }
+
+    // Messages are never buffered
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn open_network_channel(
+    mgr: *mut sys::IDiscordLobbyManager,
+    lobby_id: sys::DiscordLobbyId,
+    channel_id: u8,
+    reliable: bool,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("lobbies.open_network_channel") {
+        return res;
+    }
+
+    into_result(with_own_member(
+        Instance::from_manager(mgr),
+        lobby_id,
+        |member| {
+            if !member.network {
+                return Err(sys::DiscordResult_NotFound);
+            }
+
+            let _ = member.channels.insert(channel_id, reliable);
+            Ok(())
+        },
+    ))
+}
+
+unsafe extern "C" fn send_network_message(
+    mgr: *mut sys::IDiscordLobbyManager,
+    lobby_id: sys::DiscordLobbyId,
+    user_id: sys::DiscordUserId,
+    channel_id: u8,
+    data: *mut u8,
+    data_length: u32,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("lobbies.send_network_message") {
+        return res;
+    }
+
+    let instance = Instance::from_manager(mgr);
+    let sender_id = instance.user_id();
+
+    let res = with_own_lobby(instance, lobby_id, |lobby| {
+        if !lobby.is_member(user_id) {
+            return Err(sys::DiscordResult_NotFound);
+        }
+
+        let sender = lobby.member_mut(sender_id).unwrap();
+
+        if !sender.network {
+            return Err(sys::DiscordResult_NotFound);
+        }
+
+        if !sender.channels.contains_key(&channel_id) {
+            return Err(sys::DiscordResult_InvalidChannel);
+        }
+
+        Ok(())
+    });
+
+    if res.is_err() {
+        return into_result(res);
+    }
+
+    let data = read_bytes(data, data_length);
+
+    // Like the SDK, messages to members that are not listening on the channel are dropped
+    if let Some(recipient) = instance.hub.borrow().instance(user_id) {
+        let data = data.clone();
+
+        recipient.emit(move |recipient| {
+            let listening = with_own_member(recipient, lobby_id, |member| {
+                Ok(member.network && member.channels.contains_key(&channel_id))
+            });
+
+            if listening == Ok(true) {
+                inject::fire(
+                    recipient,
+                    Event::LobbyNetworkMessage {
+                        lobby_id,
+                        member_id: sender_id,
+                        channel_id,
+                        data,
+                    },
+                );
+            }
+        });
+    }
+
+    instance
+        .state
+        .borrow_mut()
+        .lobbies
+        .network_messages
+        .push((lobby_id, user_id, channel_id, data));
+
+    sys::DiscordResult_Ok
+}
@@ -0,0 +1,108 @@
+use crate::mock::hub::Hub;
+#[cfg(test)]
+use crate::{Discord, EventQueue, MockState};
+use std::{cell::RefCell, rc::Rc};
+
+/// In-process stand-in for Discord's servers, shared by several mock instances
+///
+/// Instances created with [`Discord::with_mock_hub`](struct.Discord.html#method.with_mock_hub)
+/// on the same hub see the same lobbies. They receive each other's lobby messages,
+/// lobby network messages and peer-to-peer messages, and are told when members connect,
+/// disconnect or update a lobby, as with the SDK.
+///
+/// Each instance must run as a different user, see
+/// [`MockState::with_current_user`](struct.MockState.html#method.with_current_user).
+/// An instance that is dropped leaves all of its lobbies.
+///
+/// ```rust
+/// # use discord_game_sdk::*;
+/// # use std::{cell::RefCell, rc::Rc};
+/// # fn example() -> Result<()> {
+/// #[derive(Default)]
+/// struct Inbox(Vec<(UserID, Vec<u8>)>);
+///
+/// impl EventHandler for Inbox {
+///     fn on_lobby_message(
+///         &mut self,
+///         _: &Discord<'_, Self>,
+///         _: LobbyID,
+///         member_id: UserID,
+///         data: &[u8],
+///     ) {
+///         self.0.push((member_id, data.to_vec()));
+///     }
+/// }
+///
+/// let hub = MockHub::new();
+///
+/// let mut alice = Discord::<Inbox>::with_mock_hub(
+///     &hub,
+///     MockState::new().with_current_user(1, "alice", "0001"),
+/// );
+/// let mut bob = Discord::<Inbox>::with_mock_hub(
+///     &hub,
+///     MockState::new().with_current_user(2, "bob", "0002"),
+/// );
+/// *bob.event_handler_mut() = Some(Inbox::default());
+///
+/// let lobby = Rc::new(RefCell::new(None));
+/// let created = lobby.clone();
+/// alice.create_lobby(&LobbyTransaction::new(), move |_, result| {
+///     *created.borrow_mut() = Some(result.unwrap().clone());
+/// });
+/// alice.run_callbacks()?;
+///
+/// let lobby = lobby.borrow().clone().unwrap();
+/// bob.connect_lobby(lobby.id(), lobby.secret(), |_, result| assert!(result.is_ok()));
+/// bob.run_callbacks()?;
+///
+/// alice.send_lobby_message(lobby.id(), b"hello", |_, result| assert!(result.is_ok()));
+/// alice.run_callbacks()?;
+/// bob.run_callbacks()?;
+///
+/// assert_eq!(bob.event_handler().as_ref().unwrap().0, [(1, b"hello".to_vec())]);
+/// # Ok(()) }
+/// # example().unwrap();
+/// ```
+#[derive(Clone)]
+pub struct MockHub(pub(crate) Rc<RefCell<Hub>>);
+
+impl MockHub {
+    /// A hub without lobbies, lobbies of the instances' [`MockState`](struct.MockState.html)
+    /// are added as they are created
+    pub fn new() -> Self {
+        Self(Rc::new(RefCell::new(Hub::new())))
+    }
+}
+
+#[cfg(test)]
+impl MockHub {
+    // An instance on the hub that queues its events, for tests with several members
+    pub(crate) fn member<'d>(&self, state: &MockState) -> Discord<'d, EventQueue> {
+        let mut discord = Discord::with_mock_hub(self, state);
+        *discord.event_handler_mut() = Some(EventQueue::new());
+        discord
+    }
+}
+
+impl Default for MockHub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for MockHub {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let hub = self.0.borrow();
+
+        fmt.debug_struct("MockHub")
+            .field(
+                "user_ids",
+                &hub.instances()
+                    .map(|instance| instance.user_id())
+                    .collect::<Vec<_>>(),
+            )
+            .field("lobby_ids", &hub.lobbies.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
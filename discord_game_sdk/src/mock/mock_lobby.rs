@@ -0,0 +1,102 @@
+use crate::{LobbyID, LobbyKind, UserID};
+use std::collections::BTreeMap;
+
+/// Lobby that exists before the code under test runs
+///
+/// The owner is always a member, other members are added with
+/// [`with_member`](#method.with_member).
+///
+/// ```rust
+/// # use discord_game_sdk::*;
+/// let mut lobby = MockLobby::new(42, 1);
+/// lobby
+///     .with_kind(LobbyKind::Public)
+///     .with_capacity(4)
+///     .with_metadata("map", "de_dust2")
+///     .with_member(2);
+///
+/// let discord = Discord::<()>::with_mock_state(MockState::new().with_lobby(&lobby));
+/// assert_eq!(discord.lobby(42).unwrap().capacity(), 4);
+/// ```
+#[derive(Clone, Debug)]
+pub struct MockLobby {
+    pub(crate) id: LobbyID,
+    pub(crate) kind: LobbyKind,
+    pub(crate) owner_id: UserID,
+    pub(crate) secret: String,
+    pub(crate) capacity: u32,
+    pub(crate) locked: bool,
+    pub(crate) metadata: BTreeMap<String, String>,
+    pub(crate) members: Vec<(UserID, BTreeMap<String, String>)>,
+}
+
+impl MockLobby {
+    /// A private, unlocked lobby with a capacity of 16 and `owner_id` as its only member
+    pub fn new(id: LobbyID, owner_id: UserID) -> Self {
+        Self {
+            id,
+            kind: LobbyKind::Private,
+            owner_id,
+            secret: format!("mock-secret-{}", id),
+            capacity: 16,
+            locked: false,
+            metadata: BTreeMap::new(),
+            members: vec![(owner_id, BTreeMap::new())],
+        }
+    }
+
+    /// Marks the lobby as private or public
+    pub fn with_kind(&mut self, kind: LobbyKind) -> &mut Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Sets the password to the lobby
+    pub fn with_secret(&mut self, secret: &str) -> &mut Self {
+        self.secret = secret.to_string();
+        self
+    }
+
+    /// Sets the maximum amount of players that can join
+    pub fn with_capacity(&mut self, capacity: u32) -> &mut Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Sets whether the lobby can be joined
+    pub fn with_locked(&mut self, locked: bool) -> &mut Self {
+        self.locked = locked;
+        self
+    }
+
+    /// Sets a metadata value under a given key for the lobby
+    pub fn with_metadata(&mut self, key: &str, value: &str) -> &mut Self {
+        let _ = self.metadata.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    /// Adds a member to the lobby, does nothing if they already are
+    ///
+    /// The member's [`User`](struct.User.html) is looked up from the
+    /// [`MockState`](struct.MockState.html) the lobby is added to.
+    pub fn with_member(&mut self, user_id: UserID) -> &mut Self {
+        if !self.members.iter().any(|(id, _)| *id == user_id) {
+            self.members.push((user_id, BTreeMap::new()));
+        }
+
+        self
+    }
+
+    /// Sets a metadata value under a given key for a member, adding them if needed
+    pub fn with_member_metadata(&mut self, user_id: UserID, key: &str, value: &str) -> &mut Self {
+        self.with_member(user_id);
+
+        for (id, metadata) in &mut self.members {
+            if *id == user_id {
+                let _ = metadata.insert(key.to_string(), value.to_string());
+            }
+        }
+
+        self
+    }
+}
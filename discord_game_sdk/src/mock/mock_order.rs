@@ -0,0 +1,15 @@
+/// Order in which the mock runs the callbacks and events that are due
+///
+/// Callbacks scheduled with a latency are only ever reordered with the ones that become
+/// due during the same call to [`run_callbacks`](struct.Discord.html#method.run_callbacks).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum MockOrder {
+    /// In the order they were scheduled, like the SDK does
+    Fifo,
+
+    /// Most recently scheduled first
+    Lifo,
+
+    /// Shuffled, the same seed always gives the same order
+    Shuffle(u64),
+}
@@ -0,0 +1,407 @@
+use crate::{
+    mock::{
+        ffi::write_str,
+        state::{self, File, Member, State},
+        MockLobby,
+    },
+    sys, Activity, Entitlement, EntitlementKind, Lobby, LobbyID, NetworkPeerID, PremiumKind,
+    Relationship, RelationshipKind, Sku, SkuKind, Snowflake, Status, User, UserAchievement,
+    UserFlags, UserID,
+};
+
+/// The world as seen by a mock instance
+///
+/// Used to seed [`Discord::with_mock_state`](struct.Discord.html#method.with_mock_state)
+/// and returned by [`Discord::mock_state`](struct.Discord.html#method.mock_state) to assert
+/// against what the code under test did.
+///
+/// As with the SDK, SKUs, entitlements and achievements are only visible to the code under test
+/// once they have been fetched.
+///
+/// ```rust
+/// # use discord_game_sdk::*;
+/// # fn example() -> Result<()> {
+/// let mut state = MockState::new();
+/// state
+///     .with_current_user(100, "alice", "1234")
+///     .with_relationship(
+///         RelationshipKind::Friend,
+///         200,
+///         "bob",
+///         "5678",
+///         Status::Online,
+///         Activity::empty().with_state("In Menus"),
+///     )
+///     .with_achievement(7, 50)
+///     .with_file("save.dat", b"hello");
+///
+/// let mut discord = Discord::<()>::with_mock_state(&state);
+///
+/// discord.fetch_user_achievements(|discord, _| {
+///     discord.set_user_achievement(7, 100, |_, _| {});
+/// });
+///
+/// discord.run_callbacks()?;
+/// discord.run_callbacks()?;
+///
+/// let state = discord.mock_state();
+/// assert_eq!(state.achievements()[0].percent_complete(), 100);
+/// assert_eq!(state.file("save.dat"), Some(&b"hello"[..]));
+/// # Ok(()) }
+/// # example().unwrap();
+/// ```
+#[derive(Clone)]
+pub struct MockState(pub(crate) State);
+
+impl MockState {
+    /// The current user is `mock#0001` with ID `1`, there is nothing else
+    pub fn new() -> Self {
+        Self(State::default())
+    }
+
+    /// Sets the user the SDK is running as
+    pub fn with_current_user(
+        &mut self,
+        user_id: UserID,
+        username: &str,
+        discriminator: &str,
+    ) -> &mut Self {
+        let state = &mut self.0;
+
+        state.users.current = user(user_id, username, discriminator);
+        state.network.peer_id = user_id as NetworkPeerID;
+        state.network.route = format!("mock-route-{}", user_id);
+
+        for achievement in &mut state.achievements.list {
+            achievement.user_id = user_id;
+        }
+
+        self
+    }
+
+    /// Sets the current user's premium subscription
+    pub fn with_premium_kind(&mut self, kind: PremiumKind) -> &mut Self {
+        self.0.users.premium_type = kind.into();
+        self
+    }
+
+    /// Sets the current user's flags
+    pub fn with_user_flags(&mut self, flags: UserFlags) -> &mut Self {
+        self.0.users.flags = flags.bits();
+        self
+    }
+
+    /// Adds a user that can be fetched with [`Discord::user`](struct.Discord.html#method.user)
+    pub fn with_user(&mut self, user_id: UserID, username: &str, discriminator: &str) -> &mut Self {
+        let _ = self
+            .0
+            .users
+            .known
+            .insert(user_id, user(user_id, username, discriminator));
+
+        self
+    }
+
+    /// Adds or replaces a relationship with a user and their presence
+    pub fn with_relationship(
+        &mut self,
+        kind: RelationshipKind,
+        user_id: UserID,
+        username: &str,
+        discriminator: &str,
+        status: Status,
+        activity: &Activity,
+    ) -> &mut Self {
+        let relationship = sys::DiscordRelationship {
+            type_: kind.into(),
+            user: user(user_id, username, discriminator),
+            presence: sys::DiscordPresence {
+                status: status.into(),
+                activity: activity.0,
+            },
+        };
+
+        let list = &mut self.0.relationships.list;
+
+        match list.iter_mut().find(|r| r.user.id == user_id) {
+            Some(existing) => *existing = relationship,
+            None => list.push(relationship),
+        }
+
+        self
+    }
+
+    /// Adds a SKU to the store
+    pub fn with_sku(
+        &mut self,
+        sku_id: Snowflake,
+        kind: SkuKind,
+        name: &str,
+        price_amount: u32,
+        price_currency: &str,
+    ) -> &mut Self {
+        let mut sku = sys::DiscordSku {
+            id: sku_id,
+            type_: kind.into(),
+            ..sys::DiscordSku::default()
+        };
+        write_str(&mut sku.name, name);
+        sku.price.amount = price_amount;
+        write_str(&mut sku.price.currency, price_currency);
+
+        self.0.store.skus.push(sku);
+        self
+    }
+
+    /// Gives the current user an entitlement to a SKU
+    pub fn with_entitlement(
+        &mut self,
+        entitlement_id: Snowflake,
+        kind: EntitlementKind,
+        sku_id: Snowflake,
+    ) -> &mut Self {
+        let store = &mut self.0.store;
+
+        store.entitlements.push(sys::DiscordEntitlement {
+            id: entitlement_id,
+            type_: kind.into(),
+            sku_id,
+        });
+
+        store.next_entitlement_id = store.next_entitlement_id.max(entitlement_id + 1);
+        self
+    }
+
+    /// Stores a file, replacing any file with the same name
+    pub fn with_file(&mut self, filename: &str, contents: &[u8]) -> &mut Self {
+        let storage = &mut self.0.storage;
+
+        storage.clock += 1;
+
+        let _ = storage.files.insert(
+            filename.to_string(),
+            File {
+                data: contents.to_vec(),
+                last_modified: storage.clock,
+            },
+        );
+
+        self
+    }
+
+    /// Defines an achievement and the current user's progress towards it
+    ///
+    /// `percent_complete` is clamped to 100, reaching 100 unlocks the achievement.
+    pub fn with_achievement(
+        &mut self,
+        achievement_id: Snowflake,
+        percent_complete: u8,
+    ) -> &mut Self {
+        let mut achievement = sys::DiscordUserAchievement {
+            user_id: self.0.users.current.id,
+            achievement_id,
+            percent_complete: percent_complete.min(100),
+            unlocked_at: [0; 64],
+        };
+
+        if achievement.percent_complete == 100 {
+            write_str(&mut achievement.unlocked_at, "1970-01-01T00:00:00+00:00");
+        }
+
+        let list = &mut self.0.achievements.list;
+
+        match list.iter_mut().find(|a| a.achievement_id == achievement_id) {
+            Some(existing) => *existing = achievement,
+            None => list.push(achievement),
+        }
+
+        self
+    }
+
+    /// Adds or replaces a lobby
+    ///
+    /// Members are looked up among the users known at this point,
+    /// those who are not known will only have an ID.
+    pub fn with_lobby(&mut self, lobby: &MockLobby) -> &mut Self {
+        let state = &mut self.0;
+
+        let mut data = sys::DiscordLobby {
+            id: lobby.id,
+            type_: lobby.kind.into(),
+            owner_id: lobby.owner_id,
+            capacity: lobby.capacity,
+            locked: lobby.locked,
+            ..sys::DiscordLobby::default()
+        };
+        write_str(&mut data.secret, &lobby.secret);
+
+        let members = lobby
+            .members
+            .iter()
+            .map(|(user_id, metadata)| {
+                Member::new(
+                    state.find_user(*user_id).unwrap_or(sys::DiscordUser {
+                        id: *user_id,
+                        ..sys::DiscordUser::default()
+                    }),
+                    metadata.clone(),
+                )
+            })
+            .collect();
+
+        let _ = state.lobbies.list.insert(
+            lobby.id,
+            state::Lobby {
+                lobby: data,
+                metadata: lobby.metadata.clone(),
+                members,
+            },
+        );
+
+        self
+    }
+
+    /// The user the SDK is running as
+    pub fn current_user(&self) -> User {
+        User(self.0.users.current)
+    }
+
+    /// All relationships, regardless of filters
+    pub fn relationships(&self) -> Vec<Relationship> {
+        self.0
+            .relationships
+            .list
+            .iter()
+            .map(|relationship| Relationship(*relationship))
+            .collect()
+    }
+
+    /// The activity set with [`Discord::update_activity`](struct.Discord.html#method.update_activity)
+    pub fn activity(&self) -> Option<Activity> {
+        self.0.activities.current.map(Activity)
+    }
+
+    /// All SKUs in the store
+    pub fn skus(&self) -> Vec<Sku> {
+        self.0.store.skus.iter().map(|sku| Sku(*sku)).collect()
+    }
+
+    /// All entitlements of the current user, including those from purchases
+    pub fn entitlements(&self) -> Vec<Entitlement> {
+        self.0
+            .store
+            .entitlements
+            .iter()
+            .map(|entitlement| Entitlement(*entitlement))
+            .collect()
+    }
+
+    /// The contents of a stored file
+    pub fn file(&self, filename: &str) -> Option<&[u8]> {
+        self.0
+            .storage
+            .files
+            .get(filename)
+            .map(|file| file.data.as_slice())
+    }
+
+    /// The names of all stored files, in alphabetical order
+    pub fn filenames(&self) -> Vec<&str> {
+        self.0.storage.files.keys().map(String::as_str).collect()
+    }
+
+    /// All achievements and the current user's progress towards them
+    pub fn achievements(&self) -> Vec<UserAchievement> {
+        self.0
+            .achievements
+            .list
+            .iter()
+            .map(|achievement| UserAchievement(*achievement))
+            .collect()
+    }
+
+    /// The IDs of all existing lobbies, in ascending order
+    pub fn lobby_ids(&self) -> Vec<LobbyID> {
+        self.0.lobbies.list.keys().cloned().collect()
+    }
+
+    /// An existing lobby
+    pub fn lobby(&self, lobby_id: LobbyID) -> Option<Lobby> {
+        self.0
+            .lobbies
+            .list
+            .get(&lobby_id)
+            .map(|lobby| Lobby(lobby.lobby))
+    }
+
+    /// A metadata value of an existing lobby
+    pub fn lobby_metadata(&self, lobby_id: LobbyID, key: &str) -> Option<&str> {
+        self.0
+            .lobbies
+            .list
+            .get(&lobby_id)?
+            .metadata
+            .get(key)
+            .map(String::as_str)
+    }
+
+    /// The members of an existing lobby, in the order they joined
+    pub fn lobby_member_ids(&self, lobby_id: LobbyID) -> Vec<UserID> {
+        self.0
+            .lobbies
+            .list
+            .get(&lobby_id)
+            .map(|lobby| lobby.members.iter().map(|member| member.user.id).collect())
+            .unwrap_or_default()
+    }
+
+    /// A metadata value of a member of an existing lobby
+    pub fn lobby_member_metadata(
+        &self,
+        lobby_id: LobbyID,
+        user_id: UserID,
+        key: &str,
+    ) -> Option<&str> {
+        self.0
+            .lobbies
+            .list
+            .get(&lobby_id)?
+            .members
+            .iter()
+            .find(|member| member.user.id == user_id)?
+            .metadata
+            .get(key)
+            .map(String::as_str)
+    }
+}
+
+impl Default for MockState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for MockState {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fmt.debug_struct("MockState")
+            .field("current_user", &self.current_user())
+            .field("relationships", &self.relationships())
+            .field("activity", &self.activity())
+            .field("skus", &self.skus())
+            .field("entitlements", &self.entitlements())
+            .field("filenames", &self.filenames())
+            .field("achievements", &self.achievements())
+            .field("lobby_ids", &self.lobby_ids())
+            .finish()
+    }
+}
+
+fn user(user_id: UserID, username: &str, discriminator: &str) -> sys::DiscordUser {
+    let mut user = sys::DiscordUser {
+        id: user_id,
+        ..sys::DiscordUser::default()
+    };
+    write_str(&mut user.username, username);
+    write_str(&mut user.discriminator, discriminator);
+    user
+}
@@ -1,22 +1,121 @@
 use crate::{
     discord::{Discord, DiscordInner},
-    events, CreateFlags, EventHandler, UserAchievement,
+    events, sys, CreateFlags, Error, Event, EventHandler,
+};
+use std::{
+    cell::{RefCell, UnsafeCell},
+    marker::PhantomData,
+    sync::Arc,
 };
-use std::{cell::UnsafeCell, marker::PhantomData};
 
+mod achievements;
+mod activities;
+mod applications;
+mod faults;
 mod ffi;
+mod hub;
+mod images;
+mod inject;
+mod lobbies;
+mod mock_hub;
+mod mock_lobby;
+mod mock_order;
+mod mock_state;
+mod networking;
+mod overlay;
+mod relationships;
+mod state;
+mod storage;
+mod store;
+mod users;
+mod voice;
+
+pub use self::{
+    mock_hub::MockHub, mock_lobby::MockLobby, mock_order::MockOrder, mock_state::MockState,
+};
 
+/// # Mock
+///
+/// Requires the `mock` feature.
+///
+/// An in-process stand-in for the Discord client, useful to test code that uses this crate
+/// without the SDK library or a running Discord client.
+///
+/// All callbacks and events are deferred until the next call to
+/// [`run_callbacks`](#method.run_callbacks), like they are with the SDK.
+///
+/// ```rust
+/// # use discord_game_sdk::*;
+/// # fn example() -> Result<()> {
+/// let mut state = MockState::new();
+/// state.with_achievement(0, 0).with_achievement(1, 100);
+///
+/// let mut discord = Discord::<()>::with_mock_state(&state);
+///
+/// discord.fetch_user_achievements(|discord, result| {
+///     assert!(result.is_ok());
+///     assert_eq!(discord.user_achievement_count(), 2);
+/// });
+///
+/// discord.run_callbacks()?;
+/// # Ok(()) }
+/// # example().unwrap();
+/// ```
 impl<E> Discord<'_, E> {
-    pub(crate) fn mock() -> Self
+    /// Calls [`with_mock_state`] with [`MockState::new`].
+    ///
+    /// [`with_mock_state`]: #method.with_mock_state
+    /// [`MockState::new`]: struct.MockState.html#method.new
+    pub fn mock() -> Self
+    where
+        E: EventHandler,
+    {
+        Self::with_mock_state(&MockState::new())
+    }
+
+    /// Creates an instance backed by the mock instead of the Discord Game SDK.
+    ///
+    /// The Client ID is `0`.
+    pub fn with_mock_state(state: &MockState) -> Self
+    where
+        E: EventHandler,
+    {
+        Self::with_mock_hub(&MockHub::new(), state)
+    }
+
+    /// Creates an instance backed by the mock that shares lobbies and networking with
+    /// the other instances on `hub`, see [`MockHub`](struct.MockHub.html).
+    ///
+    /// The lobbies of `state` are added to the hub, replacing those with the same ID.
+    ///
+    /// ## Panics
+    ///
+    /// If another instance on `hub` runs as the same user.
+    pub fn with_mock_hub(hub: &MockHub, state: &MockState) -> Self
     where
         E: EventHandler,
     {
+        log::debug!("instantiating mock");
+
+        let user_id = state.0.users.current.id;
+
+        assert!(
+            hub.0.borrow().instance(user_id).is_none(),
+            "another mock instance runs as user {}",
+            user_id
+        );
+
         let mut instance = Discord(Box::into_raw(Box::new(DiscordInner {
             _invariant_lifetime: PhantomData,
 
+            // SAFETY: overwritten by `ffi::create_mock`, not deref'd until then
             core: std::ptr::null_mut(),
             client_id: 0,
             event_handler: UnsafeCell::new(None),
+            commands: Arc::default(),
+            network_stats: RefCell::new(None),
+            send_queue: RefCell::new(None),
+            is_mock: true,
 
             achievement_events: events::achievement::<E>(),
             activity_events: events::activity::<E>(),
@@ -31,71 +130,360 @@ impl<E> Discord<'_, E> {
 
         let params = instance.create_params(CreateFlags::Default.into());
 
-        instance.inner_mut().core = unsafe { ffi::create_mock(params) };
+        instance.inner_mut().core =
+            unsafe { ffi::create_mock(params, state.0.clone(), hub.0.clone()) };
+
+        instance.set_log_hook();
 
         instance
     }
+
+    /// A snapshot of the world as seen by the mock instance.
+    ///
+    /// ## Panics
+    ///
+    /// If the instance was not created with [`mock`](#method.mock) or
+    /// [`with_mock_state`](#method.with_mock_state).
+    pub fn mock_state(&self) -> MockState {
+        MockState(self.mock_instance().snapshot())
+    }
+
+    /// Queues an event, it will be passed to the [`EventHandler`](trait.EventHandler.html)
+    /// during the next call to [`run_callbacks`](#method.run_callbacks).
+    ///
+    /// The events are delivered exactly as they are, the mock state is left untouched.
+    ///
+    /// ## Panics
+    ///
+    /// If the instance was not created with [`mock`](#method.mock) or
+    /// [`with_mock_state`](#method.with_mock_state).
+    ///
+    /// ```rust
+    /// # use discord_game_sdk::*;
+    /// # fn example() -> Result<()> {
+    /// #[derive(Default)]
+    /// struct Joins(Vec<String>);
+    ///
+    /// impl EventHandler for Joins {
+    ///     fn on_activity_join(&mut self, _: &Discord<'_, Self>, secret: &str) {
+    ///         self.0.push(secret.to_string());
+    ///     }
+    /// }
+    ///
+    /// let mut discord = Discord::mock();
+    /// *discord.event_handler_mut() = Some(Joins::default());
+    ///
+    /// discord.inject_event(Event::ActivityJoin {
+    ///     secret: "42:hunter2".to_string(),
+    /// });
+    ///
+    /// discord.run_callbacks()?;
+    ///
+    /// assert_eq!(discord.event_handler().as_ref().unwrap().0, ["42:hunter2"]);
+    /// # Ok(()) }
+    /// # example().unwrap();
+    /// ```
+    pub fn inject_event(&self, event: Event) {
+        self.mock_instance()
+            .emit(move |instance| inject::fire(instance, event));
+    }
+
+    /// Makes the next `times` calls to `call` fail with `error`, replaces any failure
+    /// previously set for `call`. A `times` of `0` removes it.
+    ///
+    /// `call` is the name of the SDK function, prefixed by its manager:
+    /// `"storage.read"`, `"lobbies.create_lobby"`, `"users.get_user"`, ...
+    ///
+    /// Functions that return a result fail right away, functions that take a callback
+    /// give the error to their callback without doing anything.
+    ///
+    /// ## Panics
+    ///
+    /// If the instance was not created with [`mock`](#method.mock) or
+    /// [`with_mock_state`](#method.with_mock_state), if `call` is not the name of
+    /// an SDK function that can fail, or if `error` is the SDK's success code,
+    /// `Error::Undefined(0)`.
+    ///
+    /// ```rust
+    /// # use discord_game_sdk::*;
+    /// # fn example() -> Result<()> {
+    /// let mut discord = Discord::<()>::mock();
+    ///
+    /// discord.mock_fail("storage.write", Error::InvalidFileSize, 1);
+    /// discord.mock_fail("users.get_user", Error::ServiceUnavailable, 1);
+    ///
+    /// assert_eq!(discord.write_file("save", b"data"), Err(Error::InvalidFileSize));
+    /// assert!(discord.write_file("save", b"data").is_ok());
+    ///
+    /// discord.user(42, |_, result| {
+    ///     assert_eq!(result.unwrap_err(), Error::ServiceUnavailable);
+    /// });
+    ///
+    /// discord.run_callbacks()?;
+    /// # Ok(()) }
+    /// # example().unwrap();
+    /// ```
+    pub fn mock_fail(&self, call: &str, error: Error, times: u32) {
+        let result = error.into_sys();
+
+        assert!(
+            result != sys::DiscordResult_Ok,
+            "{:?} is not an error of the SDK",
+            error
+        );
+
+        self.mock_instance()
+            .faults
+            .borrow_mut()
+            .set(call, result, times);
+    }
+
+    /// Removes all failures set with [`mock_fail`](#method.mock_fail).
+    ///
+    /// ## Panics
+    ///
+    /// If the instance was not created with [`mock`](#method.mock) or
+    /// [`with_mock_state`](#method.with_mock_state).
+    pub fn mock_clear_failures(&self) {
+        self.mock_instance().faults.borrow_mut().clear();
+    }
+
+    /// Delays callbacks and events scheduled from now on by `ticks` extra calls to
+    /// [`run_callbacks`](#method.run_callbacks). The default is `0`, they run during the next one.
+    ///
+    /// ## Panics
+    ///
+    /// If the instance was not created with [`mock`](#method.mock) or
+    /// [`with_mock_state`](#method.with_mock_state).
+    ///
+    /// ```rust
+    /// # use discord_game_sdk::*;
+    /// # fn example() -> Result<()> {
+    /// let mut discord = Discord::<()>::mock();
+    /// discord.mock_set_latency(2);
+    ///
+    /// let done = std::rc::Rc::new(std::cell::Cell::new(false));
+    /// let done_ = done.clone();
+    /// discord.clear_activity(move |_, _| done_.set(true));
+    ///
+    /// discord.run_callbacks()?;
+    /// discord.run_callbacks()?;
+    /// assert!(!done.get());
+    ///
+    /// discord.run_callbacks()?;
+    /// assert!(done.get());
+    /// # Ok(()) }
+    /// # example().unwrap();
+    /// ```
+    pub fn mock_set_latency(&self, ticks: u32) {
+        self.mock_instance().faults.borrow_mut().latency = ticks;
+    }
+
+    /// Sets the order in which the callbacks and events that are due run.
+    /// The default is [`MockOrder::Fifo`](enum.MockOrder.html#variant.Fifo).
+    ///
+    /// ## Panics
+    ///
+    /// If the instance was not created with [`mock`](#method.mock) or
+    /// [`with_mock_state`](#method.with_mock_state).
+    pub fn mock_set_order(&self, order: MockOrder) {
+        self.mock_instance().faults.borrow_mut().set_order(order);
+    }
+
+    pub(crate) fn mock_instance(&self) -> &ffi::Instance {
+        assert!(self.inner().is_mock, "not a mock instance");
+
+        unsafe { ffi::Instance::from_core(self.inner().core) }
+    }
 }
 
-#[test]
-fn miri_tests() {
-    struct E;
-
-    impl EventHandler for E {
-        fn on_user_achievement_update(
-            &mut self,
-            discord: &Discord<'_, Self>,
-            user_achievement: &UserAchievement,
-        ) {
-            for a in discord.iter_user_achievements() {
-                let a = a.unwrap();
-                eprintln!(
-                    "in event_handler {}: {}%",
-                    a.achievement_id(),
-                    a.percent_complete()
-                );
-            }
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Discord, Error, EventHandler, LobbyID, MockHub, MockLobby, MockState, NetworkChannelID,
+        NetworkPeerID, Reliability, UserAchievement, UserID,
+    };
 
-            if user_achievement.percent_complete() == 99 {
-                discord.set_user_achievement(
-                    user_achievement.achievement_id(),
-                    100,
-                    |discord, _res| {
-                        for a in discord.iter_user_achievements() {
-                            let a = a.unwrap();
-                            eprintln!(
-                                "in event_handler in set {}: {}%",
-                                a.achievement_id(),
-                                a.percent_complete()
-                            );
-                        }
-                    },
-                );
+    #[test]
+    fn miri_tests() {
+        struct E;
+
+        impl EventHandler for E {
+            fn on_user_achievement_update(
+                &mut self,
+                discord: &Discord<'_, Self>,
+                user_achievement: &UserAchievement,
+            ) {
+                for a in discord.iter_user_achievements() {
+                    let a = a.unwrap();
+                    eprintln!(
+                        "in event_handler {}: {}%",
+                        a.achievement_id(),
+                        a.percent_complete()
+                    );
+                }
+
+                if user_achievement.percent_complete() == 99 {
+                    discord.set_user_achievement(
+                        user_achievement.achievement_id(),
+                        100,
+                        |discord, _res| {
+                            for a in discord.iter_user_achievements() {
+                                let a = a.unwrap();
+                                eprintln!(
+                                    "in event_handler in set {}: {}%",
+                                    a.achievement_id(),
+                                    a.percent_complete()
+                                );
+                            }
+                        },
+                    );
+                }
             }
         }
+
+        let mut discord = Discord::with_mock_state(MockState::new().with_achievement(0, 0));
+        *discord.event_handler_mut() = Some(E);
+
+        discord.fetch_user_achievements(|discord, _res| {
+            discord.set_user_achievement(0, 99, |discord, _res| {
+                for a in discord.iter_user_achievements() {
+                    let a = a.unwrap();
+                    eprintln!(
+                        "in fetch in set {}: {}%",
+                        a.achievement_id(),
+                        a.percent_complete()
+                    );
+                }
+            });
+        });
+
+        for _ in 0..100 {
+            discord.run_callbacks().unwrap();
+        }
+
+        discord.fetch_user_achievements(|discord, _res| {
+            discord.set_user_achievement(0, 99, |_discord, _res| {});
+        });
     }
 
-    let mut discord = Discord::mock();
-    *discord.event_handler_mut() = Some(E);
-
-    discord.fetch_user_achievements(|discord, _res| {
-        discord.set_user_achievement(0, 99, |discord, _res| {
-            for a in discord.iter_user_achievements() {
-                let a = a.unwrap();
-                eprintln!(
-                    "in fetch in set {}: {}%",
-                    a.achievement_id(),
-                    a.percent_complete()
-                );
+    #[test]
+    fn hub() {
+        #[derive(Debug, Default, PartialEq)]
+        struct Seen(Vec<String>);
+
+        impl EventHandler for Seen {
+            fn on_member_connect(&mut self, _: &Discord<'_, Self>, _: LobbyID, member: UserID) {
+                self.0.push(format!("connect {}", member));
             }
-        });
-    });
 
-    for _ in 0..100 {
-        discord.run_callbacks().unwrap();
+            fn on_member_disconnect(&mut self, _: &Discord<'_, Self>, _: LobbyID, member: UserID) {
+                self.0.push(format!("disconnect {}", member));
+            }
+
+            fn on_lobby_network_message(
+                &mut self,
+                _: &Discord<'_, Self>,
+                _: LobbyID,
+                member: UserID,
+                channel: NetworkChannelID,
+                data: &[u8],
+            ) {
+                self.0
+                    .push(format!("lobby {} {} {:?}", member, channel, data));
+            }
+
+            fn on_network_message(
+                &mut self,
+                _: &Discord<'_, Self>,
+                peer: NetworkPeerID,
+                channel: NetworkChannelID,
+                data: &[u8],
+            ) {
+                self.0.push(format!("peer {} {} {:?}", peer, channel, data));
+            }
+        }
+
+        let hub = MockHub::new();
+
+        let mut alice = Discord::<Seen>::with_mock_hub(
+            &hub,
+            MockState::new()
+                .with_current_user(1, "alice", "0001")
+                .with_lobby(&MockLobby::new(7, 1)),
+        );
+        *alice.event_handler_mut() = Some(Seen::default());
+
+        let mut bob = Discord::<Seen>::with_mock_hub(
+            &hub,
+            MockState::new().with_current_user(2, "bob", "0002"),
+        );
+        *bob.event_handler_mut() = Some(Seen::default());
+
+        bob.connect_lobby(7, "mock-secret-7", |_, res| assert!(res.is_ok()));
+        bob.run_callbacks().unwrap();
+        alice.run_callbacks().unwrap();
+
+        for discord in &[&alice, &bob] {
+            discord.connect_lobby_network(7).unwrap();
+            discord
+                .open_lobby_network_channel(7, 0, Reliability::Reliable)
+                .unwrap();
+        }
+
+        alice.send_lobby_network_message(7, 2, 0, &[1, 2]).unwrap();
+
+        alice.open_peer(2, "mock-route-2").unwrap();
+        alice.open_channel(2, 3, Reliability::Reliable).unwrap();
+        bob.open_peer(1, "mock-route-1").unwrap();
+        bob.open_channel(1, 3, Reliability::Unreliable).unwrap();
+        alice.send_message(2, 3, [3]).unwrap();
+
+        // Not listening on channel 4
+        alice.open_channel(2, 4, Reliability::Reliable).unwrap();
+        alice.send_message(2, 4, [4]).unwrap();
+
+        bob.run_callbacks().unwrap();
+        assert_eq!(
+            bob.event_handler().as_ref().unwrap().0,
+            ["lobby 1 0 [1, 2]", "peer 1 3 [3]"]
+        );
+
+        drop(bob);
+        alice.run_callbacks().unwrap();
+
+        assert_eq!(alice.mock_state().lobby_member_ids(7), [1]);
+        assert_eq!(
+            alice.event_handler().as_ref().unwrap().0,
+            ["connect 2", "disconnect 2"]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "not an error")]
+    fn rejects_success() {
+        Discord::<()>::mock().mock_fail("storage.write", Error::Undefined(0), 1);
     }
 
-    discord.fetch_user_achievements(|discord, _res| {
-        discord.set_user_achievement(0, 99, |_discord, _res| {});
-    });
+    #[test]
+    fn lobby_members() {
+        let discord = Discord::<()>::with_mock_state(
+            MockState::new()
+                .with_current_user(1, "alice", "0001")
+                .with_user(2, "bob", "0002")
+                .with_lobby(MockLobby::new(42, 1).with_member(2)),
+        );
+
+        assert_eq!(discord.lobby_member_user(42, 2).unwrap().username(), "bob");
+        assert!(discord.lobby_member_user(42, 3).is_err());
+
+        let members = discord
+            .iter_lobby_members(42)
+            .unwrap()
+            .map(|member| member.map(|(id, user)| (id, user.username().to_string())))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(members, [(1, "alice".to_string()), (2, "bob".to_string())]);
+    }
 }
@@ -0,0 +1,204 @@
+use crate::{
+    mock::{
+        ffi::{read_bytes, read_str, Instance},
+        inject,
+        state::Peer,
+    },
+    sys, Event,
+};
+use std::collections::BTreeMap;
+
+pub(crate) const VTABLE: sys::IDiscordNetworkManager = sys::IDiscordNetworkManager {
+    get_peer_id: Some(get_peer_id),
+    flush: Some(flush),
+    open_peer: Some(open_peer),
+    update_peer: Some(update_peer),
+    close_peer: Some(close_peer),
+    open_channel: Some(open_channel),
+    close_channel: Some(close_channel),
+    send_message: Some(send_message),
+};
+
+unsafe extern "C" fn get_peer_id(
+    mgr: *mut sys::IDiscordNetworkManager,
+    peer_id: *mut sys::DiscordNetworkPeerId,
+) {
+    *peer_id = Instance::from_manager(mgr).state.borrow().network.peer_id;
+}
+
+unsafe extern "C" fn flush(mgr: *mut sys::IDiscordNetworkManager) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("networking.flush") {
+        return res;
+    }
+
+    // Messages are never buffered
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn open_peer(
+    mgr: *mut sys::IDiscordNetworkManager,
+    peer_id: sys::DiscordNetworkPeerId,
+    route_data: *const u8,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("networking.open_peer") {
+        return res;
+    }
+
+    let route = read_str(route_data);
+    let mut state = Instance::from_manager(mgr).state.borrow_mut();
+
+    if state.network.peers.contains_key(&peer_id) {
+        return sys::DiscordResult_Conflict;
+    }
+
+    let _ = state.network.peers.insert(
+        peer_id,
+        Peer {
+            route,
+            channels: BTreeMap::new(),
+        },
+    );
+
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn update_peer(
+    mgr: *mut sys::IDiscordNetworkManager,
+    peer_id: sys::DiscordNetworkPeerId,
+    route_data: *const u8,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("networking.update_peer") {
+        return res;
+    }
+
+    let route = read_str(route_data);
+    let mut state = Instance::from_manager(mgr).state.borrow_mut();
+
+    match state.network.peers.get_mut(&peer_id) {
+        Some(peer) => {
+            peer.route = route;
+            sys::DiscordResult_Ok
+        }
+
+        None => sys::DiscordResult_NotFound,
+    }
+}
+
+unsafe extern "C" fn close_peer(
+    mgr: *mut sys::IDiscordNetworkManager,
+    peer_id: sys::DiscordNetworkPeerId,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("networking.close_peer") {
+        return res;
+    }
+
+    let mut state = Instance::from_manager(mgr).state.borrow_mut();
+
+    match state.network.peers.remove(&peer_id) {
+        Some(_) => sys::DiscordResult_Ok,
+        None => sys::DiscordResult_NotFound,
+    }
+}
+
+unsafe extern "C" fn open_channel(
+    mgr: *mut sys::IDiscordNetworkManager,
+    peer_id: sys::DiscordNetworkPeerId,
+    channel_id: sys::DiscordNetworkChannelId,
+    reliable: bool,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("networking.open_channel") {
+        return res;
+    }
+
+    let mut state = Instance::from_manager(mgr).state.borrow_mut();
+
+    match state.network.peers.get_mut(&peer_id) {
+        Some(peer) => {
+            let _ = peer.channels.insert(channel_id, reliable);
+            sys::DiscordResult_Ok
+        }
+
+        None => sys::DiscordResult_NotFound,
+    }
+}
+
+unsafe extern "C" fn close_channel(
+    mgr: *mut sys::IDiscordNetworkManager,
+    peer_id: sys::DiscordNetworkPeerId,
+    channel_id: sys::DiscordNetworkChannelId,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("networking.close_channel") {
+        return res;
+    }
+
+    let mut state = Instance::from_manager(mgr).state.borrow_mut();
+
+    match state
+        .network
+        .peers
+        .get_mut(&peer_id)
+        .and_then(|peer| peer.channels.remove(&channel_id))
+    {
+        Some(_) => sys::DiscordResult_Ok,
+        None => sys::DiscordResult_NotFound,
+    }
+}
+
+unsafe extern "C" fn send_message(
+    mgr: *mut sys::IDiscordNetworkManager,
+    peer_id: sys::DiscordNetworkPeerId,
+    channel_id: sys::DiscordNetworkChannelId,
+    data: *mut u8,
+    data_length: u32,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("networking.send_message") {
+        return res;
+    }
+
+    let instance = Instance::from_manager(mgr);
+    let sender_id = instance.state.borrow().network.peer_id;
+
+    match instance.state.borrow().network.peers.get(&peer_id) {
+        Some(peer) if peer.channels.contains_key(&channel_id) => {}
+        Some(_) => return sys::DiscordResult_InvalidChannel,
+        None => return sys::DiscordResult_NotFound,
+    }
+
+    let data = read_bytes(data, data_length);
+
+    // Like the SDK, the recipient must have opened the same channel to us to receive anything
+    if let Some(recipient) = instance.hub.borrow().peer(peer_id) {
+        let data = data.clone();
+
+        recipient.emit(move |recipient| {
+            let listening = recipient
+                .state
+                .borrow()
+                .network
+                .peers
+                .get(&sender_id)
+                .and_then(|peer| peer.channels.get(&channel_id))
+                .is_some();
+
+            if listening {
+                inject::fire(
+                    recipient,
+                    Event::NetworkMessage {
+                        peer_id: sender_id,
+                        channel_id,
+                        data,
+                    },
+                );
+            }
+        });
+    }
+
+    instance
+        .state
+        .borrow_mut()
+        .network
+        .messages
+        .push((peer_id, channel_id, data));
+
+    sys::DiscordResult_Ok
+}
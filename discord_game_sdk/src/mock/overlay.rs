@@ -0,0 +1,105 @@
+use crate::{
+    mock::ffi::{read_str, Instance, ResultCallback},
+    sys,
+};
+use std::ffi::c_void;
+
+pub(crate) const VTABLE: sys::IDiscordOverlayManager = sys::IDiscordOverlayManager {
+    is_enabled: Some(is_enabled),
+    is_locked: Some(is_locked),
+    set_locked: Some(set_locked),
+    open_activity_invite: Some(open_activity_invite),
+    open_guild_invite: Some(open_guild_invite),
+    open_voice_settings: Some(open_voice_settings),
+};
+
+unsafe extern "C" fn is_enabled(mgr: *mut sys::IDiscordOverlayManager, enabled: *mut bool) {
+    *enabled = Instance::from_manager(mgr).state.borrow().overlay.enabled;
+}
+
+unsafe extern "C" fn is_locked(mgr: *mut sys::IDiscordOverlayManager, locked: *mut bool) {
+    *locked = Instance::from_manager(mgr).state.borrow().overlay.locked;
+}
+
+fn set_overlay_locked(instance: &Instance, locked: bool) -> sys::EDiscordResult {
+    {
+        let mut state = instance.state.borrow_mut();
+
+        if !state.overlay.enabled {
+            return sys::DiscordResult_InternalError;
+        }
+
+        if state.overlay.locked == locked {
+            return sys::DiscordResult_Ok;
+        }
+
+        state.overlay.locked = locked;
+    }
+
+    instance.event(instance.params.overlay_events, |events, data| unsafe {
+        events.on_toggle.unwrap()(data, locked)
+    });
+
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn set_locked(
+    mgr: *mut sys::IDiscordOverlayManager,
+    locked: bool,
+    callback_data: *mut c_void,
+    callback: ResultCallback,
+) {
+    let _fault = Instance::from_manager(mgr).fault_next_callback("overlay.set_locked");
+
+    Instance::from_manager(mgr).respond(callback_data, callback, move |instance| {
+        set_overlay_locked(instance, locked)
+    })
+}
+
+unsafe extern "C" fn open_activity_invite(
+    mgr: *mut sys::IDiscordOverlayManager,
+    _: sys::EDiscordActivityActionType,
+    callback_data: *mut c_void,
+    callback: ResultCallback,
+) {
+    let _fault = Instance::from_manager(mgr).fault_next_callback("overlay.open_activity_invite");
+
+    Instance::from_manager(mgr).respond(callback_data, callback, |instance| {
+        if instance.state.borrow().activities.current.is_none() {
+            return sys::DiscordResult_NoEligibleActivity;
+        }
+
+        set_overlay_locked(instance, false)
+    })
+}
+
+unsafe extern "C" fn open_guild_invite(
+    mgr: *mut sys::IDiscordOverlayManager,
+    code: *const u8,
+    callback_data: *mut c_void,
+    callback: ResultCallback,
+) {
+    let _fault = Instance::from_manager(mgr).fault_next_callback("overlay.open_guild_invite");
+
+    let code = read_str(code);
+
+    Instance::from_manager(mgr).respond(callback_data, callback, move |instance| {
+        if code.is_empty() {
+            return sys::DiscordResult_InvalidInvite;
+        }
+
+        set_overlay_locked(instance, false)
+    })
+}
+
+unsafe extern "C" fn open_voice_settings(
+    mgr: *mut sys::IDiscordOverlayManager,
+    callback_data: *mut c_void,
+    callback: ResultCallback,
+) {
+    let _fault = Instance::from_manager(mgr).fault_next_callback("overlay.open_voice_settings");
+
+    Instance::from_manager(mgr).respond(callback_data, callback, |instance| {
+        set_overlay_locked(instance, false)
+    })
+}
@@ -0,0 +1,93 @@
+use crate::{mock::ffi::Instance, sys};
+use std::{convert::TryFrom, ffi::c_void};
+
+pub(crate) const VTABLE: sys::IDiscordRelationshipManager = sys::IDiscordRelationshipManager {
+    filter: Some(filter),
+    count: Some(count),
+    get: Some(get),
+    get_at: Some(get_at),
+};
+
+// `count` and `get_at` work on the result of the latest `filter`, everything until then
+unsafe extern "C" fn filter(
+    mgr: *mut sys::IDiscordRelationshipManager,
+    filter_data: *mut c_void,
+    filter: Option<unsafe extern "C" fn(*mut c_void, *mut sys::DiscordRelationship) -> bool>,
+) {
+    let instance = Instance::from_manager(mgr);
+
+    // The filter may call back into the SDK, the state can't be borrowed while it runs
+    let relationships = instance.state.borrow().relationships.list.clone();
+
+    let filtered = relationships
+        .into_iter()
+        .filter(|relationship| {
+            let mut relationship = *relationship;
+            filter.unwrap()(filter_data, &mut relationship)
+        })
+        .collect();
+
+    instance.state.borrow_mut().relationships.filtered = Some(filtered);
+}
+
+unsafe extern "C" fn count(
+    mgr: *mut sys::IDiscordRelationshipManager,
+    count: *mut i32,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("relationships.count") {
+        return res;
+    }
+
+    let state = Instance::from_manager(mgr).state.borrow();
+
+    *count = i32::try_from(state.relationships.visible().len()).unwrap();
+
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn get(
+    mgr: *mut sys::IDiscordRelationshipManager,
+    user_id: sys::DiscordUserId,
+    relationship: *mut sys::DiscordRelationship,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("relationships.get") {
+        return res;
+    }
+
+    let state = Instance::from_manager(mgr).state.borrow();
+
+    match state
+        .relationships
+        .list
+        .iter()
+        .find(|found| found.user.id == user_id)
+    {
+        Some(found) => {
+            *relationship = *found;
+            sys::DiscordResult_Ok
+        }
+
+        None => sys::DiscordResult_NotFound,
+    }
+}
+
+unsafe extern "C" fn get_at(
+    mgr: *mut sys::IDiscordRelationshipManager,
+    index: u32,
+    relationship: *mut sys::DiscordRelationship,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("relationships.get_at") {
+        return res;
+    }
+
+    let state = Instance::from_manager(mgr).state.borrow();
+
+    match state.relationships.visible().get(index as usize) {
+        Some(found) => {
+            *relationship = *found;
+            sys::DiscordResult_Ok
+        }
+
+        None => sys::DiscordResult_NotFound,
+    }
+}
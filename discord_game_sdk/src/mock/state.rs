@@ -0,0 +1,268 @@
+use crate::{mock::ffi::write_str, sys, LobbyID, NetworkChannelID, NetworkPeerID, UserID};
+use std::collections::BTreeMap;
+
+/// Everything the mock instance knows about, in SDK representation
+#[derive(Clone)]
+pub(crate) struct State {
+    pub(crate) achievements: Achievements,
+    pub(crate) activities: Activities,
+    pub(crate) applications: Applications,
+    pub(crate) images: Images,
+    pub(crate) lobbies: Lobbies,
+    pub(crate) network: Network,
+    pub(crate) overlay: Overlay,
+    pub(crate) relationships: Relationships,
+    pub(crate) storage: Storage,
+    pub(crate) store: Store,
+    pub(crate) users: Users,
+    pub(crate) voice: Voice,
+}
+
+#[derive(Clone, Default)]
+pub(crate) struct Achievements {
+    pub(crate) fetched: bool,
+    pub(crate) list: Vec<sys::DiscordUserAchievement>,
+}
+
+#[derive(Clone, Default)]
+pub(crate) struct Activities {
+    pub(crate) current: Option<sys::DiscordActivity>,
+    pub(crate) command: Option<String>,
+    pub(crate) steam_id: Option<u32>,
+    pub(crate) invites: Vec<(UserID, sys::EDiscordActivityActionType, String)>,
+    pub(crate) replies: Vec<(UserID, sys::EDiscordActivityJoinRequestReply)>,
+    pub(crate) accepted: Vec<UserID>,
+}
+
+#[derive(Clone)]
+pub(crate) struct Applications {
+    pub(crate) locale: String,
+    pub(crate) branch: String,
+    pub(crate) oauth2_token: sys::DiscordOAuth2Token,
+    pub(crate) ticket: String,
+}
+
+#[derive(Clone, Default)]
+pub(crate) struct Images {
+    pub(crate) fetched: Vec<sys::DiscordImageHandle>,
+}
+
+#[derive(Clone, Default)]
+pub(crate) struct Lobbies {
+    // Only used to seed the hub and in snapshots, the hub owns the lobbies
+    pub(crate) list: BTreeMap<LobbyID, Lobby>,
+    pub(crate) search_results: Vec<LobbyID>,
+    pub(crate) pending: Vec<Pending>,
+    pub(crate) messages: Vec<(LobbyID, Vec<u8>)>,
+    pub(crate) network_messages: Vec<(LobbyID, UserID, NetworkChannelID, Vec<u8>)>,
+}
+
+#[derive(Clone)]
+pub(crate) struct Lobby {
+    pub(crate) lobby: sys::DiscordLobby,
+    pub(crate) metadata: BTreeMap<String, String>,
+    pub(crate) members: Vec<Member>,
+}
+
+#[derive(Clone)]
+pub(crate) struct Member {
+    pub(crate) user: sys::DiscordUser,
+    pub(crate) metadata: BTreeMap<String, String>,
+    pub(crate) voice: bool,
+    pub(crate) network: bool,
+    pub(crate) channels: BTreeMap<NetworkChannelID, bool>,
+}
+
+impl Member {
+    pub(crate) fn new(user: sys::DiscordUser, metadata: BTreeMap<String, String>) -> Self {
+        Self {
+            user,
+            metadata,
+            voice: false,
+            network: false,
+            channels: BTreeMap::new(),
+        }
+    }
+}
+
+/// Transactions and search queries handed out but not yet consumed
+#[derive(Clone, Copy)]
+pub(crate) enum Pending {
+    Lobby(*mut sys::IDiscordLobbyTransaction),
+    Member(*mut sys::IDiscordLobbyMemberTransaction),
+    Search(*mut sys::IDiscordLobbySearchQuery),
+}
+
+#[derive(Clone)]
+pub(crate) struct Network {
+    pub(crate) peer_id: NetworkPeerID,
+    pub(crate) route: String,
+    pub(crate) peers: BTreeMap<NetworkPeerID, Peer>,
+    pub(crate) messages: Vec<(NetworkPeerID, NetworkChannelID, Vec<u8>)>,
+}
+
+#[derive(Clone)]
+pub(crate) struct Peer {
+    pub(crate) route: String,
+    pub(crate) channels: BTreeMap<NetworkChannelID, bool>,
+}
+
+#[derive(Clone)]
+pub(crate) struct Overlay {
+    pub(crate) enabled: bool,
+    pub(crate) locked: bool,
+}
+
+#[derive(Clone, Default)]
+pub(crate) struct Relationships {
+    pub(crate) list: Vec<sys::DiscordRelationship>,
+    pub(crate) filtered: Option<Vec<sys::DiscordRelationship>>,
+}
+
+impl Relationships {
+    /// What `count` and `get_at` see
+    pub(crate) fn visible(&self) -> &[sys::DiscordRelationship] {
+        self.filtered.as_ref().unwrap_or(&self.list)
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct Storage {
+    pub(crate) path: String,
+    pub(crate) files: BTreeMap<String, File>,
+    pub(crate) clock: u64,
+}
+
+#[derive(Clone)]
+pub(crate) struct File {
+    pub(crate) data: Vec<u8>,
+    pub(crate) last_modified: u64,
+}
+
+#[derive(Clone, Default)]
+pub(crate) struct Store {
+    pub(crate) skus_fetched: bool,
+    pub(crate) skus: Vec<sys::DiscordSku>,
+    pub(crate) entitlements_fetched: bool,
+    pub(crate) entitlements: Vec<sys::DiscordEntitlement>,
+    pub(crate) next_entitlement_id: sys::DiscordSnowflake,
+}
+
+#[derive(Clone)]
+pub(crate) struct Users {
+    pub(crate) current: sys::DiscordUser,
+    pub(crate) premium_type: sys::EDiscordPremiumType,
+    pub(crate) flags: sys::EDiscordUserFlag,
+    pub(crate) known: BTreeMap<UserID, sys::DiscordUser>,
+}
+
+#[derive(Clone)]
+pub(crate) struct Voice {
+    pub(crate) input_mode: sys::DiscordInputMode,
+    pub(crate) self_mute: bool,
+    pub(crate) self_deaf: bool,
+    pub(crate) local_mutes: BTreeMap<UserID, bool>,
+    pub(crate) local_volumes: BTreeMap<UserID, u8>,
+}
+
+impl State {
+    /// A copy that does not own any of the pending transactions
+    pub(crate) fn snapshot(&self) -> Self {
+        let mut state = self.clone();
+        state.lobbies.pending.clear();
+        state
+    }
+
+    /// The user that `current_user` would return
+    pub(crate) fn current_user(&self) -> sys::DiscordUser {
+        self.users.current
+    }
+
+    /// Looks up users we know of: ourselves, relationships and lobby members
+    pub(crate) fn find_user(&self, user_id: UserID) -> Option<sys::DiscordUser> {
+        if self.users.current.id == user_id {
+            return Some(self.users.current);
+        }
+
+        if let Some(user) = self.users.known.get(&user_id) {
+            return Some(*user);
+        }
+
+        if let Some(relationship) = self
+            .relationships
+            .list
+            .iter()
+            .find(|relationship| relationship.user.id == user_id)
+        {
+            return Some(relationship.user);
+        }
+
+        self.lobbies
+            .list
+            .values()
+            .flat_map(|lobby| lobby.members.iter())
+            .find(|member| member.user.id == user_id)
+            .map(|member| member.user)
+    }
+}
+
+impl Default for State {
+    fn default() -> Self {
+        let mut current = sys::DiscordUser {
+            id: 1,
+            ..sys::DiscordUser::default()
+        };
+        write_str(&mut current.username, "mock");
+        write_str(&mut current.discriminator, "0001");
+
+        let mut oauth2_token = sys::DiscordOAuth2Token::default();
+        write_str(&mut oauth2_token.access_token, "mock-access-token");
+        write_str(&mut oauth2_token.scopes, "identify");
+
+        Self {
+            achievements: Achievements::default(),
+            activities: Activities::default(),
+            applications: Applications {
+                locale: "en-US".to_string(),
+                branch: "master".to_string(),
+                oauth2_token,
+                ticket: "mock-ticket".to_string(),
+            },
+            images: Images::default(),
+            lobbies: Lobbies::default(),
+            network: Network {
+                peer_id: current.id as NetworkPeerID,
+                route: format!("mock-route-{}", current.id),
+                peers: BTreeMap::new(),
+                messages: Vec::new(),
+            },
+            overlay: Overlay {
+                enabled: true,
+                locked: true,
+            },
+            relationships: Relationships::default(),
+            storage: Storage {
+                path: "mock".to_string(),
+                files: BTreeMap::new(),
+                clock: 0,
+            },
+            store: Store {
+                next_entitlement_id: 1,
+                ..Store::default()
+            },
+            users: Users {
+                current,
+                premium_type: sys::DiscordPremiumType_None,
+                flags: 0,
+                known: BTreeMap::new(),
+            },
+            voice: Voice {
+                input_mode: sys::DiscordInputMode::default(),
+                self_mute: false,
+                self_deaf: false,
+                local_mutes: BTreeMap::new(),
+                local_volumes: BTreeMap::new(),
+            },
+        }
+    }
+}
@@ -0,0 +1,310 @@
+use crate::{
+    mock::{
+        ffi::{read_bytes, read_str, write_str, Instance, ResultCallback},
+        state::{File, State},
+    },
+    sys,
+};
+use std::{convert::TryFrom, ffi::c_void};
+
+type DataCallback = Option<unsafe extern "C" fn(*mut c_void, sys::EDiscordResult, *mut u8, u32)>;
+
+pub(crate) const VTABLE: sys::IDiscordStorageManager = sys::IDiscordStorageManager {
+    read: Some(read),
+    read_async: Some(read_async),
+    read_async_partial: Some(read_async_partial),
+    write: Some(write),
+    write_async: Some(write_async),
+    delete_: Some(delete_),
+    exists: Some(exists),
+    count: Some(count),
+    stat: Some(stat),
+    stat_at: Some(stat_at),
+    get_path: Some(get_path),
+};
+
+fn write_file(state: &mut State, name: String, data: Vec<u8>) -> sys::EDiscordResult {
+    if name.is_empty() {
+        return sys::DiscordResult_InvalidFilename;
+    }
+
+    state.storage.clock += 1;
+
+    let _ = state.storage.files.insert(
+        name,
+        File {
+            data,
+            last_modified: state.storage.clock,
+        },
+    );
+
+    sys::DiscordResult_Ok
+}
+
+fn file_stat(name: &str, file: &File) -> sys::DiscordFileStat {
+    let mut stat = sys::DiscordFileStat::default();
+    write_str(&mut stat.filename, name);
+    stat.size = file.data.len() as u64;
+    stat.last_modified = file.last_modified;
+    stat
+}
+
+// The data is only valid for the duration of the callback
+fn respond_data(
+    instance: &Instance,
+    callback_data: *mut c_void,
+    callback: DataCallback,
+    action: impl 'static + FnOnce(&Instance) -> Result<Vec<u8>, sys::EDiscordResult>,
+) {
+    instance.defer(move |instance, res| {
+        let res = if res == sys::DiscordResult_Ok {
+            action(instance)
+        } else {
+            Err(res)
+        };
+
+        let (res, mut data) = match res {
+            Ok(data) => (sys::DiscordResult_Ok, data),
+            Err(res) => (res, Vec::new()),
+        };
+
+        unsafe {
+            callback.unwrap()(
+                callback_data,
+                res,
+                data.as_mut_ptr(),
+                u32::try_from(data.len()).unwrap(),
+            )
+        }
+    })
+}
+
+unsafe extern "C" fn read(
+    mgr: *mut sys::IDiscordStorageManager,
+    name: *const u8,
+    data: *mut u8,
+    data_length: u32,
+    read: *mut u32,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("storage.read") {
+        return res;
+    }
+
+    let name = read_str(name);
+    let state = Instance::from_manager(mgr).state.borrow();
+
+    let file = match state.storage.files.get(&name) {
+        Some(file) => file,
+        None => return sys::DiscordResult_NotFound,
+    };
+
+    if file.data.len() > data_length as usize {
+        return sys::DiscordResult_InsufficientBuffer;
+    }
+
+    std::slice::from_raw_parts_mut(data, file.data.len()).copy_from_slice(&file.data);
+    *read = u32::try_from(file.data.len()).unwrap();
+
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn read_async(
+    mgr: *mut sys::IDiscordStorageManager,
+    name: *const u8,
+    callback_data: *mut c_void,
+    callback: DataCallback,
+) {
+    let _fault = Instance::from_manager(mgr).fault_next_callback("storage.read_async");
+
+    let name = read_str(name);
+
+    respond_data(
+        Instance::from_manager(mgr),
+        callback_data,
+        callback,
+        move |instance| match instance.state.borrow().storage.files.get(&name) {
+            Some(file) => Ok(file.data.clone()),
+            None => Err(sys::DiscordResult_NotFound),
+        },
+    )
+}
+
+unsafe extern "C" fn read_async_partial(
+    mgr: *mut sys::IDiscordStorageManager,
+    name: *const u8,
+    offset: u64,
+    length: u64,
+    callback_data: *mut c_void,
+    callback: DataCallback,
+) {
+    let _fault = Instance::from_manager(mgr).fault_next_callback("storage.read_async_partial");
+
+    let name = read_str(name);
+
+    respond_data(
+        Instance::from_manager(mgr),
+        callback_data,
+        callback,
+        move |instance| {
+            let state = instance.state.borrow();
+            let file = state
+                .storage
+                .files
+                .get(&name)
+                .ok_or(sys::DiscordResult_NotFound)?;
+
+            let len = file.data.len() as u64;
+
+            if offset > len {
+                return Err(sys::DiscordResult_InvalidPayload);
+            }
+
+            let end = offset.saturating_add(length).min(len);
+
+            Ok(file.data[offset as usize..end as usize].to_vec())
+        },
+    )
+}
+
+unsafe extern "C" fn write(
+    mgr: *mut sys::IDiscordStorageManager,
+    name: *const u8,
+    data: *mut u8,
+    data_length: u32,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("storage.write") {
+        return res;
+    }
+
+    let name = read_str(name);
+    let data = read_bytes(data, data_length);
+
+    write_file(
+        &mut Instance::from_manager(mgr).state.borrow_mut(),
+        name,
+        data,
+    )
+}
+
+unsafe extern "C" fn write_async(
+    mgr: *mut sys::IDiscordStorageManager,
+    name: *const u8,
+    data: *mut u8,
+    data_length: u32,
+    callback_data: *mut c_void,
+    callback: ResultCallback,
+) {
+    let _fault = Instance::from_manager(mgr).fault_next_callback("storage.write_async");
+
+    let name = read_str(name);
+    let data = read_bytes(data, data_length);
+
+    Instance::from_manager(mgr).respond(callback_data, callback, move |instance| {
+        write_file(&mut instance.state.borrow_mut(), name, data)
+    })
+}
+
+unsafe extern "C" fn delete_(
+    mgr: *mut sys::IDiscordStorageManager,
+    name: *const u8,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("storage.delete") {
+        return res;
+    }
+
+    let name = read_str(name);
+    let mut state = Instance::from_manager(mgr).state.borrow_mut();
+
+    match state.storage.files.remove(&name) {
+        Some(_) => sys::DiscordResult_Ok,
+        None => sys::DiscordResult_NotFound,
+    }
+}
+
+unsafe extern "C" fn exists(
+    mgr: *mut sys::IDiscordStorageManager,
+    name: *const u8,
+    exists: *mut bool,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("storage.exists") {
+        return res;
+    }
+
+    let name = read_str(name);
+
+    *exists = Instance::from_manager(mgr)
+        .state
+        .borrow()
+        .storage
+        .files
+        .contains_key(&name);
+
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn count(mgr: *mut sys::IDiscordStorageManager, count: *mut i32) {
+    let state = Instance::from_manager(mgr).state.borrow();
+
+    *count = i32::try_from(state.storage.files.len()).unwrap();
+}
+
+unsafe extern "C" fn stat(
+    mgr: *mut sys::IDiscordStorageManager,
+    name: *const u8,
+    stat: *mut sys::DiscordFileStat,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("storage.stat") {
+        return res;
+    }
+
+    let name = read_str(name);
+    let state = Instance::from_manager(mgr).state.borrow();
+
+    match state.storage.files.get(&name) {
+        Some(file) => {
+            *stat = file_stat(&name, file);
+            sys::DiscordResult_Ok
+        }
+
+        None => sys::DiscordResult_NotFound,
+    }
+}
+
+unsafe extern "C" fn stat_at(
+    mgr: *mut sys::IDiscordStorageManager,
+    index: i32,
+    stat: *mut sys::DiscordFileStat,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("storage.stat_at") {
+        return res;
+    }
+
+    let state = Instance::from_manager(mgr).state.borrow();
+
+    match usize::try_from(index)
+        .ok()
+        .and_then(|index| state.storage.files.iter().nth(index))
+    {
+        Some((name, file)) => {
+            *stat = file_stat(name, file);
+            sys::DiscordResult_Ok
+        }
+
+        None => sys::DiscordResult_NotFound,
+    }
+}
+
+unsafe extern "C" fn get_path(
+    mgr: *mut sys::IDiscordStorageManager,
+    path: *mut sys::DiscordPath,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("storage.get_path") {
+        return res;
+    }
+
+    let state = Instance::from_manager(mgr).state.borrow();
+
+    write_str(&mut *path, &state.storage.path);
+
+    sys::DiscordResult_Ok
+}
@@ -0,0 +1,229 @@
+use crate::{
+    mock::ffi::{Instance, ResultCallback},
+    sys,
+};
+use std::{convert::TryFrom, ffi::c_void};
+
+pub(crate) const VTABLE: sys::IDiscordStoreManager = sys::IDiscordStoreManager {
+    fetch_skus: Some(fetch_skus),
+    count_skus: Some(count_skus),
+    get_sku: Some(get_sku),
+    get_sku_at: Some(get_sku_at),
+    fetch_entitlements: Some(fetch_entitlements),
+    count_entitlements: Some(count_entitlements),
+    get_entitlement: Some(get_entitlement),
+    get_entitlement_at: Some(get_entitlement_at),
+    has_sku_entitlement: Some(has_sku_entitlement),
+    start_purchase: Some(start_purchase),
+};
+
+fn find<T: Copy>(
+    fetched: bool,
+    list: &[T],
+    predicate: impl Fn(&T) -> bool,
+    out: *mut T,
+) -> sys::EDiscordResult {
+    if !fetched {
+        return sys::DiscordResult_NotFetched;
+    }
+
+    match list.iter().find(|item| predicate(item)) {
+        Some(item) => {
+            unsafe { *out = *item };
+            sys::DiscordResult_Ok
+        }
+
+        None => sys::DiscordResult_NotFound,
+    }
+}
+
+fn count<T>(fetched: bool, list: &[T]) -> i32 {
+    if fetched {
+        i32::try_from(list.len()).unwrap()
+    } else {
+        0
+    }
+}
+
+unsafe extern "C" fn fetch_skus(
+    mgr: *mut sys::IDiscordStoreManager,
+    callback_data: *mut c_void,
+    callback: ResultCallback,
+) {
+    let _fault = Instance::from_manager(mgr).fault_next_callback("store.fetch_skus");
+
+    Instance::from_manager(mgr).respond(callback_data, callback, |instance| {
+        instance.state.borrow_mut().store.skus_fetched = true;
+
+        sys::DiscordResult_Ok
+    })
+}
+
+unsafe extern "C" fn count_skus(mgr: *mut sys::IDiscordStoreManager, count_: *mut i32) {
+    let state = Instance::from_manager(mgr).state.borrow();
+
+    *count_ = count(state.store.skus_fetched, &state.store.skus);
+}
+
+unsafe extern "C" fn get_sku(
+    mgr: *mut sys::IDiscordStoreManager,
+    sku_id: sys::DiscordSnowflake,
+    sku: *mut sys::DiscordSku,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("store.get_sku") {
+        return res;
+    }
+
+    let state = Instance::from_manager(mgr).state.borrow();
+
+    find(
+        state.store.skus_fetched,
+        &state.store.skus,
+        |sku| sku.id == sku_id,
+        sku,
+    )
+}
+
+unsafe extern "C" fn get_sku_at(
+    mgr: *mut sys::IDiscordStoreManager,
+    index: i32,
+    sku: *mut sys::DiscordSku,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("store.get_sku_at") {
+        return res;
+    }
+
+    let state = Instance::from_manager(mgr).state.borrow();
+    let index = usize::try_from(index).ok();
+
+    find(
+        state.store.skus_fetched,
+        index
+            .and_then(|index| state.store.skus.get(index..=index))
+            .unwrap_or(&[]),
+        |_| true,
+        sku,
+    )
+}
+
+unsafe extern "C" fn fetch_entitlements(
+    mgr: *mut sys::IDiscordStoreManager,
+    callback_data: *mut c_void,
+    callback: ResultCallback,
+) {
+    let _fault = Instance::from_manager(mgr).fault_next_callback("store.fetch_entitlements");
+
+    Instance::from_manager(mgr).respond(callback_data, callback, |instance| {
+        instance.state.borrow_mut().store.entitlements_fetched = true;
+
+        sys::DiscordResult_Ok
+    })
+}
+
+unsafe extern "C" fn count_entitlements(mgr: *mut sys::IDiscordStoreManager, count_: *mut i32) {
+    let state = Instance::from_manager(mgr).state.borrow();
+
+    *count_ = count(state.store.entitlements_fetched, &state.store.entitlements);
+}
+
+unsafe extern "C" fn get_entitlement(
+    mgr: *mut sys::IDiscordStoreManager,
+    entitlement_id: sys::DiscordSnowflake,
+    entitlement: *mut sys::DiscordEntitlement,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("store.get_entitlement") {
+        return res;
+    }
+
+    let state = Instance::from_manager(mgr).state.borrow();
+
+    find(
+        state.store.entitlements_fetched,
+        &state.store.entitlements,
+        |entitlement| entitlement.id == entitlement_id,
+        entitlement,
+    )
+}
+
+unsafe extern "C" fn get_entitlement_at(
+    mgr: *mut sys::IDiscordStoreManager,
+    index: i32,
+    entitlement: *mut sys::DiscordEntitlement,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("store.get_entitlement_at") {
+        return res;
+    }
+
+    let state = Instance::from_manager(mgr).state.borrow();
+    let index = usize::try_from(index).ok();
+
+    find(
+        state.store.entitlements_fetched,
+        index
+            .and_then(|index| state.store.entitlements.get(index..=index))
+            .unwrap_or(&[]),
+        |_| true,
+        entitlement,
+    )
+}
+
+unsafe extern "C" fn has_sku_entitlement(
+    mgr: *mut sys::IDiscordStoreManager,
+    sku_id: sys::DiscordSnowflake,
+    has_entitlement: *mut bool,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("store.has_sku_entitlement") {
+        return res;
+    }
+
+    let state = Instance::from_manager(mgr).state.borrow();
+
+    if !state.store.entitlements_fetched {
+        return sys::DiscordResult_NotFetched;
+    }
+
+    *has_entitlement = state
+        .store
+        .entitlements
+        .iter()
+        .any(|entitlement| entitlement.sku_id == sku_id);
+
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn start_purchase(
+    mgr: *mut sys::IDiscordStoreManager,
+    sku_id: sys::DiscordSnowflake,
+    callback_data: *mut c_void,
+    callback: ResultCallback,
+) {
+    let _fault = Instance::from_manager(mgr).fault_next_callback("store.start_purchase");
+
+    Instance::from_manager(mgr).respond(callback_data, callback, move |instance| {
+        let entitlement = {
+            let mut state = instance.state.borrow_mut();
+
+            if !state.store.skus.iter().any(|sku| sku.id == sku_id) {
+                return sys::DiscordResult_NotFound;
+            }
+
+            let entitlement = sys::DiscordEntitlement {
+                id: state.store.next_entitlement_id,
+                type_: sys::DiscordEntitlementType_Purchase,
+                sku_id,
+            };
+
+            state.store.next_entitlement_id += 1;
+            state.store.entitlements.push(entitlement);
+
+            entitlement
+        };
+
+        instance.event(instance.params.store_events, |events, data| unsafe {
+            let mut entitlement = entitlement;
+            events.on_entitlement_create.unwrap()(data, &mut entitlement)
+        });
+
+        sys::DiscordResult_Ok
+    })
+}
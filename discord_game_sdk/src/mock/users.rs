@@ -0,0 +1,74 @@
+use crate::{mock::ffi::Instance, sys};
+use std::ffi::c_void;
+
+pub(crate) const VTABLE: sys::IDiscordUserManager = sys::IDiscordUserManager {
+    get_current_user: Some(get_current_user),
+    get_user: Some(get_user),
+    get_current_user_premium_type: Some(get_current_user_premium_type),
+    current_user_has_flag: Some(current_user_has_flag),
+};
+
+unsafe extern "C" fn get_current_user(
+    mgr: *mut sys::IDiscordUserManager,
+    current_user: *mut sys::DiscordUser,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("users.get_current_user") {
+        return res;
+    }
+
+    *current_user = Instance::from_manager(mgr).state.borrow().current_user();
+
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn get_user(
+    mgr: *mut sys::IDiscordUserManager,
+    user_id: sys::DiscordUserId,
+    callback_data: *mut c_void,
+    callback: Option<unsafe extern "C" fn(*mut c_void, sys::EDiscordResult, *mut sys::DiscordUser)>,
+) {
+    let _fault = Instance::from_manager(mgr).fault_next_callback("users.get_user");
+
+    Instance::from_manager(mgr).defer(move |instance, res| {
+        let found = instance.find_user(user_id);
+
+        let (res, mut user) = match found {
+            _ if res != sys::DiscordResult_Ok => (res, sys::DiscordUser::default()),
+            Some(user) => (sys::DiscordResult_Ok, user),
+            None => (sys::DiscordResult_NotFound, sys::DiscordUser::default()),
+        };
+
+        callback.unwrap()(callback_data, res, &mut user)
+    })
+}
+
+unsafe extern "C" fn get_current_user_premium_type(
+    mgr: *mut sys::IDiscordUserManager,
+    premium_type: *mut sys::EDiscordPremiumType,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("users.get_current_user_premium_type") {
+        return res;
+    }
+
+    *premium_type = Instance::from_manager(mgr)
+        .state
+        .borrow()
+        .users
+        .premium_type;
+
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn current_user_has_flag(
+    mgr: *mut sys::IDiscordUserManager,
+    flag: sys::EDiscordUserFlag,
+    has_flag: *mut bool,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("users.current_user_has_flag") {
+        return res;
+    }
+
+    *has_flag = Instance::from_manager(mgr).state.borrow().users.flags & flag == flag;
+
+    sys::DiscordResult_Ok
+}
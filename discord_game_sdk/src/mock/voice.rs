@@ -0,0 +1,187 @@
+use crate::{
+    mock::ffi::{Instance, ResultCallback},
+    sys,
+};
+use std::ffi::c_void;
+
+pub(crate) const VTABLE: sys::IDiscordVoiceManager = sys::IDiscordVoiceManager {
+    get_input_mode: Some(get_input_mode),
+    set_input_mode: Some(set_input_mode),
+    is_self_mute: Some(is_self_mute),
+    set_self_mute: Some(set_self_mute),
+    is_self_deaf: Some(is_self_deaf),
+    set_self_deaf: Some(set_self_deaf),
+    is_local_mute: Some(is_local_mute),
+    set_local_mute: Some(set_local_mute),
+    get_local_volume: Some(get_local_volume),
+    set_local_volume: Some(set_local_volume),
+};
+
+fn fire_settings_update(instance: &Instance) {
+    instance.event(instance.params.voice_events, |events, data| unsafe {
+        events.on_settings_update.unwrap()(data)
+    })
+}
+
+unsafe extern "C" fn get_input_mode(
+    mgr: *mut sys::IDiscordVoiceManager,
+    input_mode: *mut sys::DiscordInputMode,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("voice.get_input_mode") {
+        return res;
+    }
+
+    *input_mode = Instance::from_manager(mgr).state.borrow().voice.input_mode;
+
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn set_input_mode(
+    mgr: *mut sys::IDiscordVoiceManager,
+    input_mode: sys::DiscordInputMode,
+    callback_data: *mut c_void,
+    callback: ResultCallback,
+) {
+    let _fault = Instance::from_manager(mgr).fault_next_callback("voice.set_input_mode");
+
+    Instance::from_manager(mgr).respond(callback_data, callback, move |instance| {
+        instance.state.borrow_mut().voice.input_mode = input_mode;
+        fire_settings_update(instance);
+
+        sys::DiscordResult_Ok
+    })
+}
+
+unsafe extern "C" fn is_self_mute(
+    mgr: *mut sys::IDiscordVoiceManager,
+    mute: *mut bool,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("voice.is_self_mute") {
+        return res;
+    }
+
+    *mute = Instance::from_manager(mgr).state.borrow().voice.self_mute;
+
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn set_self_mute(
+    mgr: *mut sys::IDiscordVoiceManager,
+    mute: bool,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("voice.set_self_mute") {
+        return res;
+    }
+
+    let instance = Instance::from_manager(mgr);
+    instance.state.borrow_mut().voice.self_mute = mute;
+    instance.emit(fire_settings_update);
+
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn is_self_deaf(
+    mgr: *mut sys::IDiscordVoiceManager,
+    deaf: *mut bool,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("voice.is_self_deaf") {
+        return res;
+    }
+
+    *deaf = Instance::from_manager(mgr).state.borrow().voice.self_deaf;
+
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn set_self_deaf(
+    mgr: *mut sys::IDiscordVoiceManager,
+    deaf: bool,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("voice.set_self_deaf") {
+        return res;
+    }
+
+    let instance = Instance::from_manager(mgr);
+    instance.state.borrow_mut().voice.self_deaf = deaf;
+    instance.emit(fire_settings_update);
+
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn is_local_mute(
+    mgr: *mut sys::IDiscordVoiceManager,
+    user_id: sys::DiscordSnowflake,
+    mute: *mut bool,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("voice.is_local_mute") {
+        return res;
+    }
+
+    let state = Instance::from_manager(mgr).state.borrow();
+
+    *mute = state
+        .voice
+        .local_mutes
+        .get(&user_id)
+        .cloned()
+        .unwrap_or(false);
+
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn set_local_mute(
+    mgr: *mut sys::IDiscordVoiceManager,
+    user_id: sys::DiscordSnowflake,
+    mute: bool,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("voice.set_local_mute") {
+        return res;
+    }
+
+    let mut state = Instance::from_manager(mgr).state.borrow_mut();
+
+    let _ = state.voice.local_mutes.insert(user_id, mute);
+
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn get_local_volume(
+    mgr: *mut sys::IDiscordVoiceManager,
+    user_id: sys::DiscordSnowflake,
+    volume: *mut u8,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("voice.get_local_volume") {
+        return res;
+    }
+
+    let state = Instance::from_manager(mgr).state.borrow();
+
+    *volume = state
+        .voice
+        .local_volumes
+        .get(&user_id)
+        .cloned()
+        .unwrap_or(100);
+
+    sys::DiscordResult_Ok
+}
+
+unsafe extern "C" fn set_local_volume(
+    mgr: *mut sys::IDiscordVoiceManager,
+    user_id: sys::DiscordSnowflake,
+    volume: u8,
+) -> sys::EDiscordResult {
+    if let Some(res) = Instance::from_manager(mgr).fault("voice.set_local_volume") {
+        return res;
+    }
+
+    if volume > 200 {
+        return sys::DiscordResult_InvalidPayload;
+    }
+
+    let mut state = Instance::from_manager(mgr).state.borrow_mut();
+
+    let _ = state.voice.local_volumes.insert(user_id, volume);
+
+    sys::DiscordResult_Ok
+}
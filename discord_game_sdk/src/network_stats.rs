@@ -0,0 +1,210 @@
+use crate::{ChannelStats, LobbyID, NetworkChannelID, NetworkPeerID, Reliability};
+use std::collections::HashMap;
+
+/// A snapshot of the traffic counters of the networking and lobby networking layers
+///
+/// Counters are disabled by default, see
+/// [`enable_network_stats`](struct.Discord.html#method.enable_network_stats).
+/// They count what goes through [`send_message`](struct.Discord.html#method.send_message),
+/// [`send_lobby_network_message`](struct.Discord.html#method.send_lobby_network_message),
+/// and the network message events, by peer or lobby, and by channel.
+///
+/// ```rust
+/// # use discord_game_sdk::*;
+/// # fn example(discord: Discord<'_, ()>) -> Result<()> {
+/// discord.enable_network_stats();
+///
+/// // ...
+///
+/// if let Some(stats) = discord.network_stats() {
+///     let unreliable = stats.reliability_total(Reliability::Unreliable);
+///     println!("{} bytes out, {} bytes in", unreliable.bytes_sent(), unreliable.bytes_received());
+///
+///     for (peer_id, channel_id, channel) in stats.iter_peers() {
+///         if channel.send_error_count() > 0 {
+///             println!("{}:{} errors {:?}", peer_id, channel_id, channel.send_errors());
+///         }
+///     }
+/// }
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct NetworkStats {
+    peers: HashMap<(NetworkPeerID, NetworkChannelID), ChannelStats>,
+    lobbies: HashMap<(LobbyID, NetworkChannelID), ChannelStats>,
+}
+
+impl NetworkStats {
+    /// The counters of a channel to a peer
+    pub fn peer(
+        &self,
+        peer_id: NetworkPeerID,
+        channel_id: NetworkChannelID,
+    ) -> Option<&ChannelStats> {
+        self.peers.get(&(peer_id, channel_id))
+    }
+
+    /// The counters of a channel of a lobby
+    pub fn lobby(&self, lobby_id: LobbyID, channel_id: NetworkChannelID) -> Option<&ChannelStats> {
+        self.lobbies.get(&(lobby_id, channel_id))
+    }
+
+    /// The counters of every channel to a peer, in no particular order
+    pub fn iter_peers(
+        &self,
+    ) -> impl '_ + Iterator<Item = (NetworkPeerID, NetworkChannelID, &ChannelStats)> {
+        self.peers
+            .iter()
+            .map(|(&(peer_id, channel_id), channel)| (peer_id, channel_id, channel))
+    }
+
+    /// The counters of every channel of a lobby, in no particular order
+    pub fn iter_lobbies(
+        &self,
+    ) -> impl '_ + Iterator<Item = (LobbyID, NetworkChannelID, &ChannelStats)> {
+        self.lobbies
+            .iter()
+            .map(|(&(lobby_id, channel_id), channel)| (lobby_id, channel_id, channel))
+    }
+
+    /// The sum of the counters of every channel to a peer
+    pub fn peer_total(&self, peer_id: NetworkPeerID) -> ChannelStats {
+        Self::sum(
+            self.iter_peers()
+                .filter(|&(id, _, _)| id == peer_id)
+                .map(|(_, _, channel)| channel),
+        )
+    }
+
+    /// The sum of the counters of every channel of a lobby
+    pub fn lobby_total(&self, lobby_id: LobbyID) -> ChannelStats {
+        Self::sum(
+            self.iter_lobbies()
+                .filter(|&(id, _, _)| id == lobby_id)
+                .map(|(_, _, channel)| channel),
+        )
+    }
+
+    /// The sum of the counters of every channel opened with a given reliability
+    pub fn reliability_total(&self, reliability: Reliability) -> ChannelStats {
+        let mut total = Self::sum(
+            self.peers
+                .values()
+                .chain(self.lobbies.values())
+                .filter(|channel| channel.reliability == Some(reliability)),
+        );
+
+        total.reliability = Some(reliability);
+        total
+    }
+
+    /// The sum of the counters of every channel
+    pub fn total(&self) -> ChannelStats {
+        Self::sum(self.peers.values().chain(self.lobbies.values()))
+    }
+
+    fn sum<'a>(channels: impl Iterator<Item = &'a ChannelStats>) -> ChannelStats {
+        let mut total = ChannelStats::default();
+
+        for channel in channels {
+            total.merge(channel);
+        }
+
+        total
+    }
+
+    pub(crate) fn reset(&mut self) {
+        for channel in self.peers.values_mut().chain(self.lobbies.values_mut()) {
+            *channel = ChannelStats {
+                reliability: channel.reliability,
+                ..ChannelStats::default()
+            };
+        }
+    }
+
+    pub(crate) fn peer_mut(
+        &mut self,
+        peer_id: NetworkPeerID,
+        channel_id: NetworkChannelID,
+    ) -> &mut ChannelStats {
+        self.peers.entry((peer_id, channel_id)).or_default()
+    }
+
+    pub(crate) fn lobby_mut(
+        &mut self,
+        lobby_id: LobbyID,
+        channel_id: NetworkChannelID,
+    ) -> &mut ChannelStats {
+        self.lobbies.entry((lobby_id, channel_id)).or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Error, MockHub, MockLobby, MockState, Reliability};
+
+    #[test]
+    fn counts_traffic() {
+        let hub = MockHub::new();
+
+        let alice = hub.member(
+            MockState::new()
+                .with_current_user(1, "alice", "0001")
+                .with_lobby(MockLobby::new(42, 1).with_member(2)),
+        );
+
+        let mut bob = hub.member(MockState::new().with_current_user(2, "bob", "0002"));
+
+        assert_eq!(alice.network_stats(), None);
+
+        alice.enable_network_stats();
+        bob.enable_network_stats();
+
+        alice.open_peer(2, "mock-route-2").unwrap();
+        alice.open_channel(2, 0, Reliability::Unreliable).unwrap();
+        bob.open_peer(1, "mock-route-1").unwrap();
+        bob.open_channel(1, 0, Reliability::Unreliable).unwrap();
+
+        for discord in &[&alice, &bob] {
+            discord.connect_lobby_network(42).unwrap();
+            discord
+                .open_lobby_network_channel(42, 1, Reliability::Reliable)
+                .unwrap();
+        }
+
+        alice.send_message(2, 0, [0; 10]).unwrap();
+        alice.send_message(2, 0, [0; 5]).unwrap();
+        alice.mock_fail("networking.send_message", Error::InvalidChannel, 1);
+        assert!(alice.send_message(2, 0, [0; 5]).is_err());
+        alice.send_lobby_network_message(42, 2, 1, &[0; 7]).unwrap();
+
+        bob.run_callbacks().unwrap();
+
+        let sent = alice.network_stats().unwrap();
+        let peer = sent.peer(2, 0).unwrap();
+        assert_eq!(peer.reliability(), Some(Reliability::Unreliable));
+        assert_eq!((peer.messages_sent(), peer.bytes_sent()), (2, 15));
+        assert_eq!(peer.send_errors().get(&Error::InvalidChannel), Some(&1));
+        assert_eq!(sent.lobby(42, 1).unwrap().bytes_sent(), 7);
+        assert_eq!(
+            sent.reliability_total(Reliability::Reliable).bytes_sent(),
+            7
+        );
+        assert_eq!(sent.total().bytes_sent(), 22);
+
+        let received = bob.network_stats().unwrap();
+        assert_eq!(received.peer_total(1).messages_received(), 2);
+        assert_eq!(received.lobby_total(42).bytes_received(), 7);
+
+        bob.reset_network_stats();
+        let reset = bob.network_stats().unwrap();
+        assert_eq!(reset.total().bytes_received(), 0);
+        assert_eq!(
+            reset.lobby(42, 1).unwrap().reliability(),
+            Some(Reliability::Reliable)
+        );
+
+        bob.disable_network_stats();
+        assert_eq!(bob.network_stats(), None);
+    }
+}
@@ -42,3 +42,42 @@ impl std::fmt::Debug for OAuth2Token {
             .finish()
     }
 }
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for OAuth2Token {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("OAuth2Token", 3)?;
+        state.serialize_field("access_token", &self.access_token())?;
+        state.serialize_field("scopes", &self.scopes())?;
+        state.serialize_field("expires", &self.expires())?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for OAuth2Token {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use crate::utils::deserialize_charbuf;
+
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "OAuth2Token")]
+        struct Fields {
+            access_token: String,
+            scopes: String,
+            expires: UnixTimestamp,
+        }
+
+        let fields = Fields::deserialize(deserializer)?;
+        let mut raw = sys::DiscordOAuth2Token {
+            expires: fields.expires,
+            ..sys::DiscordOAuth2Token::default()
+        };
+
+        deserialize_charbuf(&mut raw.access_token, &fields.access_token)?;
+        deserialize_charbuf(&mut raw.scopes, &fields.scopes)?;
+
+        Ok(Self(raw))
+    }
+}
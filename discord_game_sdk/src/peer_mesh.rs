@@ -0,0 +1,464 @@
+use crate::{
+    Discord, Error, Event, LobbyID, LobbyMemberTransaction, LobbyMetadata, MetadataDecoder,
+    MetadataEncoder, NetworkChannelID, NetworkPeerID, Reliability, Result, UserID,
+};
+use std::{
+    cell::Cell,
+    collections::{BTreeSet, HashMap},
+    rc::Rc,
+};
+
+/// Connects the current user to every member of a lobby through the
+/// [networking layer](struct.Discord.html#networking)
+///
+/// The current user's peer ID and route are published in their member metadata, under the
+/// `peer_id` and `route` keys, and published again when the route changes.
+/// Peers are opened, with every channel of the mesh, for members that published theirs,
+/// updated when their route changes, and closed when they leave.
+///
+/// The route comes from [`Event::NetworkRouteUpdate`](enum.Event.html#variant.NetworkRouteUpdate),
+/// which the SDK fires soon after starting, the mesh must be created before it is handled.
+///
+/// Like [`LobbyCache`](struct.LobbyCache.html), events only mark members as stale,
+/// and they are read again on the next [`update`](#method.update).
+///
+/// ```rust
+/// # use discord_game_sdk::*;
+/// # fn example(mut discord: Discord<'_, EventQueue>, lobby_id: LobbyID) -> Result<()> {
+/// let mut mesh = PeerMesh::new(lobby_id);
+/// mesh.channel(0, Reliability::Reliable)
+///     .channel(1, Reliability::Unreliable);
+///
+/// loop {
+///     discord.run_callbacks()?;
+///
+///     for event in discord.drain_events() {
+///         mesh.handle_event(&event);
+///     }
+///
+///     mesh.update(&discord)?;
+///
+///     for peer_id in mesh.peer_ids() {
+///         discord.send_message(peer_id, 1, b"ping")?;
+///     }
+/// #   break;
+/// }
+/// # Ok(()) }
+/// ```
+#[derive(Debug)]
+pub struct PeerMesh {
+    lobby_id: LobbyID,
+    channels: Vec<(NetworkChannelID, Reliability)>,
+    route: Option<String>,
+    publish: Rc<Cell<Publish>>,
+    peers: HashMap<UserID, PeerInfo>,
+    stale: BTreeSet<UserID>,
+    refresh: bool,
+}
+
+// Clones do not share the publish state, so that they cannot change each other's.
+// A clone does not get the callback of a pending publish, and publishes again instead.
+impl Clone for PeerMesh {
+    fn clone(&self) -> Self {
+        let publish = match self.publish.get() {
+            Publish::Done => Publish::Done,
+            Publish::Needed | Publish::Pending | Publish::PendingOutdated => Publish::Needed,
+        };
+
+        Self {
+            lobby_id: self.lobby_id,
+            channels: self.channels.clone(),
+            route: self.route.clone(),
+            publish: Rc::new(Cell::new(publish)),
+            peers: self.peers.clone(),
+            stale: self.stale.clone(),
+            refresh: self.refresh,
+        }
+    }
+}
+
+/// Whether the current user's route is published, it is published again if it changed
+/// while being published, or if publishing failed
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Publish {
+    Needed,
+    Pending,
+    PendingOutdated,
+    Done,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct PeerInfo {
+    peer_id: NetworkPeerID,
+    route: String,
+}
+
+impl LobbyMetadata for PeerInfo {
+    fn encode(&self, encoder: &mut MetadataEncoder) {
+        encoder
+            .set("peer_id", &self.peer_id)
+            .set("route", &self.route);
+    }
+
+    fn decode(decoder: &MetadataDecoder<'_>) -> Result<Self> {
+        Ok(Self {
+            peer_id: decoder.get("peer_id")?,
+            route: decoder.get("route")?,
+        })
+    }
+}
+
+impl PeerMesh {
+    /// A mesh over the members of a lobby, without channels
+    ///
+    /// Every member is read on the first update.
+    pub fn new(lobby_id: LobbyID) -> Self {
+        Self {
+            lobby_id,
+            channels: Vec::new(),
+            route: None,
+            publish: Rc::new(Cell::new(Publish::Needed)),
+            peers: HashMap::new(),
+            stale: BTreeSet::new(),
+            refresh: true,
+        }
+    }
+
+    /// Adds a channel to open with every peer, which must be the same on every member
+    pub fn channel(&mut self, channel_id: NetworkChannelID, reliability: Reliability) -> &mut Self {
+        self.channels.push((channel_id, reliability));
+        self
+    }
+
+    /// The lobby of the mesh
+    pub fn lobby_id(&self) -> LobbyID {
+        self.lobby_id
+    }
+
+    /// The peer ID of a member, once their peer is open
+    pub fn peer_id(&self, member_id: UserID) -> Option<NetworkPeerID> {
+        self.peers.get(&member_id).map(|peer| peer.peer_id)
+    }
+
+    /// The member of a peer ID
+    pub fn member_id(&self, peer_id: NetworkPeerID) -> Option<UserID> {
+        self.peers
+            .iter()
+            .find(|(_, peer)| peer.peer_id == peer_id)
+            .map(|(&member_id, _)| member_id)
+    }
+
+    /// The peer IDs of every open peer, in no particular order
+    pub fn peer_ids(&self) -> impl '_ + Iterator<Item = NetworkPeerID> {
+        self.peers.values().map(|peer| peer.peer_id)
+    }
+
+    /// Updates the mesh from lobby and networking events, ignores other events
+    pub fn handle_event(&mut self, event: &Event) {
+        match *event {
+            Event::NetworkRouteUpdate { ref route } => self.on_network_route_update(route),
+            Event::MemberConnect {
+                lobby_id,
+                member_id,
+            } => self.on_member_connect(lobby_id, member_id),
+            Event::MemberUpdate {
+                lobby_id,
+                member_id,
+            } => self.on_member_update(lobby_id, member_id),
+            Event::MemberDisconnect {
+                lobby_id,
+                member_id,
+            } => self.on_member_disconnect(lobby_id, member_id),
+            _ => {}
+        }
+    }
+
+    /// To be called from [`EventHandler::on_network_route_update`](trait.EventHandler.html#method.on_network_route_update)
+    pub fn on_network_route_update(&mut self, route: &str) {
+        if let Some(ref old) = self.route {
+            if old == route {
+                return;
+            }
+        }
+
+        self.route = Some(route.to_string());
+
+        self.publish.set(match self.publish.get() {
+            Publish::Pending | Publish::PendingOutdated => Publish::PendingOutdated,
+            Publish::Needed | Publish::Done => Publish::Needed,
+        });
+    }
+
+    /// To be called from [`EventHandler::on_member_connect`](trait.EventHandler.html#method.on_member_connect)
+    pub fn on_member_connect(&mut self, lobby_id: LobbyID, member_id: UserID) {
+        if lobby_id == self.lobby_id {
+            let _ = self.stale.insert(member_id);
+        }
+    }
+
+    /// To be called from [`EventHandler::on_member_update`](trait.EventHandler.html#method.on_member_update)
+    pub fn on_member_update(&mut self, lobby_id: LobbyID, member_id: UserID) {
+        if lobby_id == self.lobby_id {
+            let _ = self.stale.insert(member_id);
+        }
+    }
+
+    /// To be called from [`EventHandler::on_member_disconnect`](trait.EventHandler.html#method.on_member_disconnect)
+    pub fn on_member_disconnect(&mut self, lobby_id: LobbyID, member_id: UserID) {
+        if lobby_id == self.lobby_id {
+            let _ = self.stale.insert(member_id);
+        }
+    }
+
+    /// Publishes the current user's route if needed, and opens, updates or closes
+    /// the peers of stale members
+    ///
+    /// ## Errors
+    ///
+    /// Members that could not be read or connected to stay stale, and are read again on the
+    /// next update. The other members are still read, and the first error is returned.
+    ///
+    /// A route that failed to publish is published again on the next update.
+    pub fn update<E>(&mut self, discord: &Discord<'_, E>) -> Result<()> {
+        let current_user_id = discord.current_user()?.id();
+
+        if self.refresh {
+            for member_id in discord.iter_lobby_member_ids(self.lobby_id)? {
+                let _ = self.stale.insert(member_id?);
+            }
+
+            self.refresh = false;
+        }
+
+        if let (Publish::Needed, Some(route)) = (self.publish.get(), self.route.as_ref()) {
+            let info = PeerInfo {
+                peer_id: discord.peer_id(),
+                route: route.clone(),
+            };
+
+            let publish = self.publish.clone();
+            publish.set(Publish::Pending);
+
+            discord.update_member(
+                self.lobby_id,
+                current_user_id,
+                LobbyMemberTransaction::new().add_typed_metadata(&info)?,
+                move |_, res| {
+                    if let Err(ref error) = res {
+                        log::warn!("failed to publish network route: {}", error);
+                    }
+
+                    publish.set(match (publish.get(), res) {
+                        (Publish::Pending, Ok(())) => Publish::Done,
+                        (Publish::Pending, Err(_)) | (Publish::PendingOutdated, _) => {
+                            Publish::Needed
+                        }
+                        (state, _) => state,
+                    });
+                },
+            );
+        }
+
+        let mut result = Ok(());
+
+        for member_id in self.stale.iter().cloned().collect::<Vec<_>>() {
+            let res = if member_id == current_user_id {
+                Ok(())
+            } else {
+                self.update_member(discord, member_id)
+            };
+
+            match res {
+                Ok(()) => {
+                    let _ = self.stale.remove(&member_id);
+                }
+
+                Err(error) => {
+                    if result.is_ok() {
+                        result = Err(error);
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    fn update_member<E>(&mut self, discord: &Discord<'_, E>, member_id: UserID) -> Result<()> {
+        let info = match discord.lobby_member_typed_metadata::<PeerInfo>(self.lobby_id, member_id) {
+            Ok(info) => Some(info),
+            // Left the lobby, or has not published their route yet
+            Err(Error::NotFound) | Err(Error::InvalidPayload) => None,
+            Err(error) => return Err(error),
+        };
+
+        self.connect(discord, member_id, info)
+    }
+
+    /// Closes every peer, for when the current user leaves the lobby
+    pub fn close<E>(&mut self, discord: &Discord<'_, E>) -> Result<()> {
+        let member_ids = self.peers.keys().cloned().collect::<Vec<_>>();
+
+        for member_id in member_ids {
+            self.connect(discord, member_id, None)?;
+        }
+
+        Ok(())
+    }
+
+    fn connect<E>(
+        &mut self,
+        discord: &Discord<'_, E>,
+        member_id: UserID,
+        info: Option<PeerInfo>,
+    ) -> Result<()> {
+        match (self.peers.get(&member_id).cloned(), info) {
+            (Some(ref old), Some(ref new)) if old == new => {}
+
+            (Some(old), Some(ref new)) if old.peer_id == new.peer_id => {
+                discord.update_peer(new.peer_id, new.route.as_str())?;
+                let _ = self.peers.insert(member_id, new.clone());
+            }
+
+            (old, new) => {
+                if let Some(old) = old {
+                    for &(channel_id, _) in &self.channels {
+                        discord.close_channel(old.peer_id, channel_id)?;
+                    }
+
+                    discord.close_peer(old.peer_id)?;
+                    let _ = self.peers.remove(&member_id);
+                }
+
+                if let Some(new) = new {
+                    discord.open_peer(new.peer_id, new.route.as_str())?;
+
+                    for &(channel_id, reliability) in &self.channels {
+                        if let Err(error) =
+                            discord.open_channel(new.peer_id, channel_id, reliability)
+                        {
+                            // Opened again on the next update
+                            if let Err(error) = discord.close_peer(new.peer_id) {
+                                log::warn!("failed to close peer: {}", error);
+                            }
+
+                            return Err(error);
+                        }
+                    }
+
+                    let _ = self.peers.insert(member_id, new);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Discord, Error, Event, EventQueue, MockHub, MockLobby, MockState, PeerMesh, Reliability,
+    };
+
+    fn step(discord: &mut Discord<'_, EventQueue>, mesh: &mut PeerMesh) -> Vec<Event> {
+        discord.run_callbacks().unwrap();
+
+        let events = discord.drain_events().collect::<Vec<_>>();
+
+        for event in &events {
+            mesh.handle_event(event);
+        }
+
+        mesh.update(discord).unwrap();
+        events
+    }
+
+    #[test]
+    fn connects_members() {
+        let hub = MockHub::new();
+
+        let mut alice = hub.member(
+            MockState::new()
+                .with_current_user(1, "alice", "0001")
+                .with_lobby(MockLobby::new(42, 1).with_member(2)),
+        );
+
+        let mut bob = hub.member(MockState::new().with_current_user(2, "bob", "0002"));
+
+        let mut alice_mesh = PeerMesh::new(42);
+        alice_mesh.channel(0, Reliability::Reliable);
+
+        let mut bob_mesh = PeerMesh::new(42);
+        bob_mesh.channel(0, Reliability::Reliable);
+
+        for _ in 0..3 {
+            let _ = step(&mut alice, &mut alice_mesh);
+            let _ = step(&mut bob, &mut bob_mesh);
+        }
+
+        assert_eq!(alice_mesh.peer_id(2), Some(2));
+        assert_eq!(bob_mesh.peer_id(1), Some(1));
+        assert_eq!(bob_mesh.member_id(1), Some(1));
+        assert_eq!(bob_mesh.peer_ids().collect::<Vec<_>>(), [1]);
+
+        alice.send_message(2, 0, b"hi").unwrap();
+
+        let events = step(&mut bob, &mut bob_mesh);
+        assert!(events.contains(&Event::NetworkMessage {
+            peer_id: 1,
+            channel_id: 0,
+            data: b"hi".to_vec(),
+        }));
+
+        drop(bob);
+        let _ = step(&mut alice, &mut alice_mesh);
+
+        assert_eq!(alice_mesh.peer_id(2), None);
+        assert!(alice.send_message(2, 0, b"hi").is_err());
+    }
+
+    #[test]
+    fn retries() {
+        let hub = MockHub::new();
+
+        let mut alice = hub.member(
+            MockState::new()
+                .with_current_user(1, "alice", "0001")
+                .with_lobby(MockLobby::new(42, 1).with_member(2)),
+        );
+
+        let mut bob = hub.member(MockState::new().with_current_user(2, "bob", "0002"));
+
+        let mut meshes = [PeerMesh::new(42), PeerMesh::new(42)];
+
+        for mesh in &mut meshes {
+            mesh.channel(0, Reliability::Reliable);
+        }
+
+        alice.mock_fail("lobbies.update_member", Error::ServiceUnavailable, 1);
+        bob.mock_fail("networking.open_channel", Error::InvalidChannel, 1);
+
+        let mut errors = Vec::new();
+
+        for _ in 0..4 {
+            for (discord, mesh) in [&mut alice, &mut bob].iter_mut().zip(&mut meshes) {
+                discord.run_callbacks().unwrap();
+
+                for event in discord.drain_events() {
+                    mesh.handle_event(&event);
+                }
+
+                if let Err(error) = mesh.update(discord) {
+                    errors.push(error);
+                }
+            }
+        }
+
+        // The route is published again, and the peer is opened again
+        assert_eq!(errors, [Error::InvalidChannel]);
+        assert_eq!(meshes[0].peer_id(2), Some(2));
+        assert_eq!(meshes[1].peer_id(1), Some(1));
+        bob.send_message(1, 0, b"hi").unwrap();
+    }
+}
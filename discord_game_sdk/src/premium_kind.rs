@@ -4,6 +4,7 @@ use crate::sys;
 ///
 /// > [Enum in official docs](https://discordapp.com/developers/docs/game-sdk/users#data-models-premiumtype-enum)
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PremiumKind {
     /// Not a Nitro subscriber
     None,
@@ -25,3 +26,14 @@ impl From<sys::EDiscordPremiumType> for PremiumKind {
         }
     }
 }
+
+impl Into<sys::EDiscordPremiumType> for PremiumKind {
+    fn into(self) -> sys::EDiscordPremiumType {
+        match self {
+            Self::None => sys::DiscordPremiumType_None,
+            Self::Tier1 => sys::DiscordPremiumType_Tier1,
+            Self::Tier2 => sys::DiscordPremiumType_Tier2,
+            Self::Undefined(n) => n,
+        }
+    }
+}
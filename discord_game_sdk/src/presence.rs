@@ -33,3 +33,34 @@ impl std::fmt::Display for Presence {
         write!(fmt, "{}, {}", self.status(), self.activity())
     }
 }
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Presence {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("Presence", 2)?;
+        state.serialize_field("status", &self.status())?;
+        state.serialize_field("activity", self.activity())?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Presence {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "Presence")]
+        struct Fields {
+            status: Status,
+            activity: Activity,
+        }
+
+        let fields = Fields::deserialize(deserializer)?;
+
+        Ok(Self(sys::DiscordPresence {
+            status: fields.status.into(),
+            activity: fields.activity.0,
+        }))
+    }
+}
@@ -33,3 +33,69 @@ impl std::fmt::Debug for Relationship {
             .finish()
     }
 }
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Relationship {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("Relationship", 3)?;
+        state.serialize_field("kind", &self.kind())?;
+        state.serialize_field("user", self.user())?;
+        state.serialize_field("presence", self.presence())?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Relationship {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "Relationship")]
+        struct Fields {
+            kind: RelationshipKind,
+            user: User,
+            presence: Presence,
+        }
+
+        let fields = Fields::deserialize(deserializer)?;
+
+        Ok(Self(sys::DiscordRelationship {
+            type_: fields.kind.into(),
+            user: fields.user.0,
+            presence: fields.presence.0,
+        }))
+    }
+}
+
+#[cfg(all(test, feature = "bincode"))]
+mod tests {
+    use crate::{
+        sys, utils::write_charbuf, Activity, Bincode, Decode, Encode, Relationship,
+        RelationshipKind, Status,
+    };
+
+    #[test]
+    fn serde() {
+        let mut user = sys::DiscordUser {
+            id: 42,
+            ..sys::DiscordUser::default()
+        };
+        write_charbuf(&mut user.username, "friend");
+
+        let relationship = Relationship(sys::DiscordRelationship {
+            type_: RelationshipKind::Friend.into(),
+            user,
+            presence: sys::DiscordPresence {
+                status: Status::Online.into(),
+                activity: Activity::empty()
+                    .with_state("In the menus")
+                    .with_party_capacity(4)
+                    .0,
+            },
+        });
+
+        let bytes = Bincode(relationship.clone()).encode().unwrap();
+        assert_eq!(Bincode::decode(&bytes), Ok(Bincode(relationship)));
+    }
+}
@@ -0,0 +1,530 @@
+use crate::{Discord, Error, Result};
+use std::fmt::Write;
+
+const MANIFEST_HEADER: &str = "discord_game_sdk save manifest 1";
+
+/// Versioned saves over the [storage](struct.Discord.html#storage) methods
+///
+/// Each slot has a manifest listing its latest version and up to
+/// [`versions`](#method.versions) previous ones, with their length and CRC-32 checksum.
+/// Saving writes the contents to a new file, and only then writes the manifest that points
+/// to it. The manifest alternates between two files, so that one of them is always whole.
+/// A save interrupted at any point leaves the previous version in place.
+///
+/// Loading uses the latest version whose contents match the manifest,
+/// and falls back to the previous ones otherwise.
+///
+/// For a slot `profile` and a prefix `saves/`, the files are `saves/profile.manifest.0`,
+/// `saves/profile.manifest.1` and `saves/profile.<version>`.
+///
+/// ```rust
+/// # use discord_game_sdk::*;
+/// # fn example(discord: Discord<'_, ()>) -> Result<()> {
+/// let mut slots = SaveSlots::new();
+/// slots.prefix("saves/").versions(2);
+///
+/// slots.save(&discord, "profile", b"level 3")?;
+///
+/// assert_eq!(slots.load(&discord, "profile")?, b"level 3");
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Debug)]
+pub struct SaveSlots {
+    prefix: String,
+    versions: usize,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+struct Manifest {
+    sequence: u64,
+    // Newest first
+    entries: Vec<Entry>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Entry {
+    version: u64,
+    length: u64,
+    checksum: u32,
+}
+
+impl SaveSlots {
+    /// Slots without prefix, that keep 2 previous versions
+    pub fn new() -> Self {
+        Self {
+            prefix: String::new(),
+            versions: 2,
+        }
+    }
+
+    /// Prepended to the file names of every slot
+    pub fn prefix(&mut self, prefix: impl Into<String>) -> &mut Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// How many previous versions to keep for every slot
+    pub fn versions(&mut self, versions: usize) -> &mut Self {
+        self.versions = versions;
+        self
+    }
+
+    /// Writes a new version of a slot, returns its number
+    ///
+    /// Files of versions that are no longer listed, including those of interrupted saves,
+    /// are deleted afterwards.
+    ///
+    /// ## Errors
+    ///
+    /// [`Error::InvalidFilename`](enum.Error.html#variant.InvalidFilename) if the slot name
+    /// is empty or has a nul byte, or the errors of
+    /// [`write_file`](struct.Discord.html#method.write_file).
+    pub fn save<E>(&self, discord: &Discord<'_, E>, slot: &str, contents: &[u8]) -> Result<u64> {
+        let (manifest, entry) = self.prepare(discord, slot, contents)?;
+
+        discord.write_file(self.version_file(slot, entry.version), contents)?;
+        self.commit(discord, slot, &manifest)?;
+
+        Ok(entry.version)
+    }
+
+    /// Writes a new version of a slot with
+    /// [`write_file_async`](struct.Discord.html#method.write_file_async),
+    /// see [`save`](#method.save)
+    ///
+    /// Saves of a slot should not overlap, as they would write the same version.
+    pub fn save_async<'d, E>(
+        &self,
+        discord: &Discord<'d, E>,
+        slot: &str,
+        contents: &[u8],
+        callback: impl 'd + FnOnce(&Discord<'d, E>, Result<u64>),
+    ) {
+        let (manifest, entry) = match self.prepare(discord, slot, contents) {
+            Ok(prepared) => prepared,
+            Err(error) => return callback(discord, Err(error)),
+        };
+
+        let slots = self.clone();
+        let slot = slot.to_string();
+
+        discord.write_file_async(
+            self.version_file(&slot, entry.version),
+            contents,
+            move |discord, res| {
+                if let Err(error) = res {
+                    return callback(discord, Err(error));
+                }
+
+                discord.write_file_async(
+                    slots.manifest_file(&slot, manifest.sequence),
+                    manifest.to_string(),
+                    move |discord, res| {
+                        callback(
+                            discord,
+                            res.map(|_| {
+                                slots.clean(discord, &slot, &manifest);
+                                entry.version
+                            }),
+                        )
+                    },
+                );
+            },
+        );
+    }
+
+    /// Reads the latest intact version of a slot
+    ///
+    /// ## Errors
+    ///
+    /// [`Error::NotFound`](enum.Error.html#variant.NotFound) if the slot was never saved,
+    /// [`Error::InvalidPayload`](enum.Error.html#variant.InvalidPayload) if no version is intact.
+    pub fn load<E>(&self, discord: &Discord<'_, E>, slot: &str) -> Result<Vec<u8>> {
+        let manifest = self.manifest(discord, slot)?.ok_or(Error::NotFound)?;
+
+        for entry in &manifest.entries {
+            match self.read(discord, slot, entry) {
+                Ok(contents) => return Ok(contents),
+                Err(error) => log::warn!(
+                    "version {} of save slot {} is unreadable: {}",
+                    entry.version,
+                    slot,
+                    error
+                ),
+            }
+        }
+
+        Err(Error::InvalidPayload)
+    }
+
+    /// Reads a given version of a slot
+    ///
+    /// ## Errors
+    ///
+    /// [`Error::NotFound`](enum.Error.html#variant.NotFound) if the version is not listed,
+    /// [`Error::InvalidPayload`](enum.Error.html#variant.InvalidPayload) if it is not intact.
+    pub fn load_version<E>(
+        &self,
+        discord: &Discord<'_, E>,
+        slot: &str,
+        version: u64,
+    ) -> Result<Vec<u8>> {
+        let manifest = self.manifest(discord, slot)?.ok_or(Error::NotFound)?;
+
+        let entry = manifest
+            .entries
+            .iter()
+            .find(|entry| entry.version == version)
+            .ok_or(Error::NotFound)?;
+
+        self.read(discord, slot, entry)
+    }
+
+    /// The versions of a slot, the latest first, empty if it was never saved
+    pub fn history<E>(&self, discord: &Discord<'_, E>, slot: &str) -> Result<Vec<u64>> {
+        Ok(self
+            .manifest(discord, slot)?
+            .map(|manifest| manifest.entries.iter().map(|entry| entry.version).collect())
+            .unwrap_or_default())
+    }
+
+    /// The names of every saved slot, in no particular order
+    pub fn slots<E>(&self, discord: &Discord<'_, E>) -> Result<Vec<String>> {
+        let mut slots = Vec::new();
+
+        for stat in discord.iter_file_stats() {
+            let stat = stat?;
+            let filename = stat.filename().trim_end_matches('\0');
+
+            let name = match strip_prefix(filename, &self.prefix) {
+                Some(name) => name,
+                None => continue,
+            };
+
+            for suffix in &[".manifest.0", ".manifest.1"] {
+                if name.ends_with(suffix) && name.len() > suffix.len() {
+                    let slot = &name[..name.len() - suffix.len()];
+
+                    if !slots.iter().any(|known| known == slot) {
+                        slots.push(slot.to_string());
+                    }
+                }
+            }
+        }
+
+        Ok(slots)
+    }
+
+    /// Deletes every version of a slot, and its manifests
+    pub fn delete<E>(&self, discord: &Discord<'_, E>, slot: &str) -> Result<()> {
+        check_slot(slot)?;
+
+        // Manifests first, so that an interrupted deletion leaves nothing half listed
+        for sequence in 0..2 {
+            ignore_not_found(discord.delete_file(self.manifest_file(slot, sequence)))?;
+        }
+
+        for filename in self.version_files(discord, slot)? {
+            ignore_not_found(discord.delete_file(filename))?;
+        }
+
+        Ok(())
+    }
+
+    fn prepare<E>(
+        &self,
+        discord: &Discord<'_, E>,
+        slot: &str,
+        contents: &[u8],
+    ) -> Result<(Manifest, Entry)> {
+        check_slot(slot)?;
+
+        let mut manifest = self.manifest(discord, slot)?.unwrap_or_default();
+
+        let entry = Entry {
+            version: manifest
+                .entries
+                .first()
+                .map_or(1, |entry| entry.version + 1),
+            length: contents.len() as u64,
+            checksum: crc32(contents),
+        };
+
+        manifest.sequence += 1;
+        manifest.entries.insert(0, entry);
+        manifest.entries.truncate(self.versions + 1);
+
+        Ok((manifest, entry))
+    }
+
+    fn commit<E>(&self, discord: &Discord<'_, E>, slot: &str, manifest: &Manifest) -> Result<()> {
+        discord.write_file(
+            self.manifest_file(slot, manifest.sequence),
+            manifest.to_string(),
+        )?;
+
+        self.clean(discord, slot, manifest);
+        Ok(())
+    }
+
+    // Deletes the files of versions that are not listed, failures only leave files behind
+    fn clean<E>(&self, discord: &Discord<'_, E>, slot: &str, manifest: &Manifest) {
+        let listed = manifest
+            .entries
+            .iter()
+            .map(|entry| self.version_file(slot, entry.version))
+            .collect::<Vec<_>>();
+
+        let res = self.version_files(discord, slot).and_then(|filenames| {
+            filenames
+                .into_iter()
+                .filter(|filename| !listed.contains(filename))
+                .try_for_each(|filename| ignore_not_found(discord.delete_file(filename)))
+        });
+
+        if let Err(error) = res {
+            log::warn!(
+                "failed to delete old versions of save slot {}: {}",
+                slot,
+                error
+            );
+        }
+    }
+
+    // The manifest with the highest sequence among those that are intact
+    fn manifest<E>(&self, discord: &Discord<'_, E>, slot: &str) -> Result<Option<Manifest>> {
+        check_slot(slot)?;
+
+        let mut best: Option<Manifest> = None;
+
+        for sequence in 0..2 {
+            let contents = match read_whole(discord, &self.manifest_file(slot, sequence)) {
+                Ok(contents) => contents,
+                Err(Error::NotFound) => continue,
+                Err(error) => return Err(error),
+            };
+
+            match Manifest::parse(&contents) {
+                Some(manifest) => match best {
+                    Some(ref best) if best.sequence > manifest.sequence => {}
+                    _ => best = Some(manifest),
+                },
+
+                None => log::warn!("manifest {} of save slot {} is corrupted", sequence, slot),
+            }
+        }
+
+        Ok(best)
+    }
+
+    fn read<E>(&self, discord: &Discord<'_, E>, slot: &str, entry: &Entry) -> Result<Vec<u8>> {
+        let contents = read_whole(discord, &self.version_file(slot, entry.version))?;
+
+        if contents.len() as u64 != entry.length || crc32(&contents) != entry.checksum {
+            return Err(Error::InvalidPayload);
+        }
+
+        Ok(contents)
+    }
+
+    fn version_files<E>(&self, discord: &Discord<'_, E>, slot: &str) -> Result<Vec<String>> {
+        let prefix = format!("{}{}.", self.prefix, slot);
+        let mut filenames = Vec::new();
+
+        for stat in discord.iter_file_stats() {
+            let stat = stat?;
+            let filename = stat.filename().trim_end_matches('\0');
+
+            let is_version = match strip_prefix(filename, &prefix) {
+                Some(version) => !version.is_empty() && version.bytes().all(|b| b.is_ascii_digit()),
+                None => false,
+            };
+
+            if is_version {
+                filenames.push(filename.to_string());
+            }
+        }
+
+        Ok(filenames)
+    }
+
+    fn version_file(&self, slot: &str, version: u64) -> String {
+        format!("{}{}.{}", self.prefix, slot, version)
+    }
+
+    fn manifest_file(&self, slot: &str, sequence: u64) -> String {
+        format!("{}{}.manifest.{}", self.prefix, slot, sequence % 2)
+    }
+}
+
+impl Default for SaveSlots {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Manifest {
+    fn parse(contents: &[u8]) -> Option<Self> {
+        let contents = std::str::from_utf8(contents).ok()?;
+        let body_end = contents.trim_end_matches('\n').rfind('\n')? + 1;
+        let (body, footer) = contents.split_at(body_end);
+
+        let checksum = strip_prefix(footer.trim_end(), "checksum ")?;
+        if u32::from_str_radix(checksum, 16).ok()? != crc32(body.as_bytes()) {
+            return None;
+        }
+
+        let mut lines = body.lines();
+
+        if lines.next()? != MANIFEST_HEADER {
+            return None;
+        }
+
+        let sequence = strip_prefix(lines.next()?, "sequence ")?.parse().ok()?;
+        let mut entries = Vec::new();
+
+        for line in lines {
+            let mut fields = line.split(' ');
+
+            entries.push(Entry {
+                version: fields.next()?.parse().ok()?,
+                length: fields.next()?.parse().ok()?,
+                checksum: u32::from_str_radix(fields.next()?, 16).ok()?,
+            });
+        }
+
+        Some(Self { sequence, entries })
+    }
+}
+
+impl std::fmt::Display for Manifest {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut body = format!("{}\nsequence {}\n", MANIFEST_HEADER, self.sequence);
+
+        for entry in &self.entries {
+            writeln!(
+                body,
+                "{} {} {:08x}",
+                entry.version, entry.length, entry.checksum
+            )?;
+        }
+
+        writeln!(fmt, "{}checksum {:08x}", body, crc32(body.as_bytes()))
+    }
+}
+
+// `str::strip_prefix` is not available in our minimum supported Rust version
+fn strip_prefix<'a>(string: &'a str, prefix: &str) -> Option<&'a str> {
+    string
+        .get(..prefix.len())
+        .filter(|start| *start == prefix)?;
+    string.get(prefix.len()..)
+}
+
+fn check_slot(slot: &str) -> Result<()> {
+    if slot.is_empty() || slot.contains('\0') {
+        return Err(Error::InvalidFilename);
+    }
+
+    Ok(())
+}
+
+fn ignore_not_found(res: Result<()>) -> Result<()> {
+    match res {
+        Err(Error::NotFound) => Ok(()),
+        res => res,
+    }
+}
+
+fn read_whole<E>(discord: &Discord<'_, E>, filename: &str) -> Result<Vec<u8>> {
+    let size = discord.file_stat(filename)?.size();
+    let mut contents = vec![0; size as usize];
+
+    let read = discord.read_file(filename, &mut contents)?;
+    contents.truncate(read as usize);
+
+    Ok(contents)
+}
+
+// CRC-32 (IEEE), to detect saves that were not written whole
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0_u32;
+
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xedb8_8320 & (!(crc & 1)).wrapping_add(1));
+        }
+    }
+
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::crc32;
+    use crate::{Discord, Error, MockState, SaveSlots};
+    use std::{cell::Cell, rc::Rc};
+
+    #[test]
+    fn checksum() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn recovers_versions() {
+        let mut discord = Discord::<()>::with_mock_state(&MockState::new());
+        let mut slots = SaveSlots::new();
+        slots.versions(1);
+
+        for level in 1..=4 {
+            let version = slots
+                .save(&discord, "profile", format!("level {}", level).as_bytes())
+                .unwrap();
+
+            assert_eq!(version, level);
+        }
+
+        assert_eq!(slots.history(&discord, "profile").unwrap(), [4, 3]);
+        assert!(!discord.file_exists("profile.2").unwrap());
+        assert_eq!(slots.slots(&discord).unwrap(), ["profile"]);
+
+        // Corrupted contents and manifests fall back to the previous version
+        discord.write_file("profile.4", b"level ?").unwrap();
+        assert_eq!(slots.load(&discord, "profile").unwrap(), b"level 3");
+        assert_eq!(
+            slots.load_version(&discord, "profile", 4),
+            Err(Error::InvalidPayload)
+        );
+
+        discord
+            .write_file("profile.manifest.0", b"garbage")
+            .unwrap();
+        assert_eq!(slots.history(&discord, "profile").unwrap(), [3, 2]);
+        assert_eq!(slots.load(&discord, "profile").unwrap(), b"level 3");
+
+        // Files of interrupted saves are cleaned by the next one
+        discord.write_file("profile.9", b"half").unwrap();
+        let saved = Rc::new(Cell::new(None));
+        let saved_ = saved.clone();
+        slots.save_async(&discord, "profile", b"level 5", move |_, res| {
+            saved_.set(Some(res))
+        });
+
+        // The contents, then the manifest
+        for _ in 0..2 {
+            assert_eq!(slots.load(&discord, "profile").unwrap(), b"level 3");
+            discord.run_callbacks().unwrap();
+        }
+
+        assert_eq!(saved.get(), Some(Ok(4)));
+
+        assert_eq!(slots.load(&discord, "profile").unwrap(), b"level 5");
+        assert!(!discord.file_exists("profile.9").unwrap());
+
+        slots.delete(&discord, "profile").unwrap();
+        assert_eq!(slots.load(&discord, "profile"), Err(Error::NotFound));
+        assert_eq!(discord.file_stat_count(), 0);
+    }
+}
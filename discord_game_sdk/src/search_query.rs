@@ -1,12 +1,12 @@
-use crate::{sys, to_result::ToResult, Cast, Comparison, Distance, Result};
+use crate::{sys, to_result::ToResult, Cast, Comparison, Distance, Error, MetadataValue, Result};
 
 /// Lobby Search
 ///
 /// > [Struct in official docs](https://discordapp.com/developers/docs/game-sdk/lobbies#data-models-lobbysearchquery-struct)
 #[derive(Clone, Debug, Default)]
 pub struct SearchQuery {
-    pub(crate) filter: Option<(String, String, Comparison, Cast)>,
-    pub(crate) sort: Option<(String, String, Cast)>,
+    pub(crate) filters: Vec<(String, String, Comparison, Cast)>,
+    pub(crate) sorts: Vec<(String, String, Cast)>,
     pub(crate) limit: Option<u32>,
     pub(crate) distance: Option<Distance>,
 }
@@ -21,6 +21,11 @@ impl SearchQuery {
 
     /// Filters lobbies based on metadata comparison.
     ///
+    /// Filters add up, lobbies must match all of them.
+    /// `String` values can only be compared with `Equal` and `NotEqual`,
+    /// and `Number` values must be finite numbers, or the search fails with
+    /// [`Error::InvalidPayload`](enum.Error.html#variant.InvalidPayload).
+    ///
     /// ## Performance
     ///
     /// A nul byte will be appended to `key` and `value` if one is not present.
@@ -41,12 +46,30 @@ impl SearchQuery {
             value.push('\0')
         }
 
-        self.filter = Some((key, value, comparison, cast));
+        self.filters.push((key, value, comparison, cast));
         self
     }
 
+    /// Filters lobbies based on metadata comparison, with the cast of the value's type
+    ///
+    /// ## Performance
+    ///
+    /// A nul byte will be appended to `key` if one is not present.
+    pub fn typed_filter<V: MetadataValue>(
+        &mut self,
+        key: String,
+        comparison: Comparison,
+        value: &V,
+    ) -> &mut Self {
+        self.filter(key, comparison, value.to_metadata(), V::CAST)
+    }
+
     /// Sorts the filtered lobbies based on "near-ness" to a given value
     ///
+    /// Later sorts break the ties of earlier ones.
+    /// `Number` values must be finite numbers, or the search fails with
+    /// [`Error::InvalidPayload`](enum.Error.html#variant.InvalidPayload).
+    ///
     /// ## Performance
     ///
     /// A nul byte will be appended to `key` and `value` if one is not present.
@@ -61,10 +84,55 @@ impl SearchQuery {
             value.push('\0')
         }
 
-        self.sort = Some((key, value, cast));
+        self.sorts.push((key, value, cast));
         self
     }
 
+    /// Sorts the filtered lobbies based on "near-ness" to a given value,
+    /// with the cast of the value's type
+    ///
+    /// ## Performance
+    ///
+    /// A nul byte will be appended to `key` if one is not present.
+    pub fn typed_sort<V: MetadataValue>(&mut self, key: String, value: &V) -> &mut Self {
+        self.sort(key, value.to_metadata(), V::CAST)
+    }
+
+    /// Filters lobbies whose metadata value, as a number, compares to a given number
+    ///
+    /// ## Performance
+    ///
+    /// A nul byte will be appended to `key` if one is not present.
+    pub fn filter_number(&mut self, key: String, comparison: Comparison, value: i64) -> &mut Self {
+        self.filter(key, comparison, value.to_string(), Cast::Number)
+    }
+
+    /// Filters lobbies whose metadata value is, or is not, a given string
+    ///
+    /// `comparison` must be `Equal` or `NotEqual`, or the search fails with
+    /// [`Error::InvalidPayload`](enum.Error.html#variant.InvalidPayload).
+    ///
+    /// ## Performance
+    ///
+    /// A nul byte will be appended to `key` and `value` if one is not present.
+    pub fn filter_string(
+        &mut self,
+        key: String,
+        comparison: Comparison,
+        value: String,
+    ) -> &mut Self {
+        self.filter(key, comparison, value, Cast::String)
+    }
+
+    /// Sorts the filtered lobbies by how close their metadata value is to a given number
+    ///
+    /// ## Performance
+    ///
+    /// A nul byte will be appended to `key` if one is not present.
+    pub fn near(&mut self, key: String, value: i64) -> &mut Self {
+        self.sort(key, value.to_string(), Cast::Number)
+    }
+
     /// Limits the number of lobbies returned in a search
     ///
     /// > [Method in official docs](https://discordapp.com/developers/docs/game-sdk/lobbies#lobbysearchlimit)
@@ -82,7 +150,19 @@ impl SearchQuery {
     }
 
     pub(crate) unsafe fn process(&self, tx: *mut sys::IDiscordLobbySearchQuery) -> Result<()> {
-        if let Some((key, value, comparison, cast)) = self.filter.as_ref() {
+        for (_, value, comparison, cast) in &self.filters {
+            if !comparison.supports(*cast) || !valid_value(value, *cast) {
+                return Err(Error::InvalidPayload);
+            }
+        }
+
+        for (_, value, cast) in &self.sorts {
+            if !valid_value(value, *cast) {
+                return Err(Error::InvalidPayload);
+            }
+        }
+
+        for (key, value, comparison, cast) in &self.filters {
             (*tx).filter.unwrap()(
                 tx,
                 // XXX: *mut should be *const
@@ -95,7 +175,7 @@ impl SearchQuery {
             .into_result()?;
         }
 
-        if let Some((key, value, cast)) = self.sort.as_ref() {
+        for (key, value, cast) in &self.sorts {
             (*tx).sort.unwrap()(
                 tx,
                 // XXX: *mut should be *const
@@ -118,3 +198,101 @@ impl SearchQuery {
         Ok(())
     }
 }
+
+// Values are nul-terminated by the time they are checked
+fn valid_value(value: &str, cast: Cast) -> bool {
+    match cast {
+        Cast::Number => match value.trim_end_matches('\0').parse::<f64>() {
+            Ok(number) => number.is_finite(),
+            Err(_) => false,
+        },
+        Cast::String => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Comparison, Discord, Error, LobbyKind, MockLobby, MockState, SearchQuery};
+
+    fn lobby(id: i64, map: &str, level: &str) -> MockLobby {
+        let mut lobby = MockLobby::new(id, 1);
+        lobby
+            .with_kind(LobbyKind::Public)
+            .with_metadata("map", map)
+            .with_metadata("level", level);
+        lobby
+    }
+
+    #[test]
+    fn combines_filters() {
+        let mut discord = Discord::<()>::with_mock_state(
+            MockState::new()
+                .with_lobby(&lobby(1, "dust", "10"))
+                .with_lobby(&lobby(2, "dust", "25"))
+                .with_lobby(&lobby(3, "dust", "40"))
+                .with_lobby(&lobby(4, "nuke", "20")),
+        );
+
+        discord.lobby_search(
+            SearchQuery::new()
+                .filter_string("metadata.map".into(), Comparison::Equal, "dust".into())
+                .filter_number("metadata.level".into(), Comparison::GreaterThan, 5)
+                .filter_number("metadata.level".into(), Comparison::LessThan, 40)
+                .near("metadata.level".into(), 30),
+            |_, res| assert!(res.is_ok()),
+        );
+        discord.run_callbacks().unwrap();
+
+        assert_eq!(
+            discord
+                .iter_lobbies()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap(),
+            [2, 1]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_filters() {
+        let mut discord = Discord::<()>::with_mock_state(&MockState::new());
+
+        discord.lobby_search(
+            SearchQuery::new().typed_filter(
+                "metadata.map".into(),
+                Comparison::LessThan,
+                &"dust".to_string(),
+            ),
+            |_, res| assert_eq!(res, Err(Error::InvalidPayload)),
+        );
+
+        discord.lobby_search(
+            SearchQuery::new().filter_string(
+                "metadata.map".into(),
+                Comparison::GreaterThan,
+                "dust".into(),
+            ),
+            |_, res| assert_eq!(res, Err(Error::InvalidPayload)),
+        );
+
+        discord.lobby_search(
+            SearchQuery::new().filter(
+                "metadata.level".into(),
+                Comparison::LessThan,
+                "NaN".into(),
+                crate::Cast::Number,
+            ),
+            |_, res| assert_eq!(res, Err(Error::InvalidPayload)),
+        );
+
+        discord.lobby_search(
+            SearchQuery::new().near("metadata.level".into(), 1).sort(
+                "metadata.level".into(),
+                "high".into(),
+                crate::Cast::Number,
+            ),
+            |_, res| assert_eq!(res, Err(Error::InvalidPayload)),
+        );
+
+        discord.run_callbacks().unwrap();
+    }
+}
@@ -0,0 +1,431 @@
+use crate::{Discord, Endpoint, Error, NetworkChannelID, Reliability};
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Instant,
+};
+
+const DEFAULT_MAX_QUEUED: usize = 256;
+
+/// Settings of the outgoing message queue
+///
+/// Once given to [`enable_send_queue`](struct.Discord.html#method.enable_send_queue),
+/// messages sent with [`queue_message`](struct.Discord.html#method.queue_message) and
+/// [`queue_lobby_network_message`](struct.Discord.html#method.queue_lobby_network_message)
+/// are held until the end of [`run_callbacks`](struct.Discord.html#method.run_callbacks),
+/// where as many as the budgets allow are sent, and the network is flushed.
+///
+/// Channels opened as [`Reliable`](enum.Reliability.html#variant.Reliable) are sent first,
+/// and their messages stay queued while the SDK is rate limited or unavailable.
+/// Messages of [`Unreliable`](enum.Reliability.html#variant.Unreliable) channels are dropped
+/// when they fail to send, or when their channel holds too many messages, the oldest first.
+/// Any message is dropped when its peer, lobby or channel is gone.
+/// See [`dropped_messages`](struct.Discord.html#method.dropped_messages).
+///
+/// Budgets are token buckets: they hold up to `burst` bytes and fill at `bytes_per_second`.
+/// A message larger than `burst` is sent once its bucket is full.
+///
+/// The settings of a channel ID apply to the channels of both the networking and the lobby
+/// networking layers, which are queued separately and each have their own budget.
+/// The reliability of a channel is only known if it is opened after the queue is enabled,
+/// channels opened before are treated as reliable, so their messages are never dropped
+/// for being too many.
+///
+/// ```rust
+/// # use discord_game_sdk::*;
+/// # fn example(mut discord: Discord<'_, ()>, peer_id: NetworkPeerID) -> Result<()> {
+/// discord.enable_send_queue(
+///     SendQueue::new()
+///         .total_budget(64 * 1024, 16 * 1024)
+///         .channel_budget(1, 8 * 1024, 2 * 1024)
+///         .coalesce(1),
+/// );
+///
+/// discord.open_channel(peer_id, 0, Reliability::Reliable)?;
+/// discord.open_channel(peer_id, 1, Reliability::Unreliable)?;
+///
+/// discord.queue_message(peer_id, 0, b"chat".to_vec())?;
+///
+/// // Only the latest position is sent
+/// discord.queue_message(peer_id, 1, vec![1, 2])?;
+/// discord.queue_message(peer_id, 1, vec![3, 4])?;
+///
+/// discord.run_callbacks()?;
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SendQueue {
+    budget: Option<Budget>,
+    settings: HashMap<NetworkChannelID, ChannelSettings>,
+    channels: HashMap<(Layer, NetworkChannelID), ChannelQueue>,
+}
+
+/// The layer a channel was opened on, channel IDs of both layers are unrelated
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub(crate) enum Layer {
+    Networking,
+    LobbyNetworking,
+}
+
+#[derive(Clone, Debug)]
+struct ChannelSettings {
+    budget: Option<Budget>,
+    coalesce: bool,
+    max_queued: usize,
+}
+
+#[derive(Clone, Debug)]
+struct ChannelQueue {
+    reliability: Option<Reliability>,
+    budget: Option<Budget>,
+    coalesce: bool,
+    max_queued: usize,
+    messages: VecDeque<(Endpoint, Vec<u8>)>,
+    dropped: u64,
+}
+
+#[derive(Clone, Debug)]
+struct Budget {
+    bytes_per_second: f64,
+    burst: f64,
+    tokens: f64,
+    refilled: Option<Instant>,
+}
+
+impl SendQueue {
+    /// A queue without budgets, that only defers messages to the end of
+    /// [`run_callbacks`](struct.Discord.html#method.run_callbacks)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits the bytes sent on every channel together
+    pub fn total_budget(&mut self, bytes_per_second: u32, burst: u32) -> &mut Self {
+        self.budget = Some(Budget::new(bytes_per_second, burst));
+        self
+    }
+
+    /// Limits the bytes sent on a channel
+    pub fn channel_budget(
+        &mut self,
+        channel_id: NetworkChannelID,
+        bytes_per_second: u32,
+        burst: u32,
+    ) -> &mut Self {
+        self.settings_mut(channel_id).budget = Some(Budget::new(bytes_per_second, burst));
+        self
+    }
+
+    /// A message queued on this channel replaces the queued message to the same peer or member,
+    /// for data where only the latest value matters
+    pub fn coalesce(&mut self, channel_id: NetworkChannelID) -> &mut Self {
+        self.settings_mut(channel_id).coalesce = true;
+        self
+    }
+
+    /// How many messages an unreliable channel holds before dropping the oldest,
+    /// defaults to 256
+    pub fn max_queued(&mut self, channel_id: NetworkChannelID, max_queued: usize) -> &mut Self {
+        self.settings_mut(channel_id).max_queued = max_queued;
+        self
+    }
+
+    pub(crate) fn queued(&self, channel_id: NetworkChannelID) -> usize {
+        self.channels
+            .iter()
+            .filter(|&(&(_, id), _)| id == channel_id)
+            .map(|(_, channel)| channel.messages.len())
+            .sum()
+    }
+
+    pub(crate) fn dropped(&self, channel_id: NetworkChannelID) -> u64 {
+        self.channels
+            .iter()
+            .filter(|&(&(_, id), _)| id == channel_id)
+            .map(|(_, channel)| channel.dropped)
+            .sum()
+    }
+
+    pub(crate) fn opened(
+        &mut self,
+        layer: Layer,
+        channel_id: NetworkChannelID,
+        reliability: Reliability,
+    ) {
+        self.channel_mut(layer, channel_id).reliability = Some(reliability);
+    }
+
+    pub(crate) fn push(&mut self, endpoint: Endpoint, data: Vec<u8>) {
+        let layer = match endpoint {
+            Endpoint::Peer { .. } => Layer::Networking,
+            Endpoint::Lobby { .. } => Layer::LobbyNetworking,
+        };
+
+        let channel = self.channel_mut(layer, endpoint.channel_id());
+
+        if channel.coalesce {
+            if let Some(queued) = channel
+                .messages
+                .iter_mut()
+                .find(|(queued, _)| *queued == endpoint)
+            {
+                queued.1 = data;
+                return;
+            }
+        }
+
+        if !channel.is_reliable() && channel.messages.len() >= channel.max_queued {
+            if let Some((endpoint, _)) = channel.messages.pop_front() {
+                log::debug!("queue full, dropped message to {:?}", endpoint);
+                channel.dropped += 1;
+            }
+        }
+
+        channel.messages.push_back((endpoint, data));
+    }
+
+    pub(crate) fn flush<E>(&mut self, discord: &Discord<'_, E>) {
+        let now = Instant::now();
+
+        if let Some(budget) = self.budget.as_mut() {
+            budget.refill(now);
+        }
+
+        let mut keys = self.channels.keys().cloned().collect::<Vec<_>>();
+        keys.sort_by_key(|&(layer, channel_id)| {
+            (
+                !self.channels[&(layer, channel_id)].is_reliable(),
+                channel_id,
+                layer,
+            )
+        });
+
+        for key in keys {
+            let total = &mut self.budget;
+            let channel = self.channels.get_mut(&key).unwrap();
+
+            if let Some(budget) = channel.budget.as_mut() {
+                budget.refill(now);
+            }
+
+            while let Some(len) = channel.messages.front().map(|(_, data)| data.len()) {
+                let allowed = |budget: &Option<Budget>| match budget {
+                    Some(budget) => budget.allows(len),
+                    None => true,
+                };
+
+                if !allowed(total) || !allowed(&channel.budget) {
+                    break;
+                }
+
+                let (endpoint, data) = channel.messages.pop_front().unwrap();
+
+                match endpoint.send(discord, &data) {
+                    Ok(()) => {
+                        for budget in total.iter_mut().chain(channel.budget.iter_mut()) {
+                            budget.tokens -= len as f64;
+                        }
+                    }
+
+                    Err(Error::RateLimited)
+                    | Err(Error::ServiceUnavailable)
+                    | Err(Error::InsufficientBuffer)
+                        if channel.is_reliable() =>
+                    {
+                        channel.messages.push_front((endpoint, data));
+                        break;
+                    }
+
+                    Err(error) => {
+                        log::warn!("failed to send queued message to {:?}: {}", endpoint, error);
+                        channel.dropped += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    fn settings_mut(&mut self, channel_id: NetworkChannelID) -> &mut ChannelSettings {
+        self.settings
+            .entry(channel_id)
+            .or_insert_with(|| ChannelSettings {
+                budget: None,
+                coalesce: false,
+                max_queued: DEFAULT_MAX_QUEUED,
+            })
+    }
+
+    fn channel_mut(&mut self, layer: Layer, channel_id: NetworkChannelID) -> &mut ChannelQueue {
+        let settings = &self.settings;
+
+        self.channels.entry((layer, channel_id)).or_insert_with(|| {
+            let (budget, coalesce, max_queued) = match settings.get(&channel_id) {
+                Some(settings) => (
+                    settings.budget.clone(),
+                    settings.coalesce,
+                    settings.max_queued,
+                ),
+                None => (None, false, DEFAULT_MAX_QUEUED),
+            };
+
+            ChannelQueue {
+                reliability: None,
+                budget,
+                coalesce,
+                max_queued,
+                messages: VecDeque::new(),
+                dropped: 0,
+            }
+        })
+    }
+}
+
+impl ChannelQueue {
+    // Channels of unknown reliability are never dropped from
+    fn is_reliable(&self) -> bool {
+        self.reliability != Some(Reliability::Unreliable)
+    }
+}
+
+impl Budget {
+    fn new(bytes_per_second: u32, burst: u32) -> Self {
+        Self {
+            bytes_per_second: f64::from(bytes_per_second),
+            burst: f64::from(burst),
+            tokens: f64::from(burst),
+            refilled: None,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        if let Some(refilled) = self.refilled {
+            let elapsed = now.duration_since(refilled);
+            let elapsed = elapsed.as_secs() as f64 + f64::from(elapsed.subsec_nanos()) * 1e-9;
+
+            self.tokens = (self.tokens + elapsed * self.bytes_per_second).min(self.burst);
+        }
+
+        self.refilled = Some(now);
+    }
+
+    fn allows(&self, len: usize) -> bool {
+        self.tokens >= len as f64 || self.tokens >= self.burst
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Discord, Error, Event, MockHub, MockLobby, MockState, Reliability, SendQueue};
+
+    #[test]
+    fn budgets_and_priorities() {
+        let hub = MockHub::new();
+
+        let mut alice = hub.member(MockState::new().with_current_user(1, "alice", "0001"));
+
+        let mut bob = hub.member(MockState::new().with_current_user(2, "bob", "0002"));
+
+        // Without a queue, messages are sent right away
+        alice.open_peer(2, "mock-route-2").unwrap();
+        alice.open_channel(2, 0, Reliability::Reliable).unwrap();
+        assert_eq!(alice.queue_message(3, 0, vec![0]), Err(Error::NotFound));
+
+        alice.enable_send_queue(
+            SendQueue::new()
+                .channel_budget(1, 0, 4)
+                .max_queued(1, 2)
+                .coalesce(2),
+        );
+
+        bob.open_peer(1, "mock-route-1").unwrap();
+
+        for &(channel_id, reliability) in &[
+            (0, Reliability::Reliable),
+            (1, Reliability::Unreliable),
+            (2, Reliability::Reliable),
+        ] {
+            alice.open_channel(2, channel_id, reliability).unwrap();
+            bob.open_channel(1, channel_id, reliability).unwrap();
+        }
+
+        alice.queue_message(2, 1, vec![1; 3]).unwrap();
+        alice.queue_message(2, 1, vec![2; 3]).unwrap();
+        alice.queue_message(2, 1, vec![3; 3]).unwrap();
+        alice.queue_message(2, 2, vec![4]).unwrap();
+        alice.queue_message(2, 2, vec![5]).unwrap();
+        alice.queue_message(2, 0, vec![6]).unwrap();
+
+        assert_eq!(alice.queued_messages(1), 2);
+        assert_eq!(alice.dropped_messages(1), 1);
+        assert_eq!(alice.queued_messages(2), 1);
+
+        alice.run_callbacks().unwrap();
+        bob.run_callbacks().unwrap();
+
+        let received = bob
+            .drain_events()
+            .filter_map(|event| match event {
+                Event::NetworkMessage {
+                    channel_id, data, ..
+                } => Some((channel_id, data)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        // Reliable channels first, and the budget of channel 1 only allows one message
+        assert_eq!(received, [(0, vec![6]), (2, vec![5]), (1, vec![2; 3])]);
+        assert_eq!(alice.queued_messages(1), 1);
+
+        // Reliable messages wait for the SDK
+        alice.queue_message(2, 0, vec![7]).unwrap();
+        alice.mock_fail("networking.send_message", Error::RateLimited, 1);
+        alice.run_callbacks().unwrap();
+        assert_eq!(alice.queued_messages(0), 1);
+
+        alice.run_callbacks().unwrap();
+        assert_eq!(alice.queued_messages(0), 0);
+        assert_eq!(alice.dropped_messages(0), 0);
+
+        // Flush errors do not stop the callbacks
+        alice.mock_fail("networking.flush", Error::ServiceUnavailable, 1);
+        alice.run_callbacks().unwrap();
+
+        // Nor the other flush
+        alice.mock_fail("networking.flush", Error::ServiceUnavailable, 1);
+        alice.mock_fail("lobbies.flush_network", Error::Internal, 1);
+        assert_eq!(alice.flush_send_queue(), Err(Error::ServiceUnavailable));
+        assert_eq!(alice.flush_lobby_network(), Ok(()));
+    }
+
+    #[test]
+    fn layers() {
+        let mut discord = Discord::<()>::with_mock_state(
+            MockState::new()
+                .with_current_user(1, "alice", "0001")
+                .with_lobby(MockLobby::new(42, 1).with_member(2)),
+        );
+
+        discord.enable_send_queue(SendQueue::new().channel_budget(1, 0, 0).max_queued(1, 1));
+
+        discord.open_peer(2, "mock-route-2").unwrap();
+        discord.open_channel(2, 1, Reliability::Unreliable).unwrap();
+        discord.connect_lobby_network(42).unwrap();
+        discord
+            .open_lobby_network_channel(42, 1, Reliability::Reliable)
+            .unwrap();
+
+        for _ in 0..2 {
+            discord.queue_message(2, 1, vec![0]).unwrap();
+            discord
+                .queue_lobby_network_message(42, 2, 1, vec![0])
+                .unwrap();
+        }
+
+        // Only the unreliable peer channel drops messages
+        assert_eq!(discord.queued_messages(1), 3);
+        assert_eq!(discord.dropped_messages(1), 1);
+
+        // Each layer has its own budget
+        discord.run_callbacks().unwrap();
+        assert_eq!(discord.queued_messages(1), 1);
+    }
+}
@@ -45,3 +45,48 @@ impl std::fmt::Debug for Sku {
             .finish()
     }
 }
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Sku {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("Sku", 5)?;
+        state.serialize_field("id", &self.id())?;
+        state.serialize_field("kind", &self.kind())?;
+        state.serialize_field("name", &self.name())?;
+        state.serialize_field("price_amount", &self.price_amount())?;
+        state.serialize_field("price_currency", &self.price_currency())?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Sku {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use crate::utils::deserialize_charbuf;
+
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "Sku")]
+        struct Fields {
+            id: Snowflake,
+            kind: SkuKind,
+            name: String,
+            price_amount: u32,
+            price_currency: String,
+        }
+
+        let fields = Fields::deserialize(deserializer)?;
+        let mut raw = sys::DiscordSku {
+            id: fields.id,
+            type_: fields.kind.into(),
+            ..sys::DiscordSku::default()
+        };
+
+        deserialize_charbuf(&mut raw.name, &fields.name)?;
+        raw.price.amount = fields.price_amount;
+        deserialize_charbuf(&mut raw.price.currency, &fields.price_currency)?;
+
+        Ok(Self(raw))
+    }
+}
@@ -0,0 +1,451 @@
+use crate::{Discord, Error, Result, StorageReader};
+use std::fmt;
+
+const MAGIC: &[u8; 4] = b"DGSF";
+const FORMAT_VERSION: u8 = 1;
+const HEADER_LEN: usize = 6;
+
+const DEFLATE: u8 = 1;
+const CHACHA20POLY1305: u8 = 2;
+
+#[cfg(feature = "encryption")]
+const NONCE_LEN: usize = 12;
+
+/// Compression and authenticated encryption of the files of the
+/// [storage](struct.Discord.html#storage)
+///
+/// Files start with a header that records the format version and the codecs they were written
+/// with, so that files remain readable after the settings change. Files without the header are
+/// read as they are, like those written before a codec was used. The header is the 4 bytes
+/// `DGSF` followed by the format version, so older files that happen to start with `DGSF` are
+/// taken for encoded files, and usually rejected.
+///
+/// - Compression with DEFLATE requires the `deflate` feature, contents that decompress to more
+///   than [`max_decompressed_size`](#method.max_decompressed_size) are rejected
+/// - Encryption with ChaCha20-Poly1305 requires the `encryption` feature, the header is
+///   authenticated along with the contents. Once a key is set, files that are not encrypted are
+///   rejected, unless [`accept_unencrypted`](#method.accept_unencrypted) is set.
+///
+/// ```rust
+/// # use discord_game_sdk::*;
+/// # fn example(discord: Discord<'_, ()>) -> Result<()> {
+/// let mut codec = StorageCodec::new();
+/// # #[cfg(feature = "deflate")]
+/// codec.deflate(6);
+///
+/// codec.write_file(&discord, "world.save", b"a large world")?;
+/// assert_eq!(codec.read_file(&discord, "world.save")?, b"a large world");
+/// # Ok(()) }
+/// ```
+#[derive(Clone)]
+pub struct StorageCodec {
+    #[cfg(feature = "deflate")]
+    level: Option<u32>,
+    #[cfg(feature = "deflate")]
+    max_decompressed_size: usize,
+    #[cfg(feature = "encryption")]
+    key: Option<[u8; 32]>,
+    #[cfg(feature = "encryption")]
+    accept_unencrypted: bool,
+}
+
+// Only derivable without the `deflate` feature
+#[allow(clippy::derivable_impls)]
+impl Default for StorageCodec {
+    fn default() -> Self {
+        Self {
+            #[cfg(feature = "deflate")]
+            level: None,
+            #[cfg(feature = "deflate")]
+            max_decompressed_size: 64 * 1024 * 1024,
+            #[cfg(feature = "encryption")]
+            key: None,
+            #[cfg(feature = "encryption")]
+            accept_unencrypted: false,
+        }
+    }
+}
+
+impl StorageCodec {
+    /// A codec that neither compresses nor encrypts, but still writes the header
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compresses files with DEFLATE, at a level from 0 (none) to 9 (best)
+    ///
+    /// Requires the `deflate` feature.
+    #[cfg(feature = "deflate")]
+    pub fn deflate(&mut self, level: u32) -> &mut Self {
+        self.level = Some(level.min(9));
+        self
+    }
+
+    /// The size above which compressed contents are rejected when decoded, 64 MiB by default
+    ///
+    /// Requires the `deflate` feature.
+    #[cfg(feature = "deflate")]
+    pub fn max_decompressed_size(&mut self, max_decompressed_size: usize) -> &mut Self {
+        self.max_decompressed_size = max_decompressed_size;
+        self
+    }
+
+    /// Encrypts files with ChaCha20-Poly1305, with a secret key of the application
+    ///
+    /// Requires the `encryption` feature.
+    #[cfg(feature = "encryption")]
+    pub fn encrypt(&mut self, key: [u8; 32]) -> &mut Self {
+        self.key = Some(key);
+        self
+    }
+
+    /// Whether to read files that are not encrypted once a key is set, to migrate older saves
+    ///
+    /// Requires the `encryption` feature.
+    #[cfg(feature = "encryption")]
+    pub fn accept_unencrypted(&mut self, accept_unencrypted: bool) -> &mut Self {
+        self.accept_unencrypted = accept_unencrypted;
+        self
+    }
+
+    /// Encodes contents as they would be written
+    ///
+    /// ## Errors
+    ///
+    /// [`Error::Internal`](enum.Error.html#variant.Internal) if compression or encryption fails.
+    pub fn encode(&self, contents: &[u8]) -> Result<Vec<u8>> {
+        let flags = if self.compresses() { DEFLATE } else { 0 }
+            | if self.encrypts() { CHACHA20POLY1305 } else { 0 };
+
+        #[cfg(feature = "deflate")]
+        let contents = match self.level {
+            Some(level) => std::borrow::Cow::Owned(deflate::compress(contents, level)?),
+            None => std::borrow::Cow::Borrowed(contents),
+        };
+
+        #[cfg(feature = "deflate")]
+        let contents = &*contents;
+
+        let mut encoded = Vec::with_capacity(HEADER_LEN + contents.len());
+        encoded.extend_from_slice(MAGIC);
+        encoded.push(FORMAT_VERSION);
+        encoded.push(flags);
+
+        #[cfg(feature = "encryption")]
+        {
+            if let Some(ref key) = self.key {
+                let sealed = encryption::seal(key, &encoded, contents)?;
+                encoded.extend_from_slice(&sealed);
+                return Ok(encoded);
+            }
+        }
+
+        encoded.extend_from_slice(contents);
+        Ok(encoded)
+    }
+
+    /// Decodes contents as they were written
+    ///
+    /// ## Errors
+    ///
+    /// [`Error::InvalidPayload`](enum.Error.html#variant.InvalidPayload) if the contents are
+    /// corrupted, were tampered with, use a codec whose feature is disabled or a newer format,
+    /// decompress to more than [`max_decompressed_size`](#method.max_decompressed_size),
+    /// or are not encrypted while they should be.
+    pub fn decode(&self, stored: &[u8]) -> Result<Vec<u8>> {
+        if stored.len() < HEADER_LEN || &stored[..MAGIC.len()] != MAGIC {
+            return self.unencrypted(stored.to_vec());
+        }
+
+        let (header, contents) = stored.split_at(HEADER_LEN);
+        let (version, flags) = (header[4], header[5]);
+
+        if version != FORMAT_VERSION || flags & !(DEFLATE | CHACHA20POLY1305) != 0 {
+            log::warn!(
+                "unsupported storage format {} with codecs {:#x}",
+                version,
+                flags
+            );
+            return Err(Error::InvalidPayload);
+        }
+
+        let contents = if flags & CHACHA20POLY1305 != 0 {
+            self.open(header, contents)?
+        } else {
+            self.unencrypted(contents.to_vec())?
+        };
+
+        if flags & DEFLATE != 0 {
+            return self.inflate(&contents);
+        }
+
+        Ok(contents)
+    }
+
+    /// Writes a file with [`write_file`](struct.Discord.html#method.write_file),
+    /// see [`encode`](#method.encode)
+    pub fn write_file<E>(
+        &self,
+        discord: &Discord<'_, E>,
+        filename: &str,
+        contents: &[u8],
+    ) -> Result<()> {
+        discord.write_file(filename, self.encode(contents)?)
+    }
+
+    /// Writes a file with [`write_file_async`](struct.Discord.html#method.write_file_async),
+    /// see [`encode`](#method.encode)
+    pub fn write_file_async<'d, E>(
+        &self,
+        discord: &Discord<'d, E>,
+        filename: &str,
+        contents: &[u8],
+        callback: impl 'd + FnOnce(&Discord<'d, E>, Result<()>),
+    ) {
+        match self.encode(contents) {
+            Ok(encoded) => discord.write_file_async(filename, encoded, callback),
+            Err(error) => callback(discord, Err(error)),
+        }
+    }
+
+    /// Reads a whole file with a [`StorageReader`](struct.StorageReader.html),
+    /// see [`decode`](#method.decode)
+    pub fn read_file<E>(&self, discord: &Discord<'_, E>, filename: &str) -> Result<Vec<u8>> {
+        self.decode(&StorageReader::open(discord, filename)?.into_inner())
+    }
+
+    fn compresses(&self) -> bool {
+        #[cfg(feature = "deflate")]
+        {
+            self.level.is_some()
+        }
+
+        #[cfg(not(feature = "deflate"))]
+        {
+            false
+        }
+    }
+
+    fn encrypts(&self) -> bool {
+        #[cfg(feature = "encryption")]
+        {
+            self.key.is_some()
+        }
+
+        #[cfg(not(feature = "encryption"))]
+        {
+            false
+        }
+    }
+
+    fn unencrypted(&self, contents: Vec<u8>) -> Result<Vec<u8>> {
+        #[cfg(feature = "encryption")]
+        {
+            if self.encrypts() && !self.accept_unencrypted {
+                log::warn!("rejected storage contents that are not encrypted");
+                return Err(Error::InvalidPayload);
+            }
+        }
+
+        Ok(contents)
+    }
+
+    #[cfg(feature = "encryption")]
+    fn open(&self, header: &[u8], contents: &[u8]) -> Result<Vec<u8>> {
+        match self.key {
+            Some(ref key) => encryption::open(key, header, contents),
+            None => {
+                log::warn!("storage contents are encrypted, but no key was set");
+                Err(Error::InvalidPayload)
+            }
+        }
+    }
+
+    #[cfg(not(feature = "encryption"))]
+    fn open(&self, _: &[u8], _: &[u8]) -> Result<Vec<u8>> {
+        log::warn!("storage contents are encrypted, but the `encryption` feature is disabled");
+        Err(Error::InvalidPayload)
+    }
+
+    #[cfg(feature = "deflate")]
+    fn inflate(&self, contents: &[u8]) -> Result<Vec<u8>> {
+        deflate::decompress(contents, self.max_decompressed_size)
+    }
+
+    #[cfg(not(feature = "deflate"))]
+    fn inflate(&self, _: &[u8]) -> Result<Vec<u8>> {
+        log::warn!("storage contents are compressed, but the `deflate` feature is disabled");
+        Err(Error::InvalidPayload)
+    }
+}
+
+impl fmt::Debug for StorageCodec {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The key stays out of logs
+        fmt.debug_struct("StorageCodec")
+            .field("compresses", &self.compresses())
+            .field("encrypts", &self.encrypts())
+            .finish()
+    }
+}
+
+#[cfg(feature = "deflate")]
+mod deflate {
+    use crate::{Error, Result};
+    use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression};
+    use std::io::{Read, Write};
+
+    pub(super) fn compress(contents: &[u8], level: u32) -> Result<Vec<u8>> {
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::new(level));
+
+        encoder
+            .write_all(contents)
+            .and_then(|_| encoder.finish())
+            .map_err(|error| {
+                log::warn!("failed to compress storage contents: {}", error);
+                Error::Internal
+            })
+    }
+
+    pub(super) fn decompress(contents: &[u8], max_size: usize) -> Result<Vec<u8>> {
+        let limit = max_size as u64;
+        let mut decompressed = Vec::new();
+
+        DeflateDecoder::new(contents)
+            .take(limit.saturating_add(1))
+            .read_to_end(&mut decompressed)
+            .map_err(|error| {
+                log::warn!("failed to decompress storage contents: {}", error);
+                Error::InvalidPayload
+            })?;
+
+        if decompressed.len() as u64 > limit {
+            log::warn!("storage contents decompress to more than {} bytes", limit);
+            return Err(Error::InvalidPayload);
+        }
+
+        Ok(decompressed)
+    }
+}
+
+#[cfg(feature = "encryption")]
+mod encryption {
+    use super::NONCE_LEN;
+    use crate::{Error, Result};
+    use chacha20poly1305::{
+        aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+        ChaCha20Poly1305, Key, Nonce,
+    };
+
+    // The nonce, followed by the ciphertext and its tag
+    pub(super) fn seal(key: &[u8; 32], header: &[u8], contents: &[u8]) -> Result<Vec<u8>> {
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(key));
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+        let sealed = cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: contents,
+                    aad: header,
+                },
+            )
+            .map_err(|_| Error::Internal)?;
+
+        let mut encrypted = nonce.to_vec();
+        encrypted.extend_from_slice(&sealed);
+        Ok(encrypted)
+    }
+
+    pub(super) fn open(key: &[u8; 32], header: &[u8], encrypted: &[u8]) -> Result<Vec<u8>> {
+        if encrypted.len() < NONCE_LEN {
+            return Err(Error::InvalidPayload);
+        }
+
+        let (nonce, sealed) = encrypted.split_at(NONCE_LEN);
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(key));
+
+        cipher
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: sealed,
+                    aad: header,
+                },
+            )
+            .map_err(|_| {
+                log::warn!("failed to decrypt storage contents");
+                Error::InvalidPayload
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Discord, Error, MockState, StorageCodec};
+
+    #[test]
+    fn headers() {
+        let discord = Discord::<()>::with_mock_state(&MockState::new());
+        let codec = StorageCodec::new();
+
+        codec.write_file(&discord, "plain", b"contents").unwrap();
+        assert_eq!(codec.read_file(&discord, "plain").unwrap(), b"contents");
+
+        // Files written without a codec
+        discord.write_file("legacy", b"contents").unwrap();
+        assert_eq!(codec.read_file(&discord, "legacy").unwrap(), b"contents");
+
+        let mut newer = codec.encode(b"contents").unwrap();
+        newer[4] += 1;
+        assert_eq!(codec.decode(&newer), Err(Error::InvalidPayload));
+    }
+
+    #[cfg(feature = "deflate")]
+    #[test]
+    fn deflate() {
+        let contents = vec![7; 4096];
+        let encoded = StorageCodec::new().deflate(9).encode(&contents).unwrap();
+
+        assert!(encoded.len() < 100);
+        assert_eq!(StorageCodec::new().decode(&encoded), Ok(contents));
+
+        assert_eq!(
+            StorageCodec::new()
+                .max_decompressed_size(4095)
+                .decode(&encoded),
+            Err(Error::InvalidPayload)
+        );
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn encryption() {
+        let mut codec = StorageCodec::new();
+        codec.encrypt([1; 32]);
+
+        let encoded = codec.encode(b"secret").unwrap();
+        assert!(!encoded.windows(6).any(|window| window == b"secret"));
+        assert_eq!(codec.decode(&encoded), Ok(b"secret".to_vec()));
+
+        let mut tampered = encoded.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert_eq!(codec.decode(&tampered), Err(Error::InvalidPayload));
+
+        assert_eq!(
+            StorageCodec::new().encrypt([2; 32]).decode(&encoded),
+            Err(Error::InvalidPayload)
+        );
+        assert_eq!(
+            StorageCodec::new().decode(&encoded),
+            Err(Error::InvalidPayload)
+        );
+
+        let plain = StorageCodec::new().encode(b"older").unwrap();
+        assert_eq!(codec.decode(&plain), Err(Error::InvalidPayload));
+        assert_eq!(
+            codec.accept_unencrypted(true).decode(&plain),
+            Ok(b"older".to_vec())
+        );
+    }
+}
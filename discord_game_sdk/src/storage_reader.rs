@@ -0,0 +1,290 @@
+use crate::{Discord, Error, Result};
+use std::{
+    convert::TryFrom,
+    io::{self, BufRead, Cursor, Read, Seek, SeekFrom},
+};
+
+// Length of the reads of `open_async`
+const CHUNK_SIZE: u64 = 16 * 1024 * 1024;
+
+/// A `Read`, `BufRead` and `Seek` adapter over a file of the
+/// [storage](struct.Discord.html#storage)
+///
+/// The file is read whole when it is opened, and held in memory: the SDK only reads from an
+/// offset asynchronously, with callbacks that only run during
+/// [`run_callbacks`](struct.Discord.html#method.run_callbacks), so reads cannot wait on it.
+///
+/// [`open`](#method.open) reads the file at once, in a buffer sized with
+/// [`file_stat`](struct.Discord.html#method.file_stat), which the SDK limits to
+/// `u32::MAX` bytes. [`open_async`](#method.open_async) reads larger files, in chunks.
+///
+/// ```rust
+/// # use discord_game_sdk::*;
+/// # use std::io::BufRead;
+/// # fn example(discord: Discord<'_, ()>) -> std::result::Result<(), Box<dyn std::error::Error>> {
+/// let reader = StorageReader::open(&discord, "settings.txt")?;
+///
+/// for line in reader.lines() {
+///     println!("{}", line?);
+/// }
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Debug)]
+pub struct StorageReader {
+    filename: String,
+    contents: Cursor<Vec<u8>>,
+}
+
+impl StorageReader {
+    /// Reads a file, to be read from its start
+    ///
+    /// ## Errors
+    ///
+    /// [`Error::InvalidFileSize`](enum.Error.html#variant.InvalidFileSize) if the file is
+    /// larger than [`read_file`](struct.Discord.html#method.read_file) can read, or the errors
+    /// of [`file_stat`](struct.Discord.html#method.file_stat) and
+    /// [`read_file`](struct.Discord.html#method.read_file).
+    pub fn open<E>(discord: &Discord<'_, E>, filename: impl Into<String>) -> Result<Self> {
+        let mut filename = filename.into();
+
+        if !filename.ends_with('\0') {
+            filename.push('\0');
+        }
+
+        let size = discord.file_stat(filename.as_str())?.size();
+
+        if u32::try_from(size).is_err() {
+            return Err(Error::InvalidFileSize);
+        }
+
+        let mut contents = vec![0; size as usize];
+        let read = discord.read_file(filename.as_str(), &mut contents)?;
+        contents.truncate(read as usize);
+
+        let _ = filename.pop();
+
+        Ok(Self {
+            filename,
+            contents: Cursor::new(contents),
+        })
+    }
+
+    /// Reads a file in chunks with
+    /// [`read_file_async_partial`](struct.Discord.html#method.read_file_async_partial),
+    /// to be read from its start
+    ///
+    /// ## Errors
+    ///
+    /// [`Error::InvalidFileSize`](enum.Error.html#variant.InvalidFileSize) if the file does
+    /// not fit in memory, or the errors of
+    /// [`file_stat`](struct.Discord.html#method.file_stat) and
+    /// [`read_file_async_partial`](struct.Discord.html#method.read_file_async_partial).
+    pub fn open_async<'d, E>(
+        discord: &Discord<'d, E>,
+        filename: impl Into<String>,
+        callback: impl 'd + FnOnce(&Discord<'d, E>, Result<Self>),
+    ) {
+        Self::open_async_with_chunk_size(discord, filename, CHUNK_SIZE, callback)
+    }
+
+    fn open_async_with_chunk_size<'d, E>(
+        discord: &Discord<'d, E>,
+        filename: impl Into<String>,
+        chunk_size: u64,
+        callback: impl 'd + FnOnce(&Discord<'d, E>, Result<Self>),
+    ) {
+        let mut filename = filename.into();
+
+        if filename.ends_with('\0') {
+            let _ = filename.pop();
+        }
+
+        let size = match discord.file_stat(filename.as_str()) {
+            Ok(stat) => stat.size(),
+            Err(error) => return callback(discord, Err(error)),
+        };
+
+        let contents = match usize::try_from(size) {
+            Ok(size) => Vec::with_capacity(size),
+            Err(_) => return callback(discord, Err(Error::InvalidFileSize)),
+        };
+
+        Self::read_chunk(discord, filename, contents, size, chunk_size, callback);
+    }
+
+    fn read_chunk<'d, E>(
+        discord: &Discord<'d, E>,
+        filename: String,
+        mut contents: Vec<u8>,
+        size: u64,
+        chunk_size: u64,
+        callback: impl 'd + FnOnce(&Discord<'d, E>, Result<Self>),
+    ) {
+        let offset = contents.len() as u64;
+        let length = (size - offset).min(chunk_size);
+
+        discord.read_file_async_partial(filename.clone(), offset, length, move |discord, res| {
+            match res {
+                Ok(chunk) => {
+                    contents.extend_from_slice(chunk);
+
+                    // The file may have shrunk since it was stat'd
+                    if chunk.is_empty() || contents.len() as u64 >= size {
+                        callback(
+                            discord,
+                            Ok(Self {
+                                filename,
+                                contents: Cursor::new(contents),
+                            }),
+                        );
+                    } else {
+                        Self::read_chunk(discord, filename, contents, size, chunk_size, callback);
+                    }
+                }
+
+                Err(error) => callback(discord, Err(error)),
+            }
+        });
+    }
+
+    /// The name of the file
+    pub fn filename(&self) -> &str {
+        &self.filename
+    }
+
+    /// The size of the file
+    pub fn len(&self) -> u64 {
+        self.contents.get_ref().len() as u64
+    }
+
+    /// Whether the file is empty
+    pub fn is_empty(&self) -> bool {
+        self.contents.get_ref().is_empty()
+    }
+
+    /// The contents of the file
+    pub fn into_inner(self) -> Vec<u8> {
+        self.contents.into_inner()
+    }
+}
+
+impl Read for StorageReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.contents.read(buf)
+    }
+}
+
+impl BufRead for StorageReader {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.contents.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.contents.consume(amt)
+    }
+}
+
+impl Seek for StorageReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.contents.seek(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Discord, Error, MockState, StorageReader, StorageWriter};
+    use std::{
+        cell::RefCell,
+        io::{BufRead, Read, Seek, SeekFrom, Write},
+        rc::Rc,
+    };
+
+    #[test]
+    fn round_trip() {
+        let discord = Discord::<()>::with_mock_state(&MockState::new());
+
+        {
+            let mut writer = StorageWriter::create(&discord, "notes.txt");
+            writeln!(writer, "first").unwrap();
+            writer.flush().unwrap();
+            writeln!(writer, "second").unwrap();
+        }
+
+        let mut reader = StorageReader::open(&discord, "notes.txt").unwrap();
+        assert_eq!(reader.filename(), "notes.txt");
+        assert_eq!(reader.len(), 13);
+
+        let lines = (&mut reader)
+            .lines()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(lines, ["first", "second"]);
+
+        let mut second = String::new();
+        reader.seek(SeekFrom::End(-7)).unwrap();
+        reader.read_to_string(&mut second).unwrap();
+        assert_eq!(second, "second\n");
+
+        let mut writer = StorageWriter::create(&discord, "notes.txt");
+        writer.flush().unwrap();
+        drop(writer);
+        assert_eq!(discord.file_stat("notes.txt").unwrap().size(), 13);
+
+        StorageWriter::create(&discord, "notes.txt")
+            .finish()
+            .unwrap();
+        assert!(StorageReader::open(&discord, "notes.txt")
+            .unwrap()
+            .is_empty());
+
+        let missing = StorageReader::open(&discord, "missing.txt").unwrap_err();
+        assert_eq!(
+            std::io::Error::from(missing).kind(),
+            std::io::ErrorKind::NotFound
+        );
+    }
+
+    #[test]
+    fn reads_in_chunks() {
+        let mut discord = Discord::<()>::with_mock_state(&MockState::new());
+        discord.write_file("notes.txt", b"first\nsecond\n").unwrap();
+
+        let result = Rc::new(RefCell::new(None));
+
+        for &filename in &["notes.txt", "missing.txt"] {
+            let result = result.clone();
+
+            StorageReader::open_async_with_chunk_size(&discord, filename, 4, move |_, res| {
+                *result.borrow_mut() = Some(res.map(StorageReader::into_inner))
+            });
+        }
+
+        assert_eq!(result.borrow_mut().take(), Some(Err(Error::NotFound)));
+
+        // One chunk of 4 bytes per run
+        for _ in 0..3 {
+            discord.run_callbacks().unwrap();
+        }
+
+        assert!(result.borrow().is_none());
+        discord.run_callbacks().unwrap();
+
+        assert_eq!(
+            result.borrow_mut().take(),
+            Some(Ok(b"first\nsecond\n".to_vec()))
+        );
+
+        // In a single chunk
+        let whole = result.clone();
+        StorageReader::open_async(&discord, "notes.txt", move |_, res| {
+            *whole.borrow_mut() = Some(res.map(StorageReader::into_inner))
+        });
+
+        discord.run_callbacks().unwrap();
+
+        assert_eq!(
+            result.borrow_mut().take(),
+            Some(Ok(b"first\nsecond\n".to_vec()))
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{Discord, Error, Result};
+use std::{
+    convert::TryFrom,
+    fmt,
+    io::{self, Write},
+};
+
+/// A `Write` adapter over a file of the [storage](struct.Discord.html#storage)
+///
+/// The SDK only writes whole files, so the contents are buffered and the file is replaced
+/// on [`finish`](#method.finish), or on `flush` and when the writer is dropped after a write.
+/// Errors are only logged on drop.
+///
+/// ```rust
+/// # use discord_game_sdk::*;
+/// # use std::io::Write;
+/// # fn example(discord: Discord<'_, ()>) -> std::result::Result<(), Box<dyn std::error::Error>> {
+/// let mut writer = StorageWriter::create(&discord, "settings.txt");
+/// writeln!(writer, "volume = {}", 0.8)?;
+/// writer.finish()?;
+/// # Ok(()) }
+/// ```
+pub struct StorageWriter<'a, 'd, E> {
+    discord: &'a Discord<'d, E>,
+    filename: String,
+    contents: Vec<u8>,
+    dirty: bool,
+}
+
+impl<'a, 'd, E> StorageWriter<'a, 'd, E> {
+    /// A writer that replaces a file, which is left as is until the first flush
+    pub fn create(discord: &'a Discord<'d, E>, filename: impl Into<String>) -> Self {
+        Self {
+            discord,
+            filename: filename.into(),
+            contents: Vec::new(),
+            dirty: false,
+        }
+    }
+
+    /// The name of the file
+    pub fn filename(&self) -> &str {
+        &self.filename
+    }
+
+    /// The contents written so far
+    pub fn contents(&self) -> &[u8] {
+        &self.contents
+    }
+
+    /// Writes the file, and consumes the writer
+    ///
+    /// ## Errors
+    ///
+    /// The errors of [`write_file`](struct.Discord.html#method.write_file).
+    pub fn finish(mut self) -> Result<()> {
+        self.write_file()
+    }
+
+    /// Writes the file with [`write_file_async`](struct.Discord.html#method.write_file_async),
+    /// and consumes the writer
+    pub fn finish_async(mut self, callback: impl 'd + FnOnce(&Discord<'d, E>, Result<()>)) {
+        self.dirty = false;
+
+        self.discord
+            .write_file_async(self.filename.as_str(), &self.contents, callback);
+    }
+
+    fn write_file(&mut self) -> Result<()> {
+        self.discord
+            .write_file(self.filename.as_str(), &self.contents)?;
+
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+impl<E> Write for StorageWriter<'_, '_, E> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // Files are limited to what `write_file` can write
+        if u32::try_from(self.contents.len() + buf.len()).is_err() {
+            return Err(Error::InvalidFileSize.into());
+        }
+
+        self.contents.extend_from_slice(buf);
+        self.dirty = true;
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.dirty {
+            self.write_file()?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<E> Drop for StorageWriter<'_, '_, E> {
+    fn drop(&mut self) {
+        if self.dirty {
+            if let Err(error) = self.write_file() {
+                log::warn!("failed to write {}: {}", self.filename, error);
+            }
+        }
+    }
+}
+
+impl<E> fmt::Debug for StorageWriter<'_, '_, E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("StorageWriter")
+            .field("filename", &self.filename)
+            .field("len", &self.contents.len())
+            .field("dirty", &self.dirty)
+            .finish()
+    }
+}
@@ -0,0 +1,212 @@
+use crate::{
+    Decode, Discord, Encode, Endpoint, Event, LobbyID, NetworkChannelID, NetworkPeerID,
+    Reliability, Result, UserID,
+};
+use std::{fmt, marker::PhantomData};
+
+/// A networking channel that carries values of one type
+///
+/// Keeps the channel ID and reliability together, encodes the values sent with
+/// [`Encode`](trait.Encode.html) and decodes the values received with
+/// [`Decode`](trait.Decode.html). The same channel works with peers of the
+/// [networking layer](struct.Discord.html#networking) and members of the
+/// [lobby networking layer](struct.Discord.html#lobbies).
+///
+/// ```rust
+/// # use discord_game_sdk::*;
+/// # fn example(mut discord: Discord<'_, EventQueue>, peer_id: NetworkPeerID) -> Result<()> {
+/// let chat = TypedChannel::<String>::new(1, Reliability::Reliable);
+/// chat.open(&discord, peer_id)?;
+/// chat.send(&discord, peer_id, &"hello".to_string())?;
+///
+/// discord.run_callbacks()?;
+///
+/// for event in discord.drain_events() {
+///     if let Some(Ok((from, text))) = chat.receive(&event) {
+///         println!("{:?} says {}", from, text);
+///     }
+/// }
+/// # Ok(()) }
+/// ```
+pub struct TypedChannel<T> {
+    channel_id: NetworkChannelID,
+    reliability: Reliability,
+    values: PhantomData<fn(T) -> T>,
+}
+
+impl<T> TypedChannel<T> {
+    /// A channel with a given ID and reliability, which must be the same on every end
+    pub fn new(channel_id: NetworkChannelID, reliability: Reliability) -> Self {
+        Self {
+            channel_id,
+            reliability,
+            values: PhantomData,
+        }
+    }
+
+    /// The ID of the channel
+    pub fn channel_id(&self) -> NetworkChannelID {
+        self.channel_id
+    }
+
+    /// The reliability of the channel
+    pub fn reliability(&self) -> Reliability {
+        self.reliability
+    }
+
+    /// Opens the channel to a peer with [`open_channel`](struct.Discord.html#method.open_channel)
+    pub fn open<E>(&self, discord: &Discord<'_, E>, peer_id: NetworkPeerID) -> Result<()> {
+        discord.open_channel(peer_id, self.channel_id, self.reliability)
+    }
+
+    /// Closes the channel to a peer with [`close_channel`](struct.Discord.html#method.close_channel)
+    pub fn close<E>(&self, discord: &Discord<'_, E>, peer_id: NetworkPeerID) -> Result<()> {
+        discord.close_channel(peer_id, self.channel_id)
+    }
+
+    /// Opens the channel in a lobby with
+    /// [`open_lobby_network_channel`](struct.Discord.html#method.open_lobby_network_channel)
+    pub fn open_lobby<E>(&self, discord: &Discord<'_, E>, lobby_id: LobbyID) -> Result<()> {
+        discord.open_lobby_network_channel(lobby_id, self.channel_id, self.reliability)
+    }
+
+    /// The endpoint of a peer on this channel
+    pub fn peer(&self, peer_id: NetworkPeerID) -> Endpoint {
+        Endpoint::Peer {
+            peer_id,
+            channel_id: self.channel_id,
+        }
+    }
+
+    /// The endpoint of a lobby member on this channel
+    pub fn member(&self, lobby_id: LobbyID, member_id: UserID) -> Endpoint {
+        Endpoint::Lobby {
+            lobby_id,
+            member_id,
+            channel_id: self.channel_id,
+        }
+    }
+}
+
+impl<T: Encode> TypedChannel<T> {
+    /// Sends a value to a peer
+    ///
+    /// ## Errors
+    ///
+    /// The error of [`Encode::encode`](trait.Encode.html#tymethod.encode), or of
+    /// [`send_message`](struct.Discord.html#method.send_message).
+    pub fn send<E>(
+        &self,
+        discord: &Discord<'_, E>,
+        peer_id: NetworkPeerID,
+        value: &T,
+    ) -> Result<()> {
+        self.peer(peer_id).send(discord, &value.encode()?)
+    }
+
+    /// Sends a value to a lobby member
+    ///
+    /// ## Errors
+    ///
+    /// The error of [`Encode::encode`](trait.Encode.html#tymethod.encode), or of
+    /// [`send_lobby_network_message`](struct.Discord.html#method.send_lobby_network_message).
+    pub fn send_lobby<E>(
+        &self,
+        discord: &Discord<'_, E>,
+        lobby_id: LobbyID,
+        member_id: UserID,
+        value: &T,
+    ) -> Result<()> {
+        self.member(lobby_id, member_id)
+            .send(discord, &value.encode()?)
+    }
+}
+
+impl<T: Decode> TypedChannel<T> {
+    /// Decodes the value of a networking event on this channel, with where it comes from
+    ///
+    /// `None` for other channels and other events.
+    pub fn receive(&self, event: &Event) -> Option<Result<(Endpoint, T)>> {
+        let (endpoint, data) = Endpoint::from_event(event)?;
+
+        if endpoint.channel_id() != self.channel_id {
+            return None;
+        }
+
+        Some(T::decode(data).map(|value| (endpoint, value)))
+    }
+}
+
+impl<T> Clone for TypedChannel<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.channel_id, self.reliability)
+    }
+}
+
+impl<T> fmt::Debug for TypedChannel<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("TypedChannel")
+            .field("channel_id", &self.channel_id)
+            .field("reliability", &self.reliability)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Endpoint, Error, MockHub, MockLobby, MockState, Reliability, TypedChannel};
+
+    #[test]
+    fn lobby_round_trip() {
+        let hub = MockHub::new();
+        let chat = TypedChannel::<String>::new(3, Reliability::Reliable);
+        let raw = TypedChannel::<Vec<u8>>::new(4, Reliability::Unreliable);
+
+        let alice = hub.member(
+            MockState::new()
+                .with_current_user(1, "alice", "0001")
+                .with_lobby(MockLobby::new(42, 1).with_member(2)),
+        );
+
+        let mut bob = hub.member(MockState::new().with_current_user(2, "bob", "0002"));
+
+        alice.connect_lobby_network(42).unwrap();
+        chat.open_lobby(&alice, 42).unwrap();
+        raw.open_lobby(&alice, 42).unwrap();
+
+        bob.connect_lobby_network(42).unwrap();
+        chat.open_lobby(&bob, 42).unwrap();
+        raw.open_lobby(&bob, 42).unwrap();
+
+        chat.send_lobby(&alice, 42, 2, &"hello".to_string())
+            .unwrap();
+        raw.send_lobby(&alice, 42, 2, &vec![0xff]).unwrap();
+
+        bob.run_callbacks().unwrap();
+
+        let events = bob.drain_events().collect::<Vec<_>>();
+        let texts = events
+            .iter()
+            .filter_map(|event| chat.receive(event))
+            .collect::<Vec<_>>();
+
+        assert_eq!(texts, [Ok((chat.member(42, 1), "hello".to_string()))]);
+
+        // Bytes that are not UTF-8 cannot be read as a String
+        let reinterpreted = TypedChannel::<String>::new(4, Reliability::Unreliable);
+        let raws = events
+            .iter()
+            .filter_map(|event| reinterpreted.receive(event))
+            .collect::<Vec<_>>();
+
+        assert_eq!(raws, [Err(Error::InvalidPayload)]);
+        assert_eq!(
+            raw.member(42, 1),
+            Endpoint::Lobby {
+                lobby_id: 42,
+                member_id: 1,
+                channel_id: 4
+            }
+        );
+    }
+}
@@ -50,3 +50,76 @@ impl std::fmt::Debug for User {
             .finish()
     }
 }
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for User {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("User", 5)?;
+        state.serialize_field("id", &self.id())?;
+        state.serialize_field("username", &self.username())?;
+        state.serialize_field("discriminator", &self.discriminator())?;
+        state.serialize_field("avatar", &self.avatar())?;
+        state.serialize_field("is_bot", &self.is_bot())?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for User {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use crate::utils::deserialize_charbuf;
+
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "User")]
+        struct Fields {
+            id: UserID,
+            username: String,
+            discriminator: String,
+            avatar: String,
+            is_bot: bool,
+        }
+
+        let fields = Fields::deserialize(deserializer)?;
+        let mut raw = sys::DiscordUser {
+            id: fields.id,
+            bot: fields.is_bot,
+            ..sys::DiscordUser::default()
+        };
+
+        deserialize_charbuf(&mut raw.username, &fields.username)?;
+        deserialize_charbuf(&mut raw.discriminator, &fields.discriminator)?;
+        deserialize_charbuf(&mut raw.avatar, &fields.avatar)?;
+
+        Ok(Self(raw))
+    }
+}
+
+#[cfg(all(test, feature = "bincode"))]
+mod tests {
+    use crate::{sys, utils::write_charbuf, Bincode, Decode, Encode, Error, User};
+
+    #[test]
+    fn serde() {
+        let mut user = User(sys::DiscordUser {
+            id: 42,
+            bot: true,
+            ..sys::DiscordUser::default()
+        });
+        write_charbuf(&mut user.0.username, "friend");
+        write_charbuf(&mut user.0.discriminator, "0042");
+        write_charbuf(&mut user.0.avatar, "avatar");
+
+        let bytes = Bincode(user.clone()).encode().unwrap();
+        assert_eq!(Bincode::decode(&bytes), Ok(Bincode(user)));
+
+        // Same layout as `User`
+        let too_long = Bincode((42_i64, "x".repeat(512), "0042", "", false));
+        let nul = Bincode((42_i64, "friend", "00\x0042", "", false));
+
+        for fields in &[too_long.encode().unwrap(), nul.encode().unwrap()] {
+            assert_eq!(Bincode::<User>::decode(fields), Err(Error::InvalidPayload));
+        }
+    }
+}
@@ -41,3 +41,45 @@ impl std::fmt::Debug for UserAchievement {
             .finish()
     }
 }
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for UserAchievement {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("UserAchievement", 4)?;
+        state.serialize_field("user_id", &self.user_id())?;
+        state.serialize_field("achievement_id", &self.achievement_id())?;
+        state.serialize_field("percent_complete", &self.percent_complete())?;
+        state.serialize_field("unlocked_at", &self.unlocked_at())?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for UserAchievement {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use crate::utils::deserialize_charbuf;
+
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "UserAchievement")]
+        struct Fields {
+            user_id: UserID,
+            achievement_id: Snowflake,
+            percent_complete: u8,
+            unlocked_at: String,
+        }
+
+        let fields = Fields::deserialize(deserializer)?;
+        let mut raw = sys::DiscordUserAchievement {
+            user_id: fields.user_id,
+            achievement_id: fields.achievement_id,
+            percent_complete: fields.percent_complete,
+            ..sys::DiscordUserAchievement::default()
+        };
+
+        deserialize_charbuf(&mut raw.unlocked_at, &fields.unlocked_at)?;
+
+        Ok(Self(raw))
+    }
+}
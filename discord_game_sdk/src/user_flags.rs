@@ -17,3 +17,17 @@ bitflags::bitflags! {
         const HYPE_SQUAD_HOUSE_3 = sys::DiscordUserFlag_HypeSquadHouse3;
     }
 }
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for UserFlags {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.bits().serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for UserFlags {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        sys::EDiscordUserFlag::deserialize(deserializer).map(Self::from_bits_truncate)
+    }
+}
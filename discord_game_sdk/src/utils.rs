@@ -38,7 +38,7 @@ pub(crate) fn charbuf_to_str(charbuf: &[u8]) -> &str {
 }
 
 pub(crate) fn charbuf_len(charbuf: &[u8]) -> usize {
-    memchr::memchr(0, charbuf).unwrap_or_else(|| charbuf.len())
+    memchr::memchr(0, charbuf).unwrap_or(charbuf.len())
 }
 
 pub(crate) fn write_charbuf(charbuf: &mut [u8], value: &str) {
@@ -54,6 +54,30 @@ pub(crate) fn write_charbuf(charbuf: &mut [u8], value: &str) {
     }
 }
 
+/// `write_charbuf` for deserialization, rejects values that would be cut
+#[cfg(feature = "serde")]
+pub(crate) fn deserialize_charbuf<E: serde::de::Error>(
+    charbuf: &mut [u8],
+    value: &str,
+) -> Result<(), E> {
+    if value.len() > charbuf.len() {
+        return Err(E::invalid_length(
+            value.len(),
+            &format!("at most {} bytes", charbuf.len()).as_str(),
+        ));
+    }
+
+    if value.contains('\0') {
+        return Err(E::invalid_value(
+            serde::de::Unexpected::Str(value),
+            &"a string without nul bytes",
+        ));
+    }
+
+    write_charbuf(charbuf, value);
+    Ok(())
+}
+
 pub(crate) unsafe fn charptr_to_str<'a>(ptr: *const u8) -> &'a str {
     let bytes = std::ffi::CStr::from_ptr(ptr as *const i8).to_bytes();
 
@@ -82,6 +106,20 @@ mod tests {
         run_test("65 characters 65 characters 65 characters 65 characters 65 charac");
     }
 
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize_charbuf() {
+        use serde::de::value::Error;
+
+        let mut charbuf = [0u8; 8];
+
+        assert!(deserialize_charbuf::<Error>(&mut charbuf, "friend").is_ok());
+        assert_eq!(charbuf_to_str(&charbuf), "friend");
+
+        assert!(deserialize_charbuf::<Error>(&mut charbuf, "9 chars..").is_err());
+        assert!(deserialize_charbuf::<Error>(&mut charbuf, "nul\0").is_err());
+    }
+
     fn run_test(val: &str) {
         let mut charbuf = [0u8; 64];
 
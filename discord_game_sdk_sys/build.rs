@@ -1,4 +1,8 @@
 fn main() {
+    if std::env::var_os("CARGO_FEATURE_LINK").is_none() {
+        return;
+    }
+
     println!("cargo:rustc-link-lib=discord_game_sdk");
     println!(
         "cargo:rustc-link-search={}/sdk/lib/x86_64",